@@ -3,5 +3,7 @@ pub mod mapbox;
 pub trait ImageProvider {
     fn name(&self) -> &str;
     // Returns a formatted URL to retrieve a color map.
+    // Tiles are addressed in the Web Mercator tiling scheme, with XYZ rows
+    // (see `WebMercatorTilingScheme` in `telluris-tiler`).
     fn get_color_map(&self, x: u32, y: u32, z: u32) -> String;
 }
@@ -0,0 +1,182 @@
+//! Typed angles.
+//! Geographic types store their angles in degrees, whereas trigonometric
+//! functions operate on radians. Wrapping values in `Degrees` and `Radians`
+//! makes the unit explicit, and conversions between them visible.
+
+use std::f64::consts::PI;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An angle expressed in degrees.
+#[derive(Debug, Default, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Degrees(pub f64);
+
+/// An angle expressed in radians.
+#[derive(Debug, Default, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Radians(pub f64);
+
+impl Degrees {
+    /// Returns the raw value of this angle, in degrees.
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Returns this angle converted to radians.
+    pub fn to_radians(self) -> Radians {
+        Radians(self.0.to_radians())
+    }
+}
+
+impl Radians {
+    /// Returns the raw value of this angle, in radians.
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Returns this angle converted to degrees.
+    pub fn to_degrees(self) -> Degrees {
+        Degrees(self.0.to_degrees())
+    }
+
+    /// Returns the sine of this angle.
+    pub fn sin(self) -> f64 {
+        self.0.sin()
+    }
+
+    /// Returns the cosine of this angle.
+    pub fn cos(self) -> f64 {
+        self.0.cos()
+    }
+
+    /// Returns the tangent of this angle.
+    pub fn tan(self) -> f64 {
+        self.0.tan()
+    }
+
+    /// Returns the sine and cosine of this angle.
+    pub fn sin_cos(self) -> (f64, f64) {
+        self.0.sin_cos()
+    }
+
+    /// Returns the angle whose sine is `x`. The argument is clamped
+    /// in the [-1, 1] range to absorb floating point errors.
+    pub fn asin(x: f64) -> Self {
+        Radians(num::clamp(x, -1.0, 1.0).asin())
+    }
+
+    /// Returns the angle whose cosine is `x`. The argument is clamped
+    /// in the [-1, 1] range to absorb floating point errors.
+    pub fn acos(x: f64) -> Self {
+        Radians(num::clamp(x, -1.0, 1.0).acos())
+    }
+
+    /// Returns the angle whose tangent is `x`.
+    pub fn atan(x: f64) -> Self {
+        Radians(x.atan())
+    }
+
+    /// Returns the four quadrant arctangent of `y` and `x`, in the [-π, π] range.
+    pub fn atan2(y: f64, x: f64) -> Self {
+        Radians(y.atan2(x))
+    }
+
+    /// Returns this angle wrapped in the [-π, π] range.
+    pub fn normalize(self) -> Self {
+        let wrapped = (self.0 + PI) % (2.0 * PI);
+        if wrapped < 0.0 {
+            Radians(wrapped + PI)
+        } else {
+            Radians(wrapped - PI)
+        }
+    }
+}
+
+impl From<Degrees> for Radians {
+    fn from(d: Degrees) -> Self {
+        d.to_radians()
+    }
+}
+
+impl From<Radians> for Degrees {
+    fn from(r: Radians) -> Self {
+        r.to_degrees()
+    }
+}
+
+macro_rules! impl_ops {
+    ($t:ident) => {
+        impl Add for $t {
+            type Output = $t;
+
+            fn add(self, other: $t) -> $t {
+                $t(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $t {
+            type Output = $t;
+
+            fn sub(self, other: $t) -> $t {
+                $t(self.0 - other.0)
+            }
+        }
+
+        impl Neg for $t {
+            type Output = $t;
+
+            fn neg(self) -> $t {
+                $t(-self.0)
+            }
+        }
+    };
+}
+
+impl_ops!(Degrees);
+impl_ops!(Radians);
+
+impl fmt::Display for Degrees {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}°", self.0)
+    }
+}
+
+impl fmt::Display for Radians {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} rad", self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::angle::*;
+
+    #[quickcheck]
+    fn degrees_to_radians_and_back_returns_the_same_value(d: f64) -> bool {
+        abs_diff_eq!(
+            Degrees(d).to_radians().to_degrees().value(),
+            d,
+            epsilon = 1e-9
+        )
+    }
+
+    #[test]
+    fn to_radians_returns_correct_values() {
+        assert_abs_diff_eq!(Degrees(180.0).to_radians().value(), PI);
+        assert_abs_diff_eq!(Degrees(-90.0).to_radians().value(), -PI / 2.0);
+    }
+
+    #[quickcheck]
+    fn normalize_returns_an_equivalent_angle_in_range(r: f64) -> bool {
+        let n = Radians(r).normalize();
+
+        n.value() >= -PI
+            && n.value() <= PI
+            && abs_diff_eq!(n.sin(), r.sin(), epsilon = 1e-9)
+            && abs_diff_eq!(n.cos(), r.cos(), epsilon = 1e-9)
+    }
+}
@@ -0,0 +1,401 @@
+//! View frustum culling.
+//! Volumes are tested against the six planes of the frustum. When testing a hierarchy
+//! of nested volumes, such as a quadtree of tiles, the planes that a parent volume
+//! lies entirely inside of do not need to be tested again for its children.
+//! This is tracked with a `PlaneMask`.
+
+use crate::angle::Degrees;
+use crate::volumes::obb::OrientedBoundingBox;
+use crate::volumes::sphere::BoundingSphere;
+use glm::{DMat4, DVec3, DVec4};
+
+/// The result of an intersection test between a volume and a plane or frustum.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Intersection {
+    /// The volume is entirely inside.
+    Inside,
+    /// The volume is entirely outside.
+    Outside,
+    /// The volume is partially inside.
+    Intersecting,
+}
+
+/// A plane defined by the equation `normal · p + distance = 0`.
+/// The normal is a unit vector that points towards the inside half-space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Plane {
+    normal: DVec3,
+    distance: f64,
+}
+
+impl Plane {
+    /// Creates a plane with the specified normal and distance to the origin.
+    /// The normal is normalized.
+    pub fn new(normal: DVec3, distance: f64) -> Self {
+        let length = normal.norm();
+
+        Plane {
+            normal: normal / length,
+            distance: distance / length,
+        }
+    }
+
+    /// Creates a plane from the coefficients `(a, b, c, d)` of its equation
+    /// `a * x + b * y + c * z + d = 0`.
+    pub fn from_coefficients(v: DVec4) -> Self {
+        Self::new(v.xyz(), v.w)
+    }
+
+    /// Creates the plane going through the point, with the specified normal.
+    pub fn from_point_normal(p: &DVec3, normal: &DVec3) -> Self {
+        let normal = normal.normalize();
+
+        Plane {
+            normal,
+            distance: -normal.dot(p),
+        }
+    }
+
+    /// Returns the unit normal of the plane.
+    pub fn normal(&self) -> DVec3 {
+        self.normal
+    }
+
+    /// Returns the signed distance from the origin to the plane.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// Returns the signed distance of the point to the plane, positive on the inside.
+    pub fn signed_distance(&self, p: &DVec3) -> f64 {
+        self.normal.dot(p) + self.distance
+    }
+}
+
+/// A volume that can be tested against a plane.
+pub trait Cullable {
+    /// Returns the position of this volume relative to the inside half-space of the plane.
+    fn intersect_plane(&self, plane: &Plane) -> Intersection;
+}
+
+/// Points are never intersecting: points on the plane are considered inside.
+impl Cullable for DVec3 {
+    fn intersect_plane(&self, plane: &Plane) -> Intersection {
+        if plane.signed_distance(self) < 0.0 {
+            Intersection::Outside
+        } else {
+            Intersection::Inside
+        }
+    }
+}
+
+impl Cullable for BoundingSphere {
+    fn intersect_plane(&self, plane: &Plane) -> Intersection {
+        classify(plane.signed_distance(&self.center()), self.radius())
+    }
+}
+
+impl Cullable for OrientedBoundingBox {
+    fn intersect_plane(&self, plane: &Plane) -> Intersection {
+        // The radius of the box, projected on the normal of the plane.
+        let axes = self.half_axes();
+        let n = plane.normal();
+        let radius = (0..3).map(|i| axes.column(i).dot(&n).abs()).sum();
+
+        classify(plane.signed_distance(&self.center()), radius)
+    }
+}
+
+fn classify(distance: f64, radius: f64) -> Intersection {
+    if distance < -radius {
+        Intersection::Outside
+    } else if distance > radius {
+        Intersection::Inside
+    } else {
+        Intersection::Intersecting
+    }
+}
+
+/// A set of frustum planes that must be tested. Each bit corresponds to the plane
+/// at the same index in `Frustum::planes`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PlaneMask(u8);
+
+impl PlaneMask {
+    /// All planes must be tested. This is the mask of root volumes.
+    pub const ALL: PlaneMask = PlaneMask(0b11_1111);
+
+    /// No plane must be tested: the volume is entirely inside the frustum.
+    pub const NONE: PlaneMask = PlaneMask(0);
+
+    /// Returns true if the plane at the given index must be tested.
+    pub fn contains(self, index: usize) -> bool {
+        self.0 & (1 << index) != 0
+    }
+
+    /// Returns true if no plane must be tested.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// A truncated pyramid that bounds the region visible from a camera.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes of the frustum from a view-projection matrix, such that
+    /// visible points lie in the `[-w, w]` range in clip space, for all axes
+    /// (the OpenGL convention used by `glm::perspective`).
+    pub fn from_matrix(view_projection: &DMat4) -> Self {
+        // Implementation taken from "Fast extraction of viewing frustum planes
+        // from the world-view-projection matrix", by G. Gribb and K. Hartmann.
+
+        let m = view_projection;
+        let row = |i: usize| -> DVec4 { m.row(i).transpose() };
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+        Frustum {
+            planes: [
+                Plane::from_coefficients(w + x),
+                Plane::from_coefficients(w - x),
+                Plane::from_coefficients(w + y),
+                Plane::from_coefficients(w - y),
+                Plane::from_coefficients(w + z),
+                Plane::from_coefficients(w - z),
+            ],
+        }
+    }
+
+    /// Creates the frustum of a perspective camera located at `position`, looking
+    /// in the `direction`. `fov_y` is the vertical field of view, in degrees, and
+    /// `aspect` is the ratio of the width to the height of the view.
+    pub fn from_camera(
+        position: &DVec3,
+        direction: &DVec3,
+        up: &DVec3,
+        fov_y: f64,
+        aspect: f64,
+        near: f64,
+        far: f64,
+    ) -> Self {
+        debug_assert!(near > 0.0 && near < far);
+
+        let d = direction.normalize();
+        let right = d.cross(up).normalize();
+        let up = right.cross(&d);
+
+        let tan_v = Degrees(fov_y / 2.0).to_radians().tan();
+        let tan_h = tan_v * aspect;
+
+        let side = |normal: DVec3| Plane::from_point_normal(position, &normal);
+
+        Frustum {
+            planes: [
+                side(right + d * tan_h),
+                side(-right + d * tan_h),
+                side(up + d * tan_v),
+                side(-up + d * tan_v),
+                Plane::from_point_normal(&(position + d * near), &d),
+                Plane::from_point_normal(&(position + d * far), &-d),
+            ],
+        }
+    }
+
+    /// Returns the planes of the frustum, in order: left, right, bottom, top, near and far.
+    /// Their normals point towards the inside of the frustum.
+    pub fn planes(&self) -> &[Plane; 6] {
+        &self.planes
+    }
+
+    /// Returns the position of the volume relative to the frustum.
+    pub fn intersect<V: Cullable + ?Sized>(&self, volume: &V) -> Intersection {
+        self.intersect_with_mask(volume, PlaneMask::ALL).0
+    }
+
+    /// Returns the position of the volume relative to the frustum, testing only the planes
+    /// in the mask of the parent volume, which is expected to enclose this volume.
+    /// Also returns the mask of the planes that the volume intersects, to be used with
+    /// the children of this volume. If the volume is outside, the parent mask is returned.
+    pub fn intersect_with_mask<V: Cullable + ?Sized>(
+        &self,
+        volume: &V,
+        parent: PlaneMask,
+    ) -> (Intersection, PlaneMask) {
+        let mut mask = PlaneMask::NONE;
+
+        for (i, plane) in self.planes.iter().enumerate() {
+            if !parent.contains(i) {
+                continue;
+            }
+
+            match volume.intersect_plane(plane) {
+                Intersection::Outside => return (Intersection::Outside, parent),
+                Intersection::Intersecting => mask.0 |= 1 << i,
+                Intersection::Inside => {}
+            }
+        }
+
+        if mask.is_empty() {
+            (Intersection::Inside, mask)
+        } else {
+            (Intersection::Intersecting, mask)
+        }
+    }
+
+    /// Returns true if the point is inside the frustum, including its boundary.
+    pub fn contains(&self, p: &DVec3) -> bool {
+        self.intersect(p) == Intersection::Inside
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::culling::frustum::*;
+    use glm::DMat3;
+    use rand::Rng;
+
+    /// A camera at the origin, looking towards negative `Z`, with `Y` up.
+    fn frustum() -> Frustum {
+        Frustum::from_camera(
+            &DVec3::zeros(),
+            &DVec3::new(0.0, 0.0, -1.0),
+            &DVec3::new(0.0, 1.0, 0.0),
+            90.0,
+            2.0,
+            1.0,
+            100.0,
+        )
+    }
+
+    fn random_sphere(extent: f64) -> BoundingSphere {
+        let mut rng = rand::thread_rng();
+        let center = DVec3::new(
+            rng.gen_range(-extent, extent),
+            rng.gen_range(-extent, extent),
+            rng.gen_range(-extent, extent),
+        );
+
+        BoundingSphere::new(center, rng.gen_range(0.0, extent / 4.0))
+    }
+
+    #[test]
+    fn contains_returns_correct_values() {
+        let f = frustum();
+
+        assert!(f.contains(&DVec3::new(0.0, 0.0, -10.0)));
+        assert!(f.contains(&DVec3::new(19.0, 9.0, -10.0)));
+        assert!(!f.contains(&DVec3::new(21.0, 0.0, -10.0)));
+        assert!(!f.contains(&DVec3::new(0.0, 11.0, -10.0)));
+        assert!(!f.contains(&DVec3::new(0.0, 0.0, 10.0)));
+        assert!(!f.contains(&DVec3::new(0.0, 0.0, -0.5)));
+        assert!(!f.contains(&DVec3::new(0.0, 0.0, -101.0)));
+    }
+
+    #[test]
+    fn intersect_sphere_returns_correct_values() {
+        let f = frustum();
+        let sphere = |x, z, r| BoundingSphere::new(DVec3::new(x, 0.0, z), r);
+
+        assert_eq!(f.intersect(&sphere(0.0, -50.0, 1.0)), Intersection::Inside);
+        assert_eq!(
+            f.intersect(&sphere(0.0, -100.0, 1.0)),
+            Intersection::Intersecting
+        );
+        assert_eq!(f.intersect(&sphere(0.0, 50.0, 1.0)), Intersection::Outside);
+    }
+
+    #[test]
+    fn intersect_obb_returns_correct_values() {
+        let f = frustum();
+        let obb = |x, z| {
+            OrientedBoundingBox::new(
+                DVec3::new(x, 0.0, z),
+                DMat3::identity(),
+                DVec3::new(1.0, 1.0, 1.0),
+            )
+        };
+
+        assert_eq!(f.intersect(&obb(0.0, -10.0)), Intersection::Inside);
+        assert_eq!(f.intersect(&obb(20.0, -10.0)), Intersection::Intersecting);
+        assert_eq!(f.intersect(&obb(30.0, -10.0)), Intersection::Outside);
+    }
+
+    #[test]
+    fn from_matrix_is_consistent_with_from_camera() {
+        let eye = DVec3::new(6_378_137.0, 1000.0, -3000.0);
+        let target = DVec3::new(0.0, 0.0, 0.0);
+        let up = DVec3::new(0.0, 0.0, 1.0);
+
+        let view = glm::look_at(&eye, &target, &up);
+        let projection = glm::perspective(1.5, Degrees(60.0).to_radians().value(), 10.0, 1e7);
+
+        let from_matrix = Frustum::from_matrix(&(projection * view));
+        let from_camera = Frustum::from_camera(&eye, &(target - eye), &up, 60.0, 1.5, 10.0, 1e7);
+
+        for (a, b) in from_matrix.planes().iter().zip(from_camera.planes().iter()) {
+            assert_abs_diff_eq!(a.normal(), b.normal(), epsilon = 1e-6);
+            assert_abs_diff_eq!(a.distance(), b.distance(), epsilon = 1e-6 * eye.norm());
+        }
+    }
+
+    #[quickcheck]
+    fn spheres_intersecting_any_plane_are_not_inside() -> bool {
+        let f = frustum();
+        let sphere = random_sphere(120.0);
+
+        let intersects_a_plane = f
+            .planes()
+            .iter()
+            .any(|p| p.signed_distance(&sphere.center()).abs() <= sphere.radius());
+
+        match f.intersect(&sphere) {
+            Intersection::Inside => !intersects_a_plane,
+            Intersection::Outside => true,
+            Intersection::Intersecting => intersects_a_plane,
+        }
+    }
+
+    #[quickcheck]
+    fn masked_tests_match_unmasked_tests_for_nested_volumes() -> bool {
+        let f = frustum();
+        let parent = random_sphere(120.0);
+
+        // A child sphere enclosed in the parent.
+        let mut rng = rand::thread_rng();
+        let offset = DVec3::new(
+            rng.gen_range(-1.0, 1.0),
+            rng.gen_range(-1.0, 1.0),
+            rng.gen_range(-1.0, 1.0),
+        ) * parent.radius()
+            / 2.0;
+        let child = BoundingSphere::new(parent.center() + offset / 2.0, parent.radius() / 8.0);
+
+        let (parent_result, mask) = f.intersect_with_mask(&parent, PlaneMask::ALL);
+        let (child_result, _) = f.intersect_with_mask(&child, mask);
+
+        match parent_result {
+            Intersection::Outside => f.intersect(&child) == Intersection::Outside,
+            _ => child_result == f.intersect(&child),
+        }
+    }
+
+    #[test]
+    fn children_of_inside_volumes_are_not_tested() {
+        let f = frustum();
+        let (result, mask) = f.intersect_with_mask(
+            &BoundingSphere::new(DVec3::new(0.0, 0.0, -50.0), 1.0),
+            PlaneMask::ALL,
+        );
+
+        assert_eq!(result, Intersection::Inside);
+        assert_eq!(mask, PlaneMask::NONE);
+
+        // Even a volume outside the frustum is reported inside, as it is expected
+        // to be enclosed by its parent.
+        let child = BoundingSphere::new(DVec3::new(0.0, 0.0, 500.0), 1.0);
+        assert_eq!(f.intersect_with_mask(&child, mask).0, Intersection::Inside);
+    }
+}
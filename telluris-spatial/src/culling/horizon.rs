@@ -0,0 +1,314 @@
+//! Horizon culling against the ellipsoid.
+//! Computations are done in the scaled space of the ellipsoid (see `Ellipsoid::to_scaled_space`),
+//! where the ellipsoid becomes the unit sphere. From a camera outside the sphere, the hidden
+//! region is the cone tangent to the sphere, beyond the plane of the horizon.
+//! A set of positions, such as the vertices of a tile, can be summarized by a single
+//! occludee point that is hidden only if all positions are hidden. This point does not
+//! depend on the camera, so it can be computed once per tile.
+
+use crate::ellipsoid::Ellipsoid;
+use crate::geobounds::GeoBounds;
+use crate::transformations::ecef::ECEF;
+use crate::volumes::sample_geo_bounds;
+use crate::volumes::sphere::BoundingSphere;
+use glm::DVec3;
+
+/// Determines whether points and volumes are hidden behind the ellipsoid,
+/// as seen from a camera position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HorizonOccluder {
+    ellipsoid: Ellipsoid,
+    camera_position: DVec3,
+    scaled_camera_position: DVec3,
+    // The squared distance from the camera to the horizon, in scaled space.
+    distance_to_limb_squared: f64,
+}
+
+impl HorizonOccluder {
+    /// Creates an occluder for the ellipsoid, seen from the camera position.
+    pub fn new(ellipsoid: Ellipsoid, camera_position: DVec3) -> Self {
+        let mut occluder = HorizonOccluder {
+            ellipsoid,
+            camera_position,
+            scaled_camera_position: DVec3::zeros(),
+            distance_to_limb_squared: 0.0,
+        };
+        occluder.set_camera_position(camera_position);
+
+        occluder
+    }
+
+    /// Returns the occluding ellipsoid.
+    pub fn ellipsoid(&self) -> &Ellipsoid {
+        &self.ellipsoid
+    }
+
+    /// Returns the position of the camera.
+    pub fn camera_position(&self) -> DVec3 {
+        self.camera_position
+    }
+
+    /// Moves the camera to the specified position.
+    pub fn set_camera_position(&mut self, camera_position: DVec3) {
+        let cv = self.ellipsoid.to_scaled_space(camera_position);
+
+        self.camera_position = camera_position;
+        self.scaled_camera_position = cv;
+        self.distance_to_limb_squared = cv.norm_squared() - 1.0;
+    }
+
+    /// Returns true if the point is not hidden behind the ellipsoid.
+    pub fn is_point_visible(&self, p: &DVec3) -> bool {
+        self.is_scaled_space_point_visible(&self.ellipsoid.to_scaled_space(*p))
+    }
+
+    /// Returns true if the point, expressed in scaled space, is not hidden behind the ellipsoid.
+    /// This is the test to use with occludee points.
+    pub fn is_scaled_space_point_visible(&self, p: &DVec3) -> bool {
+        let cv = self.scaled_camera_position;
+        let vh_magnitude_squared = self.distance_to_limb_squared;
+        let vt = p - cv;
+        let vt_dot_vc = -vt.dot(&cv);
+
+        let occluded = if vh_magnitude_squared < 0.0 {
+            // The camera is below the surface: the horizon plane goes through the camera.
+            vt_dot_vc > 0.0
+        } else {
+            vt_dot_vc > vh_magnitude_squared
+                && vt_dot_vc * vt_dot_vc / vt.norm_squared() > vh_magnitude_squared
+        };
+
+        !occluded
+    }
+
+    /// Returns true if any part of the sphere may be visible. The test is conservative:
+    /// it may report as visible a sphere that is barely hidden.
+    pub fn is_sphere_visible(&self, sphere: &BoundingSphere) -> bool {
+        // The sphere becomes an ellipsoid in scaled space, which is enclosed by
+        // a sphere whose radius is scaled by the smallest radius of the ellipsoid.
+        let center = self.ellipsoid.to_scaled_space(sphere.center());
+        let radius = sphere.radius() / self.ellipsoid.semi_minor_axis();
+
+        let cv = self.scaled_camera_position;
+        let distance = cv.norm();
+        let axis = -cv / distance;
+        let vt = center - cv;
+
+        if self.distance_to_limb_squared < 0.0 {
+            return vt.dot(&axis) <= radius;
+        }
+
+        // The sphere must be entirely beyond the plane of the horizon.
+        let horizon_plane_distance = 1.0 / distance;
+        if center.dot(&-axis) + radius >= horizon_plane_distance {
+            return true;
+        }
+
+        // And entirely inside the cone tangent to the unit sphere.
+        let vt_length = vt.norm();
+        if vt_length <= radius {
+            return true;
+        }
+
+        let cone_half_angle = (1.0 / distance).asin();
+        let angle_to_center = num::clamp(vt.dot(&axis) / vt_length, -1.0, 1.0).acos();
+        let apparent_radius = (radius / vt_length).asin();
+
+        angle_to_center + apparent_radius > cone_half_angle
+    }
+}
+
+/// Returns the point, in scaled space, that is hidden by the ellipsoid only if all positions
+/// are hidden. The point lies along the specified direction, usually the direction of
+/// the center of the positions. Positions below the surface are considered to lie on it.
+/// Returns `None` if no such point exists, for example if the positions span more than
+/// a hemisphere, or if the direction is opposite to some positions.
+pub fn occludee_point(
+    ellipsoid: &Ellipsoid,
+    direction: &DVec3,
+    positions: &[DVec3],
+) -> Option<DVec3> {
+    // Implementation taken from the blog post "Horizon culling 2", by Kevin Ring.
+
+    if direction.norm_squared() == 0.0 {
+        return None;
+    }
+
+    let scaled_direction = ellipsoid.to_scaled_space(*direction).normalize();
+
+    let mut magnitude = 0.0f64;
+    for p in positions {
+        let candidate = occludee_magnitude(ellipsoid, p, &scaled_direction);
+        if candidate < 0.0 {
+            return None;
+        }
+        magnitude = magnitude.max(candidate);
+    }
+
+    if magnitude <= 0.0 || !magnitude.is_finite() {
+        return None;
+    }
+
+    Some(scaled_direction * magnitude)
+}
+
+/// Returns the distance from the center, along the direction, of the point
+/// that is hidden only if the position is hidden.
+fn occludee_magnitude(ellipsoid: &Ellipsoid, p: &DVec3, scaled_direction: &DVec3) -> f64 {
+    let scaled = ellipsoid.to_scaled_space(*p);
+    let magnitude = scaled.norm();
+    let direction = scaled / magnitude;
+
+    let magnitude = magnitude.max(1.0);
+    let cos_alpha = direction.dot(scaled_direction);
+    let sin_alpha = direction.cross(scaled_direction).norm();
+    let cos_beta = 1.0 / magnitude;
+    let sin_beta = (magnitude * magnitude - 1.0).sqrt() * cos_beta;
+
+    1.0 / (cos_alpha * cos_beta - sin_alpha * sin_beta)
+}
+
+/// Returns the occludee point, in scaled space, of the volume covered by the bounds,
+/// from their `floor` to their `top` altitudes. See `occludee_point`.
+pub fn occludee_point_from_geo_bounds(ecef: &ECEF, bounds: &GeoBounds) -> Option<DVec3> {
+    let (positions, margin) = sample_geo_bounds(ecef, bounds);
+
+    // Raise the samples to account for the curvature of the surface between them.
+    let positions: Vec<DVec3> = positions
+        .iter()
+        .map(|p| p * (1.0 + margin / p.norm()))
+        .collect();
+
+    let direction = BoundingSphere::from_points(&positions).center();
+
+    occludee_point(ecef.ellipsoid(), &direction, &positions)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::culling::horizon::*;
+    use crate::geographic::*;
+    use crate::transformations::SpatialReference;
+    use crate::volumes::with_altitudes;
+    use rand::Rng;
+
+    fn occluder_above(geo: Geographic) -> HorizonOccluder {
+        HorizonOccluder::new(Ellipsoid::wgs84(), ECEF::default().convert_f64(geo))
+    }
+
+    #[test]
+    fn is_point_visible_returns_correct_values() {
+        let ecef = ECEF::default();
+        let occluder = occluder_above(Geographic::new(0.0, 0.0, 1_000_000.0));
+        let visible = |lat, lon, alt| {
+            occluder.is_point_visible(&ecef.convert_f64(Geographic::new(lat, lon, alt)))
+        };
+
+        assert!(visible(0.0, 0.0, 0.0));
+        assert!(visible(0.0, 20.0, 0.0));
+        assert!(!visible(0.0, 40.0, 0.0));
+        assert!(!visible(0.0, 180.0, 0.0));
+        assert!(!visible(-90.0, 0.0, 0.0));
+
+        // Points high enough are seen above the horizon.
+        assert!(visible(0.0, 40.0, 1_000_000.0));
+    }
+
+    #[test]
+    fn everything_beyond_the_camera_is_hidden_below_the_surface() {
+        let ecef = ECEF::default();
+        let occluder = occluder_above(Geographic::new(0.0, 0.0, -100.0));
+
+        assert!(occluder.is_point_visible(&ecef.convert_f64(Geographic::new(0.0, 0.0, 10.0))));
+        assert!(!occluder.is_point_visible(&DVec3::zeros()));
+    }
+
+    #[test]
+    fn far_side_tiles_are_hidden() {
+        let ecef = ECEF::default();
+        let occluder = occluder_above(Geographic::new(45.0, 5.0, 10_000.0));
+        let tile = |lat: f64, lon: f64| {
+            GeoBounds::new(
+                Geographic::new(lat, lon, 0.0),
+                Geographic::new(lat + 1.0, lon + 1.0, 4000.0),
+            )
+        };
+
+        let near = occludee_point_from_geo_bounds(&ecef, &tile(45.0, 5.0)).unwrap();
+        let far = occludee_point_from_geo_bounds(&ecef, &tile(-45.0, -175.0)).unwrap();
+
+        assert!(occluder.is_scaled_space_point_visible(&near));
+        assert!(!occluder.is_scaled_space_point_visible(&far));
+    }
+
+    #[test]
+    fn occludee_point_of_the_whole_world_does_not_exist() {
+        let ecef = ECEF::default();
+
+        assert_eq!(
+            occludee_point_from_geo_bounds(&ecef, &GeoBounds::surface()),
+            None
+        );
+    }
+
+    #[quickcheck]
+    fn hidden_occludee_point_implies_hidden_bounds(camera: Geographic, b: GeoBounds) -> bool {
+        let ecef = ECEF::default();
+        let b = with_altitudes(b);
+        let occluder = occluder_above(camera);
+
+        let point = match occludee_point_from_geo_bounds(&ecef, &b) {
+            Some(p) => p,
+            None => return true,
+        };
+
+        if occluder.is_scaled_space_point_visible(&point) {
+            return true;
+        }
+
+        let mut rng = rand::thread_rng();
+        (0..100).all(|_| {
+            let p = b.sample(
+                rng.gen_range(0.0, 1.0),
+                rng.gen_range(0.0, 1.0),
+                rng.gen_range(0.0, 1.0),
+            );
+            !occluder.is_point_visible(&ecef.convert_f64(p))
+        })
+    }
+
+    #[quickcheck]
+    fn hidden_sphere_implies_hidden_points(
+        camera: Geographic,
+        center: Geographic,
+        radius: u32,
+    ) -> bool {
+        let ecef = ECEF::default();
+        let occluder = occluder_above(camera);
+        let sphere = BoundingSphere::new(ecef.convert_f64(center), f64::from(radius % 1_000_000));
+
+        if occluder.is_sphere_visible(&sphere) {
+            return true;
+        }
+
+        let mut rng = rand::thread_rng();
+        (0..100).all(|_| {
+            let direction = DVec3::new(
+                rng.gen_range(-1.0, 1.0),
+                rng.gen_range(-1.0, 1.0),
+                rng.gen_range(-1.0, 1.0),
+            );
+            let offset = direction.normalize() * sphere.radius() * rng.gen_range(0.0, 1.0);
+            !occluder.is_point_visible(&(sphere.center() + offset))
+        })
+    }
+
+    #[quickcheck]
+    fn visible_points_are_in_visible_spheres(camera: Geographic, p: Geographic) -> bool {
+        let ecef = ECEF::default();
+        let occluder = occluder_above(camera);
+        let p = ecef.convert_f64(p);
+
+        !occluder.is_point_visible(&p) || occluder.is_sphere_visible(&BoundingSphere::new(p, 0.0))
+    }
+}
@@ -0,0 +1,4 @@
+//! Visibility tests, used to discard tiles that cannot be seen from the camera.
+
+pub mod frustum;
+pub mod horizon;
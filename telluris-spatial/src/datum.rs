@@ -0,0 +1,875 @@
+//! Geodetic datums, to convert coordinates of legacy surveys (such as ED50, NAD27
+//! or OSGB36) to and from WGS 84 before rendering.
+//! A datum pairs a reference ellipsoid with the transformation to WGS 84: a 7-parameter
+//! Helmert transformation of earth-centered, earth-fixed positions, and optionally an
+//! NTv2 grid shift, which is much more accurate inside the extent of its grid.
+//! Grid shifts transform geographic coordinates to a datum that is assumed to be
+//! identical to WGS 84, such as NAD83 or ETRS89, which holds to about a meter.
+
+use crate::ellipsoid::Ellipsoid;
+use crate::geographic::*;
+use crate::geoid::node_count;
+use glm::{DMat3, DVec3};
+use std::convert::TryInto;
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+/// The number of radians in an arc-second.
+const ARC_SECOND: f64 = PI / (180.0 * 3600.0);
+
+/// The convergence threshold of the inverse grid shift, in degrees.
+const GRID_EPSILON: f64 = 1e-12;
+
+/// The maximal number of iterations of the inverse grid shift.
+const GRID_MAX_ITERATIONS: usize = 10;
+
+/// The number of iterations of the change of height of the inverse grid shift.
+const HEIGHT_ITERATIONS: usize = 3;
+
+/// A 7-parameter Helmert transformation of earth-centered, earth-fixed positions,
+/// made of a translation, small rotations around the axes and a scale change.
+/// Rotations follow the position vector convention (EPSG method 9606), where positive
+/// rotations are counterclockwise when looking at the origin from the positive axis.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Helmert {
+    translation: DVec3,
+    rotation: DVec3,
+    scale: f64,
+}
+
+impl Helmert {
+    /// Creates a transformation from the translation in meters, the rotations in
+    /// arc-seconds with the position vector convention, and the scale change in
+    /// parts per million. The scale change must be greater than -10⁶ ppm, so that
+    /// the transformation can be inverted.
+    pub fn new(translation: DVec3, rotation: DVec3, scale: f64) -> Self {
+        assert!(translation
+            .iter()
+            .chain(rotation.iter())
+            .all(|v| v.is_finite()));
+        assert!(scale.is_finite() && scale > -1e6);
+
+        Helmert {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Creates a transformation whose rotations, in arc-seconds, follow the coordinate
+    /// frame convention (EPSG method 9607), as published for most European datums.
+    pub fn coordinate_frame(translation: DVec3, rotation: DVec3, scale: f64) -> Self {
+        Self::new(translation, -rotation, scale)
+    }
+
+    /// Creates a transformation that leaves positions unchanged.
+    pub fn identity() -> Self {
+        Self::translation(DVec3::zeros())
+    }
+
+    /// Creates a transformation that is a translation, in meters.
+    pub fn translation(translation: DVec3) -> Self {
+        Self::new(translation, DVec3::zeros(), 0.0)
+    }
+
+    /// Returns the translation, in meters.
+    pub fn translation_vector(&self) -> DVec3 {
+        self.translation
+    }
+
+    /// Returns the rotations around the `X`, `Y` and `Z` axes, in arc-seconds,
+    /// with the position vector convention.
+    pub fn rotation(&self) -> DVec3 {
+        self.rotation
+    }
+
+    /// Returns the scale change, in parts per million.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Returns the rotations, in radians, and the skew-symmetric matrix of the rotations.
+    fn skew(&self) -> (DVec3, DMat3) {
+        let r = self.rotation * ARC_SECOND;
+
+        #[rustfmt::skip]
+        let skew = DMat3::new(
+            0.0, -r.z, r.y,
+            r.z, 0.0, -r.x,
+            -r.y, r.x, 0.0,
+        );
+
+        (r, skew)
+    }
+
+    /// Returns the matrix that rotates and scales positions.
+    fn matrix(&self) -> DMat3 {
+        let (_, skew) = self.skew();
+
+        (DMat3::identity() + skew) * (1.0 + self.scale * 1e-6)
+    }
+
+    /// Transforms the position, in meters.
+    pub fn forward(&self, p: DVec3) -> DVec3 {
+        self.translation + self.matrix() * p
+    }
+
+    /// Applies the exact inverse of `forward` to the position, in meters.
+    pub fn inverse(&self, p: DVec3) -> DVec3 {
+        // The inverse of I + S is (I - S + r rᵀ) / (1 + r·r), as S r = 0 and
+        // S² = r rᵀ - (r·r) I. It exists for all rotations.
+        let (r, skew) = self.skew();
+        let inverse = (DMat3::identity() - skew + r * r.transpose())
+            / ((1.0 + r.dot(&r)) * (1.0 + self.scale * 1e-6));
+
+        inverse * (p - self.translation)
+    }
+}
+
+impl Default for Helmert {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// The error returned when reading a grid shift file fails.
+#[derive(Debug)]
+pub enum GridShiftError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file ends in the middle of a record or of a grid.
+    UnexpectedEnd,
+    /// A header record is missing or has an invalid value.
+    InvalidRecord(String),
+}
+
+impl fmt::Display for GridShiftError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridShiftError::Io(e) => write!(f, "cannot read the grid shift file: {}", e),
+            GridShiftError::UnexpectedEnd => write!(f, "unexpected end of the grid shift file"),
+            GridShiftError::InvalidRecord(r) => write!(f, "invalid grid shift record: '{}'", r),
+        }
+    }
+}
+
+impl Error for GridShiftError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GridShiftError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GridShiftError {
+    fn from(e: io::Error) -> Self {
+        GridShiftError::Io(e)
+    }
+}
+
+/// A grid of an NTv2 file. Angles are in arc-seconds, and longitudes are positive
+/// westward, as in the file.
+#[derive(Debug, Clone, PartialEq)]
+struct Subgrid {
+    south: f64,
+    north: f64,
+    east: f64,
+    west: f64,
+    lat_spacing: f64,
+    lon_spacing: f64,
+    rows: usize,
+    columns: usize,
+    // Latitude and longitude shifts, in arc-seconds, row by row from south to north,
+    // then from east to west.
+    shifts: Vec<(f32, f32)>,
+}
+
+impl Subgrid {
+    fn contains(&self, lat: f64, lon: f64) -> bool {
+        (self.south..=self.north).contains(&lat) && (self.east..=self.west).contains(&lon)
+    }
+
+    /// Returns the latitude and longitude shifts at the position, in arc-seconds,
+    /// bilinearly interpolated between the nodes of the grid.
+    fn shift(&self, lat: f64, lon: f64) -> (f64, f64) {
+        let y = (lat - self.south) / self.lat_spacing;
+        let x = (lon - self.east) / self.lon_spacing;
+
+        let row = (y.floor() as usize).min(self.rows - 2);
+        let column = (x.floor() as usize).min(self.columns - 2);
+        let v = y - row as f64;
+        let u = x - column as f64;
+
+        let node = |r: usize, c: usize| {
+            let (lat, lon) = self.shifts[r * self.columns + c];
+            (f64::from(lat), f64::from(lon))
+        };
+        let lerp =
+            |a: (f64, f64), b: (f64, f64), t: f64| (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+
+        let south = lerp(node(row, column), node(row, column + 1), u);
+        let north = lerp(node(row + 1, column), node(row + 1, column + 1), u);
+
+        lerp(south, north, v)
+    }
+}
+
+/// A grid shift in the NTv2 format, made of one or more grids of latitude and longitude
+/// shifts. Nested grids refine their parent: the densest grid that contains a position
+/// is used. Elevations are not changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridShift {
+    subgrids: Vec<Subgrid>,
+}
+
+/// Reads the 16-byte records of an NTv2 file: an 8-byte name and an 8-byte value.
+struct Records<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    big_endian: bool,
+}
+
+impl<'a> Records<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], GridShiftError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + count)
+            .ok_or(GridShiftError::UnexpectedEnd)?;
+        self.offset += count;
+
+        Ok(bytes)
+    }
+
+    /// Reads `count` records, as pairs of trimmed names and values.
+    fn header(&mut self, count: usize) -> Result<Vec<(String, [u8; 8])>, GridShiftError> {
+        (0..count)
+            .map(|_| {
+                let record = self.take(16)?;
+                let name = String::from_utf8_lossy(&record[..8])
+                    .trim_end_matches(&[' ', '\0'][..])
+                    .to_string();
+
+                Ok((name, record[8..].try_into().unwrap()))
+            })
+            .collect()
+    }
+
+    /// Returns the integer of a record value, stored in its first 4 bytes.
+    fn int(&self, value: [u8; 8]) -> usize {
+        let bytes = value[..4].try_into().unwrap();
+        let n = if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        };
+
+        n as usize
+    }
+
+    fn float(&self, value: [u8; 8]) -> f64 {
+        if self.big_endian {
+            f64::from_be_bytes(value)
+        } else {
+            f64::from_le_bytes(value)
+        }
+    }
+
+    fn f32(&mut self) -> Result<f32, GridShiftError> {
+        let bytes = self.take(4)?.try_into().unwrap();
+
+        Ok(if self.big_endian {
+            f32::from_be_bytes(bytes)
+        } else {
+            f32::from_le_bytes(bytes)
+        })
+    }
+}
+
+/// Returns the value of the record with the specified name.
+fn record(header: &[(String, [u8; 8])], name: &str) -> Result<[u8; 8], GridShiftError> {
+    header
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| *v)
+        .ok_or_else(|| GridShiftError::InvalidRecord(name.to_string()))
+}
+
+impl GridShift {
+    /// Reads a grid shift in the binary NTv2 format, such as the `OSTN15_NTv2_OSGBtoETRS.gsb`
+    /// file of the Ordnance Survey. Both byte orders are supported. Shifts must be
+    /// expressed in arc-seconds.
+    pub fn from_ntv2<R: Read>(mut reader: R) -> Result<Self, GridShiftError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        // The first record holds the number of records of the overview header,
+        // which is always 11, and reveals the byte order.
+        let first: [u8; 4] = bytes
+            .get(8..12)
+            .ok_or(GridShiftError::UnexpectedEnd)?
+            .try_into()
+            .unwrap();
+        let big_endian = match (u32::from_le_bytes(first), u32::from_be_bytes(first)) {
+            (11, _) => false,
+            (_, 11) => true,
+            _ => return Err(GridShiftError::InvalidRecord("NUM_OREC".to_string())),
+        };
+
+        let mut records = Records {
+            bytes: &bytes,
+            offset: 0,
+            big_endian,
+        };
+
+        let overview = records.header(11)?;
+        let subfile_records = records.int(record(&overview, "NUM_SREC")?);
+        let count = records.int(record(&overview, "NUM_FILE")?);
+        if &record(&overview, "GS_TYPE")? != b"SECONDS " {
+            return Err(GridShiftError::InvalidRecord("GS_TYPE".to_string()));
+        }
+
+        let mut subgrids = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let header = records.header(subfile_records)?;
+            let value = |name| record(&header, name).map(|v| records.float(v));
+            let (south, north) = (value("S_LAT")?, value("N_LAT")?);
+            let (east, west) = (value("E_LONG")?, value("W_LONG")?);
+            let (lat_spacing, lon_spacing) = (value("LAT_INC")?, value("LONG_INC")?);
+
+            let invalid = |name: &str| GridShiftError::InvalidRecord(name.to_string());
+            if !(lat_spacing > 0.0 && north > south) {
+                return Err(invalid("LAT_INC"));
+            }
+            if !(lon_spacing > 0.0 && west > east) {
+                return Err(invalid("LONG_INC"));
+            }
+
+            let rows = node_count(north - south, lat_spacing).ok_or_else(|| invalid("LAT_INC"))?;
+            let columns =
+                node_count(west - east, lon_spacing).ok_or_else(|| invalid("LONG_INC"))?;
+            let nodes = records.int(record(&header, "GS_COUNT")?);
+            if Some(nodes) != rows.checked_mul(columns) {
+                return Err(invalid("GS_COUNT"));
+            }
+            // Each node is made of the latitude and longitude shifts and their accuracies.
+            if bytes.len() < records.offset + nodes * 16 {
+                return Err(GridShiftError::UnexpectedEnd);
+            }
+
+            let shifts = (0..nodes)
+                .map(|_| {
+                    let shift = (records.f32()?, records.f32()?);
+                    records.take(8)?;
+                    Ok(shift)
+                })
+                .collect::<Result<_, GridShiftError>>()?;
+
+            subgrids.push(Subgrid {
+                south,
+                north,
+                east,
+                west,
+                lat_spacing,
+                lon_spacing,
+                rows,
+                columns,
+                shifts,
+            });
+        }
+
+        Ok(GridShift { subgrids })
+    }
+
+    /// Reads a grid shift from a file in the NTv2 format. See `from_ntv2`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, GridShiftError> {
+        Self::from_ntv2(File::open(path)?)
+    }
+
+    /// Returns the shift in degrees of the latitude and of the (eastward) longitude at
+    /// the coordinate, or `None` if the coordinate is outside of the grids.
+    fn shift(&self, geo: &Geographic) -> Option<(f64, f64)> {
+        let lat = geo.lat() * 3600.0;
+        let lon = -geo.lon() * 3600.0;
+
+        self.subgrids
+            .iter()
+            .filter(|g| g.contains(lat, lon))
+            .min_by(|a, b| {
+                let area = |g: &Subgrid| g.lat_spacing * g.lon_spacing;
+                area(a).partial_cmp(&area(b)).unwrap()
+            })
+            .map(|g| {
+                let (lat, lon) = g.shift(lat, lon);
+                (lat / 3600.0, -lon / 3600.0)
+            })
+    }
+
+    /// Shifts the coordinate, or returns `None` if it is outside of the grids.
+    pub fn forward(&self, geo: Geographic) -> Option<Geographic> {
+        self.shift(&geo).map(|(lat, lon)| shifted(geo, lat, lon))
+    }
+
+    /// Applies the inverse of `forward` to the coordinate, or returns `None` if it is
+    /// outside of the grids. The inverse is found iteratively.
+    pub fn inverse(&self, geo: Geographic) -> Option<Geographic> {
+        let mut result = self.forward(geo).map(|_| geo)?;
+
+        for _ in 0..GRID_MAX_ITERATIONS {
+            // Outside of the grids, keep the last shift.
+            let (lat, lon) = match self.shift(&result) {
+                Some(shift) => shift,
+                None => break,
+            };
+            let next = shifted(geo, -lat, -lon);
+            let converged = (next.lat() - result.lat()).abs() < GRID_EPSILON
+                && (next.lon() - result.lon()).abs() < GRID_EPSILON;
+            result = next;
+            if converged {
+                break;
+            }
+        }
+
+        Some(result)
+    }
+}
+
+/// Returns the coordinate moved by the specified angles, in degrees.
+fn shifted(geo: Geographic, lat: f64, lon: f64) -> Geographic {
+    let lon = geo.lon() + lon;
+    let lon = if (MIN_LON..=MAX_LON).contains(&lon) {
+        lon
+    } else {
+        (lon + 180.0).rem_euclid(360.0) - 180.0
+    };
+
+    Geographic::new(
+        num::clamp(geo.lat() + lat, MIN_LAT, MAX_LAT),
+        lon,
+        geo.elevation(),
+    )
+}
+
+/// A geodetic datum: a reference ellipsoid and the transformation of its coordinates
+/// to WGS 84.
+#[derive(Debug, Clone)]
+pub struct Datum {
+    ellipsoid: Ellipsoid,
+    to_wgs84: Helmert,
+    grid: Option<Arc<GridShift>>,
+}
+
+impl Datum {
+    /// Creates a datum on the provided ellipsoid, with the transformation of its
+    /// earth-centered, earth-fixed positions to WGS 84.
+    pub fn new(ellipsoid: Ellipsoid, to_wgs84: Helmert) -> Self {
+        Datum {
+            ellipsoid,
+            to_wgs84,
+            grid: None,
+        }
+    }
+
+    /// The World Geodetic System 1984.
+    pub fn wgs84() -> Self {
+        Self::new(Ellipsoid::wgs84(), Helmert::identity())
+    }
+
+    /// The European Datum 1950, with the mean transformation for western Europe
+    /// (EPSG:1133), accurate to about 5 meters.
+    pub fn ed50() -> Self {
+        Self::new(
+            Ellipsoid::international1924(),
+            Helmert::translation(DVec3::new(-87.0, -98.0, -121.0)),
+        )
+    }
+
+    /// The North American Datum 1927, with the mean transformation for the contiguous
+    /// United States (EPSG:1173), accurate to about 10 meters. NADCON or NTv2 grids are
+    /// much more accurate.
+    pub fn nad27() -> Self {
+        Self::new(
+            Ellipsoid::clarke1866(),
+            Helmert::translation(DVec3::new(-8.0, 160.0, 176.0)),
+        )
+    }
+
+    /// The datum of the Ordnance Survey of Great Britain 1936, with the transformation
+    /// of the Ordnance Survey (EPSG:1314), accurate to about 2 meters. The OSTN15 grid
+    /// is much more accurate.
+    pub fn osgb36() -> Self {
+        Self::new(
+            Ellipsoid::airy1830(),
+            Helmert::new(
+                DVec3::new(446.448, -125.157, 542.06),
+                DVec3::new(0.15, 0.247, 0.842),
+                -20.489,
+            ),
+        )
+    }
+
+    /// Returns this datum with a grid shift to WGS 84, used instead of the Helmert
+    /// transformation inside the extent of the grid. Grids only shift latitudes and
+    /// longitudes: heights still change as with the Helmert transformation, so that
+    /// they are continuous at the edges of the grid.
+    pub fn with_grid(self, grid: Arc<GridShift>) -> Self {
+        Datum {
+            grid: Some(grid),
+            ..self
+        }
+    }
+
+    /// Returns the ellipsoid of this datum.
+    pub fn ellipsoid(&self) -> &Ellipsoid {
+        &self.ellipsoid
+    }
+
+    /// Returns the Helmert transformation of this datum to WGS 84.
+    pub fn helmert(&self) -> &Helmert {
+        &self.to_wgs84
+    }
+
+    /// Returns the grid shift of this datum to WGS 84, if any.
+    pub fn grid(&self) -> Option<&GridShift> {
+        self.grid.as_deref()
+    }
+
+    /// Converts a coordinate of this datum to WGS 84.
+    /// Elevations are clamped in the domain specified by `MIN_ALT` and `MAX_ALT`.
+    pub fn to_wgs84(&self, geo: Geographic) -> Geographic {
+        if let Some(result) = self.grid().and_then(|g| g.forward(geo)) {
+            let elevation = geo.elevation() + self.height_change(geo);
+            return Geographic::new(
+                result.lat(),
+                result.lon(),
+                num::clamp(elevation, MIN_ALT, MAX_ALT),
+            );
+        }
+
+        let p = self.to_wgs84.forward(self.ellipsoid.to_cartesian(geo));
+        Ellipsoid::wgs84().to_geographic(p)
+    }
+
+    /// Converts a WGS 84 coordinate to this datum.
+    /// Elevations are clamped in the domain specified by `MIN_ALT` and `MAX_ALT`.
+    pub fn from_wgs84(&self, geo: Geographic) -> Geographic {
+        if let Some(result) = self.grid().and_then(|g| g.inverse(geo)) {
+            // The change of height depends slightly on the height itself.
+            let mut elevation = geo.elevation();
+            for _ in 0..HEIGHT_ITERATIONS {
+                let h = num::clamp(elevation, MIN_ALT, MAX_ALT);
+                let change = self.height_change(Geographic::new(result.lat(), result.lon(), h));
+                elevation = geo.elevation() - change;
+            }
+            return Geographic::new(
+                result.lat(),
+                result.lon(),
+                num::clamp(elevation, MIN_ALT, MAX_ALT),
+            );
+        }
+
+        let p = self.to_wgs84.inverse(Ellipsoid::wgs84().to_cartesian(geo));
+        self.ellipsoid.to_geographic(p)
+    }
+
+    /// Returns the change of height of the coordinate of this datum by the Helmert
+    /// transformation, in meters.
+    fn height_change(&self, geo: Geographic) -> f64 {
+        let p = self.to_wgs84.forward(self.ellipsoid.to_cartesian(geo));
+        Ellipsoid::wgs84().to_geographic(p).elevation() - geo.elevation()
+    }
+
+    /// Converts a coordinate of this datum to the target datum, through WGS 84.
+    pub fn convert(&self, geo: Geographic, target: &Datum) -> Geographic {
+        target.from_wgs84(self.to_wgs84(geo))
+    }
+}
+
+impl Default for Datum {
+    fn default() -> Self {
+        Self::wgs84()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::datum::*;
+
+    /// Returns true if the two coordinates are within 1e-9 degrees and 1 millimeter.
+    fn close(a: Geographic, b: Geographic) -> bool {
+        (a.lat() - b.lat()).abs() < 1e-9
+            && (a.lon() - b.lon()).abs() < 1e-9
+            && (a.elevation() - b.elevation()).abs() < 1e-3
+    }
+
+    /// Writes the records and values of an NTv2 file in either byte order.
+    struct Writer {
+        bytes: Vec<u8>,
+        big_endian: bool,
+    }
+
+    impl Writer {
+        fn name(&mut self, name: &str) {
+            self.bytes.extend(format!("{:<8}", name).as_bytes());
+        }
+
+        fn text(&mut self, name: &str, value: &str) {
+            self.name(name);
+            self.name(value);
+        }
+
+        fn int(&mut self, name: &str, value: u32) {
+            self.name(name);
+            let bytes = if self.big_endian {
+                value.to_be_bytes()
+            } else {
+                value.to_le_bytes()
+            };
+            self.bytes.extend(&bytes);
+            self.bytes.extend(&[0; 4]);
+        }
+
+        fn float(&mut self, name: &str, value: f64) {
+            self.name(name);
+            let bytes = if self.big_endian {
+                value.to_be_bytes()
+            } else {
+                value.to_le_bytes()
+            };
+            self.bytes.extend(&bytes);
+        }
+
+        fn f32(&mut self, value: f32) {
+            let bytes = if self.big_endian {
+                value.to_be_bytes()
+            } else {
+                value.to_le_bytes()
+            };
+            self.bytes.extend(&bytes);
+        }
+    }
+
+    /// Returns the bytes of an NTv2 file with a single grid covering [0°N, 1°N]
+    /// and [0°E, 1°E] with a spacing of 0.5°. Latitude shifts are `row + column / 2`
+    /// arc-seconds and longitude shifts are one arc-second westward.
+    fn ntv2(big_endian: bool) -> Vec<u8> {
+        let mut w = Writer {
+            bytes: Vec::new(),
+            big_endian,
+        };
+
+        w.int("NUM_OREC", 11);
+        w.int("NUM_SREC", 11);
+        w.int("NUM_FILE", 1);
+        w.text("GS_TYPE", "SECONDS");
+        w.text("VERSION", "TEST");
+        w.text("SYSTEM_F", "TEST");
+        w.text("SYSTEM_T", "TEST");
+        for name in &["MAJOR_F", "MINOR_F", "MAJOR_T", "MINOR_T"] {
+            w.float(name, 6_378_137.0);
+        }
+
+        for name in &["SUB_NAME", "PARENT", "CREATED", "UPDATED"] {
+            w.text(name, "TEST");
+        }
+        w.float("S_LAT", 0.0);
+        w.float("N_LAT", 3600.0);
+        w.float("E_LONG", -3600.0);
+        w.float("W_LONG", 0.0);
+        w.float("LAT_INC", 1800.0);
+        w.float("LONG_INC", 1800.0);
+        w.int("GS_COUNT", 9);
+
+        for row in 0..3 {
+            // Nodes go from east to west.
+            for column in (0..3).rev() {
+                w.f32(row as f32 + column as f32 / 2.0);
+                w.f32(1.0);
+                w.f32(0.0);
+                w.f32(0.0);
+            }
+        }
+        w.text("END", "");
+
+        w.bytes
+    }
+
+    #[test]
+    fn helmert_inverse_is_the_inverse_of_forward() {
+        let helmert = *Datum::osgb36().helmert();
+        let p = DVec3::new(3_874_938.85, 116_218.62, 5_047_168.21);
+
+        let result = helmert.inverse(helmert.forward(p));
+        assert!((result - p).norm() < 1e-6);
+    }
+
+    #[quickcheck]
+    fn helmert_inverse_is_the_inverse_of_large_rotations(rotation: (i32, i32, i32)) -> bool {
+        let (x, y, z) = rotation;
+        let helmert = Helmert::new(
+            DVec3::new(100.0, -200.0, 300.0),
+            DVec3::new(f64::from(x), f64::from(y), f64::from(z)),
+            -500.0,
+        );
+        let p = DVec3::new(3_874_938.85, 116_218.62, 5_047_168.21);
+
+        (helmert.inverse(helmert.forward(p)) - p).norm() < 1e-6
+    }
+
+    #[test]
+    fn helmert_coordinate_frame_negates_rotations() {
+        let t = DVec3::new(1.0, 2.0, 3.0);
+        let r = DVec3::new(0.1, 0.2, 0.3);
+
+        assert_eq!(
+            Helmert::coordinate_frame(t, r, 1.0),
+            Helmert::new(t, -r, 1.0)
+        );
+    }
+
+    #[test]
+    fn to_wgs84_returns_correct_values() {
+        let osgb36 = Datum::osgb36().to_wgs84(Geographic::new(52.658007833, 1.716073972, 0.0));
+        let ed50 = Datum::ed50().to_wgs84(Geographic::new(48.0, 2.0, 100.0));
+        let nad27 = Datum::nad27().to_wgs84(Geographic::new(40.0, -100.0, 0.0));
+
+        assert_abs_diff_eq!(osgb36.lat(), 52.658416045, epsilon = 1e-8);
+        assert_abs_diff_eq!(osgb36.lon(), 1.714204570, epsilon = 1e-8);
+        assert_abs_diff_eq!(osgb36.elevation(), 44.7073, epsilon = 1e-3);
+        assert_abs_diff_eq!(ed50.lat(), 47.999058315, epsilon = 1e-8);
+        assert_abs_diff_eq!(ed50.lon(), 1.998728313, epsilon = 1e-8);
+        assert_abs_diff_eq!(ed50.elevation(), 150.23, epsilon = 1e-3);
+        assert_abs_diff_eq!(nad27.lat(), 40.000009483, epsilon = 1e-8);
+        assert_abs_diff_eq!(nad27.lon(), -100.000417622, epsilon = 1e-8);
+        assert_abs_diff_eq!(nad27.elevation(), -35.2158, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn wgs84_is_the_identity() {
+        let geo = Geographic::new(12.0, -34.0, 56.0);
+
+        assert!(close(Datum::wgs84().to_wgs84(geo), geo));
+        assert!(close(Datum::wgs84().from_wgs84(geo), geo));
+    }
+
+    #[test]
+    fn from_ntv2_reads_both_byte_orders() {
+        let little = GridShift::from_ntv2(ntv2(false).as_slice()).unwrap();
+        let big = GridShift::from_ntv2(ntv2(true).as_slice()).unwrap();
+
+        assert_eq!(little, big);
+    }
+
+    #[test]
+    fn from_ntv2_returns_errors_for_invalid_files() {
+        let bytes = ntv2(false);
+        let truncated = GridShift::from_ntv2(&bytes[..bytes.len() - 40]);
+        let mut invalid = bytes.clone();
+        invalid[8] = 12;
+
+        match truncated {
+            Err(GridShiftError::UnexpectedEnd) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match GridShift::from_ntv2(invalid.as_slice()) {
+            Err(GridShiftError::InvalidRecord(r)) => assert_eq!(r, "NUM_OREC"),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        // Subgrids whose node counts overflow.
+        let mut huge = bytes.clone();
+        let mut set = |name: &str, value: f64| {
+            let name = format!("{:<8}", name);
+            let offset = huge.windows(8).position(|w| w == name.as_bytes()).unwrap() + 8;
+            huge[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        };
+        set("N_LAT", 1e300);
+        set("LAT_INC", 1e-300);
+        match GridShift::from_ntv2(huge.as_slice()) {
+            Err(GridShiftError::InvalidRecord(r)) => assert_eq!(r, "LAT_INC"),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match GridShift::open("/nonexistent/grid.gsb") {
+            Err(GridShiftError::Io(_)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn grid_shift_is_interpolated_between_nodes() {
+        let grid = GridShift::from_ntv2(ntv2(false).as_slice()).unwrap();
+        let geo = Geographic::new(0.25, 0.75, 10.0);
+
+        let expected = Geographic::new(0.25 + 1.25 / 3600.0, 0.75 - 1.0 / 3600.0, 10.0);
+        assert!(close(grid.forward(geo).unwrap(), expected));
+        assert!(close(grid.inverse(expected).unwrap(), geo));
+        assert_eq!(grid.forward(Geographic::new(2.0, 0.5, 0.0)), None);
+    }
+
+    #[test]
+    fn datums_use_their_grid_inside_its_extent() {
+        let grid = Arc::new(GridShift::from_ntv2(ntv2(false).as_slice()).unwrap());
+        let datum = Datum::ed50().with_grid(grid);
+        let inside = Geographic::new(0.5, 0.5, 0.0);
+        let outside = Geographic::new(48.0, 2.0, 100.0);
+
+        let elevation = Datum::ed50().to_wgs84(inside).elevation();
+        assert!(close(
+            datum.to_wgs84(inside),
+            Geographic::new(0.5 + 1.5 / 3600.0, 0.5 - 1.0 / 3600.0, elevation)
+        ));
+        assert!(close(
+            datum.to_wgs84(outside),
+            Datum::ed50().to_wgs84(outside)
+        ));
+        assert!(close(datum.from_wgs84(datum.to_wgs84(inside)), inside));
+    }
+
+    #[test]
+    fn datums_with_a_grid_have_continuous_heights_at_its_edges() {
+        let grid = Arc::new(GridShift::from_ntv2(ntv2(false).as_slice()).unwrap());
+        let datum = Datum::osgb36().with_grid(grid);
+        let inside = Geographic::new(0.5, 1.0 - 1e-9, 100.0);
+        let outside = Geographic::new(0.5, 1.0 + 1e-9, 100.0);
+
+        assert!(datum.grid().unwrap().forward(inside).is_some());
+        assert!(datum.grid().unwrap().forward(outside).is_none());
+        assert_abs_diff_eq!(
+            datum.to_wgs84(inside).elevation(),
+            datum.to_wgs84(outside).elevation(),
+            epsilon = 1e-3
+        );
+
+        let wgs84 = datum.to_wgs84(inside);
+        assert_abs_diff_eq!(
+            datum.from_wgs84(wgs84).elevation(),
+            inside.elevation(),
+            epsilon = 1e-6
+        );
+    }
+
+    #[quickcheck]
+    fn from_wgs84_is_the_inverse_of_to_wgs84(geo: Geographic) -> bool {
+        let datum = Datum::osgb36();
+        let result = datum.from_wgs84(datum.to_wgs84(geo));
+        let ellipsoid = datum.ellipsoid();
+
+        // Elevations near `MIN_ALT` may be clamped.
+        geo.elevation() < MIN_ALT + 1000.0
+            || (ellipsoid.to_cartesian(result) - ellipsoid.to_cartesian(geo)).norm() < 1e-3
+    }
+
+    #[quickcheck]
+    fn convert_between_datums_is_reversible(geo: Geographic) -> bool {
+        let (ed50, nad27) = (Datum::ed50(), Datum::nad27());
+        let result = nad27.convert(ed50.convert(geo, &nad27), &ed50);
+        let ellipsoid = ed50.ellipsoid();
+
+        geo.elevation() < MIN_ALT + 1000.0
+            || (ellipsoid.to_cartesian(result) - ellipsoid.to_cartesian(geo)).norm() < 1e-3
+    }
+}
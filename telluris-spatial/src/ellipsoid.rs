@@ -0,0 +1,514 @@
+//! Reference ellipsoids.
+
+use crate::angle::Radians;
+use crate::geographic::*;
+use glm::DVec3;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// The length of the semi major axis, in meters, in the WGS 84 system.
+pub const WGS84_SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+
+/// The length of the semi minor axis, in meters, in the WGS 84 system.
+pub const WGS84_SEMI_MINOR_AXIS: f64 = 6_356_752.314_245;
+
+/// The inverse flattening of the WGS 84 ellipsoid.
+pub const WGS84_INVERSE_FLATTENING: f64 = 298.257_223_563;
+
+/// The length of the semi major axis, in meters, in the GRS 80 system.
+pub const GRS80_SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+
+/// The inverse flattening of the GRS 80 ellipsoid.
+pub const GRS80_INVERSE_FLATTENING: f64 = 298.257_222_101;
+
+/// The convergence threshold of the iterative geodetic surface projection.
+const SURFACE_EPSILON: f64 = 1e-12;
+
+/// The maximal number of Newton iterations of the geodetic surface projection.
+const SURFACE_MAX_ITERATIONS: usize = 64;
+
+/// The maximal number of iterations when converting conformal latitudes.
+const CONFORMAL_MAX_ITERATIONS: usize = 20;
+
+/// An ellipsoid of revolution, flattened along its polar (`Z`) axis.
+/// The equatorial radius is the semi major axis, and the polar radius is
+/// the semi minor axis. A sphere is an ellipsoid whose axes are equal.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Ellipsoid {
+    semi_major: f64,
+    semi_minor: f64,
+}
+
+impl Ellipsoid {
+    /// Creates an ellipsoid with the provided equatorial and polar radii, in meters.
+    pub fn new(semi_major: f64, semi_minor: f64) -> Self {
+        assert!(semi_minor > 0.0);
+        assert!(semi_major >= semi_minor);
+
+        Ellipsoid {
+            semi_major,
+            semi_minor,
+        }
+    }
+
+    /// Creates an ellipsoid from its equatorial radius and inverse flattening.
+    pub fn from_inverse_flattening(semi_major: f64, inverse_flattening: f64) -> Self {
+        Self::new(semi_major, semi_major * (1.0 - 1.0 / inverse_flattening))
+    }
+
+    /// Creates a sphere of the provided radius, in meters.
+    pub fn sphere(radius: f64) -> Self {
+        Self::new(radius, radius)
+    }
+
+    /// The World Geodetic System 1984 ellipsoid, used by GPS.
+    pub fn wgs84() -> Self {
+        Self::new(WGS84_SEMI_MAJOR_AXIS, WGS84_SEMI_MINOR_AXIS)
+    }
+
+    /// The Geodetic Reference System 1980 ellipsoid.
+    pub fn grs80() -> Self {
+        Self::from_inverse_flattening(GRS80_SEMI_MAJOR_AXIS, GRS80_INVERSE_FLATTENING)
+    }
+
+    /// The International 1924 (Hayford) ellipsoid, used by ED50.
+    pub fn international1924() -> Self {
+        Self::from_inverse_flattening(6_378_388.0, 297.0)
+    }
+
+    /// The Clarke 1866 ellipsoid, used by NAD27.
+    pub fn clarke1866() -> Self {
+        Self::new(6_378_206.4, 6_356_583.8)
+    }
+
+    /// The Airy 1830 ellipsoid, used by OSGB36.
+    pub fn airy1830() -> Self {
+        Self::new(6_377_563.396, 6_356_256.909)
+    }
+
+    /// A sphere of radius one, useful for test scenes.
+    pub fn unit_sphere() -> Self {
+        Self::sphere(1.0)
+    }
+
+    /// The lunar sphere, as defined by the IAU.
+    pub fn moon() -> Self {
+        Self::sphere(1_737_400.0)
+    }
+
+    /// The Mars ellipsoid, as defined by the IAU.
+    pub fn mars() -> Self {
+        Self::new(3_396_190.0, 3_376_200.0)
+    }
+
+    /// Returns the equatorial radius, in meters.
+    pub fn semi_major_axis(&self) -> f64 {
+        self.semi_major
+    }
+
+    /// Returns the polar radius, in meters.
+    pub fn semi_minor_axis(&self) -> f64 {
+        self.semi_minor
+    }
+
+    /// Returns the radii along the `X`, `Y` and `Z` axes.
+    pub fn radii(&self) -> DVec3 {
+        DVec3::new(self.semi_major, self.semi_major, self.semi_minor)
+    }
+
+    /// Returns the squared radii along the `X`, `Y` and `Z` axes.
+    pub fn radii_squared(&self) -> DVec3 {
+        let r = self.radii();
+        r.component_mul(&r)
+    }
+
+    /// Returns the inverse of the squared radii along the `X`, `Y` and `Z` axes.
+    pub fn one_over_radii_squared(&self) -> DVec3 {
+        let r2 = self.radii_squared();
+        DVec3::new(1.0 / r2.x, 1.0 / r2.y, 1.0 / r2.z)
+    }
+
+    /// Divides the position by the radii. In this scaled space, the ellipsoid
+    /// becomes the unit sphere.
+    pub fn to_scaled_space(&self, p: DVec3) -> DVec3 {
+        p.component_div(&self.radii())
+    }
+
+    /// Returns the flattening, that is `(a - b) / a`.
+    pub fn flattening(&self) -> f64 {
+        (self.semi_major - self.semi_minor) / self.semi_major
+    }
+
+    /// Returns the square of the first eccentricity, that is `(a² - b²) / a²`.
+    pub fn eccentricity_squared(&self) -> f64 {
+        let f = self.flattening();
+        f * (2.0 - f)
+    }
+
+    /// Returns the first eccentricity.
+    pub fn eccentricity(&self) -> f64 {
+        self.eccentricity_squared().sqrt()
+    }
+
+    /// Returns the square of the second eccentricity, that is `(a² - b²) / b²`.
+    pub fn second_eccentricity_squared(&self) -> f64 {
+        let a2 = self.semi_major * self.semi_major;
+        let b2 = self.semi_minor * self.semi_minor;
+        (a2 - b2) / b2
+    }
+
+    /// Returns true if the ellipsoid is a sphere.
+    pub fn is_sphere(&self) -> bool {
+        self.semi_major == self.semi_minor
+    }
+
+    /// Returns the outward unit vector orthogonal to the surface
+    /// at the given geographic coordinate.
+    pub fn geodetic_surface_normal(&self, geo: Geographic) -> DVec3 {
+        let lat = geo.lat_rad();
+        let lon = geo.lon_rad();
+        let cos_lat = lat.cos();
+
+        DVec3::new(cos_lat * lon.cos(), cos_lat * lon.sin(), lat.sin())
+    }
+
+    /// Returns the outward unit vector orthogonal to the surface at the
+    /// given cartesian position, which is expected to lie on the surface.
+    pub fn geodetic_surface_normal_at(&self, p: DVec3) -> DVec3 {
+        p.component_mul(&self.one_over_radii_squared()).normalize()
+    }
+
+    /// Converts geographic coordinates into cartesian coordinates,
+    /// in the frame centered on the ellipsoid.
+    pub fn to_cartesian(&self, geo: Geographic) -> DVec3 {
+        // Implementation taken from the book "3D Engine design
+        // for virtual globes", by Patrick Cozzi and Kevin Ring.
+
+        let n = self.geodetic_surface_normal(geo);
+        let k = self.radii_squared().component_mul(&n);
+        let gamma = k.dot(&n).sqrt();
+        let surface = k / gamma;
+
+        surface + (geo.elevation() * n)
+    }
+
+    /// Converts cartesian coordinates back into geographic coordinates.
+    /// Elevations are clamped in the domain specified by `MIN_ALT` and `MAX_ALT`.
+    pub fn to_geographic(&self, p: DVec3) -> Geographic {
+        let surface = match self.scale_to_geodetic_surface(p) {
+            Some(s) => s,
+            None => return Geographic::new(0.0, 0.0, MIN_ALT),
+        };
+
+        let n = self.geodetic_surface_normal_at(surface);
+
+        let h = p - surface;
+        let elevation = h.dot(&p).signum() * h.norm();
+
+        let lat = Radians::asin(n.z);
+        let lon = Radians::atan2(n.y, n.x);
+
+        Geographic::from_radians(lat, lon, num::clamp(elevation, MIN_ALT, MAX_ALT))
+    }
+
+    /// Projects the cartesian position along the geodetic surface normal
+    /// onto the surface of the ellipsoid, or returns `None` for the center
+    /// of the ellipsoid.
+    pub fn scale_to_geodetic_surface(&self, p: DVec3) -> Option<DVec3> {
+        // Implementation taken from the book "3D Engine design
+        // for virtual globes", by Patrick Cozzi and Kevin Ring.
+
+        let r2 = self.radii_squared();
+        let r4 = r2.component_mul(&r2);
+        let inv_r2 = self.one_over_radii_squared();
+        let p2 = p.component_mul(&p);
+
+        let beta = 1.0 / p2.dot(&inv_r2).sqrt();
+        if !beta.is_finite() {
+            return None;
+        }
+        let n = (beta * p.component_mul(&inv_r2)).norm();
+        let mut alpha = (1.0 - beta) * (p.norm() / n);
+
+        let mut s = 0.0;
+        let mut ds_da = 1.0;
+        let mut d = DVec3::new(1.0, 1.0, 1.0);
+
+        for _ in 0..SURFACE_MAX_ITERATIONS {
+            alpha -= s / ds_da;
+
+            d = DVec3::new(1.0, 1.0, 1.0) + alpha * inv_r2;
+            let d2 = d.component_mul(&d);
+            let d3 = d2.component_mul(&d);
+
+            s = p2.x / (r2.x * d2.x) + p2.y / (r2.y * d2.y) + p2.z / (r2.z * d2.z) - 1.0;
+            ds_da = -2.0 * (p2.x / (r4.x * d3.x) + p2.y / (r4.y * d3.y) + p2.z / (r4.z * d3.z));
+
+            if s.abs() < SURFACE_EPSILON {
+                break;
+            }
+        }
+
+        Some(DVec3::new(p.x / d.x, p.y / d.y, p.z / d.z))
+    }
+
+    /// Projects the cartesian position along the line to the center
+    /// onto the surface of the ellipsoid, or returns `None` for the center
+    /// of the ellipsoid.
+    pub fn scale_to_geocentric_surface(&self, p: DVec3) -> Option<DVec3> {
+        let beta = 1.0
+            / p.component_mul(&p)
+                .dot(&self.one_over_radii_squared())
+                .sqrt();
+        if !beta.is_finite() {
+            return None;
+        }
+
+        Some(beta * p)
+    }
+
+    /// Returns the conformal latitude of the geodetic latitude, that is the latitude
+    /// on the sphere of a conformal mapping of the ellipsoid.
+    pub fn conformal_latitude(&self, lat: Radians) -> Radians {
+        let e = self.eccentricity();
+        let esin = e * lat.sin();
+        let t = (FRAC_PI_4 + lat.value() / 2.0).tan() * ((1.0 - esin) / (1.0 + esin)).powf(e / 2.0);
+
+        Radians(2.0 * t.atan() - FRAC_PI_2)
+    }
+
+    /// Returns the geodetic latitude of the conformal latitude.
+    /// This is the inverse of `conformal_latitude`.
+    pub fn geodetic_latitude(&self, conformal: Radians) -> Radians {
+        let e = self.eccentricity();
+        let t = (FRAC_PI_4 + conformal.value() / 2.0).tan();
+
+        // Fixed point iteration, see "Map Projections - A Working Manual", by J. P. Snyder.
+        let mut lat = conformal.value();
+        for _ in 0..CONFORMAL_MAX_ITERATIONS {
+            let esin = e * lat.sin();
+            let next = 2.0 * (t * ((1.0 + esin) / (1.0 - esin)).powf(e / 2.0)).atan() - FRAC_PI_2;
+            let delta = (next - lat).abs();
+            lat = next;
+            if delta < 1e-15 {
+                break;
+            }
+        }
+
+        Radians(lat)
+    }
+
+    /// Returns the radius of the authalic sphere, that is the sphere with
+    /// the same surface area as the ellipsoid.
+    pub fn authalic_radius(&self) -> f64 {
+        let a = self.semi_major_axis();
+        if self.is_sphere() {
+            return a;
+        }
+
+        (a * a * self.authalic_q(FRAC_PI_2) / 2.0).sqrt()
+    }
+
+    /// Returns the authalic latitude of the geodetic latitude, that is the latitude
+    /// on the authalic sphere of an equal-area mapping of the ellipsoid.
+    pub fn authalic_latitude(&self, lat: Radians) -> Radians {
+        if self.is_sphere() {
+            return lat;
+        }
+
+        let ratio = self.authalic_q(lat.value()) / self.authalic_q(FRAC_PI_2);
+
+        Radians::asin(num::clamp(ratio, -1.0, 1.0))
+    }
+
+    /// Returns the `q` function of the authalic latitude, see "Map Projections -
+    /// A Working Manual", by J. P. Snyder.
+    fn authalic_q(&self, lat: f64) -> f64 {
+        let e = self.eccentricity();
+        let e2 = self.eccentricity_squared();
+        let sin = lat.sin();
+
+        (1.0 - e2)
+            * (sin / (1.0 - e2 * sin * sin) - ((1.0 - e * sin) / (1.0 + e * sin)).ln() / (2.0 * e))
+    }
+}
+
+impl Default for Ellipsoid {
+    fn default() -> Self {
+        Self::wgs84()
+    }
+}
+
+/// Deserializes an ellipsoid, whose polar radius must be positive and not greater
+/// than its equatorial radius.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Ellipsoid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Ellipsoid", deny_unknown_fields)]
+        struct Fields {
+            semi_major: f64,
+            semi_minor: f64,
+        }
+
+        let f = Fields::deserialize(deserializer)?;
+        if !(f.semi_minor > 0.0 && f.semi_major.is_finite() && f.semi_major >= f.semi_minor) {
+            return Err(de::Error::custom(format_args!(
+                "invalid radii: {} and {}",
+                f.semi_major, f.semi_minor
+            )));
+        }
+
+        Ok(Ellipsoid::new(f.semi_major, f.semi_minor))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::angle::Degrees;
+    use crate::ellipsoid::*;
+    use glm::DVec3;
+
+    fn presets() -> Vec<Ellipsoid> {
+        vec![
+            Ellipsoid::wgs84(),
+            Ellipsoid::grs80(),
+            Ellipsoid::moon(),
+            Ellipsoid::mars(),
+        ]
+    }
+
+    #[test]
+    fn wgs84_has_correct_parameters() {
+        let e = Ellipsoid::wgs84();
+
+        assert_abs_diff_eq!(
+            1.0 / e.flattening(),
+            WGS84_INVERSE_FLATTENING,
+            epsilon = 1e-6
+        );
+        assert_abs_diff_eq!(
+            e.eccentricity_squared(),
+            0.006_694_379_990_14,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            e.second_eccentricity_squared(),
+            0.006_739_496_742_28,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn grs80_has_correct_semi_minor_axis() {
+        assert_abs_diff_eq!(
+            Ellipsoid::grs80().semi_minor_axis(),
+            6_356_752.314_140,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn spheres_have_no_flattening() {
+        for e in &[Ellipsoid::unit_sphere(), Ellipsoid::moon()] {
+            assert!(e.is_sphere());
+            assert_eq!(e.flattening(), 0.0);
+            assert_eq!(e.eccentricity(), 0.0);
+        }
+    }
+
+    #[test]
+    fn to_cartesian_on_unit_sphere_returns_the_normal() {
+        let e = Ellipsoid::unit_sphere();
+        let geo = Geographic::new(30.0, 60.0, 0.0);
+
+        assert_abs_diff_eq!(
+            e.to_cartesian(geo),
+            e.geodetic_surface_normal(geo),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn scale_to_geocentric_surface_returns_a_point_on_the_surface() {
+        let e = Ellipsoid::mars();
+        let p = e
+            .scale_to_geocentric_surface(DVec3::new(1e7, 2e7, 3e7))
+            .unwrap();
+
+        assert_abs_diff_eq!(
+            p.component_mul(&p).dot(&e.one_over_radii_squared()),
+            1.0,
+            epsilon = 1e-12
+        );
+    }
+
+    #[quickcheck]
+    fn to_geographic_is_the_inverse_of_to_cartesian(geo: Geographic) -> bool {
+        presets().iter().all(|e| {
+            let result = e.to_geographic(e.to_cartesian(geo));
+
+            abs_diff_eq!(result.lat(), geo.lat(), epsilon = 1e-9)
+                && (abs_diff_eq!(result.lon(), geo.lon(), epsilon = 1e-9)
+                    || geo.lat().abs() > 89.999)
+                && abs_diff_eq!(result.elevation(), geo.elevation(), epsilon = 1e-4)
+        })
+    }
+
+    #[test]
+    fn conformal_latitude_of_a_sphere_is_the_geodetic_latitude() {
+        let e = Ellipsoid::unit_sphere();
+
+        assert_abs_diff_eq!(e.conformal_latitude(Radians(0.5)).value(), 0.5, epsilon = 1e-15);
+    }
+
+    #[test]
+    fn authalic_sphere_of_wgs84_has_correct_values() {
+        let e = Ellipsoid::wgs84();
+        let authalic = |lat: f64| e.authalic_latitude(Degrees(lat).to_radians()).to_degrees().value();
+
+        assert_abs_diff_eq!(e.authalic_radius(), 6_371_007.180_9, epsilon = 1e-3);
+        assert_abs_diff_eq!(authalic(0.0), 0.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(authalic(45.0), 44.871_7, epsilon = 1e-4);
+        assert_abs_diff_eq!(authalic(-90.0), -90.0, epsilon = 1e-5);
+        assert_abs_diff_eq!(Ellipsoid::unit_sphere().authalic_radius(), 1.0);
+    }
+
+    #[quickcheck]
+    fn geodetic_latitude_is_the_inverse_of_conformal_latitude(geo: Geographic) -> bool {
+        presets().iter().all(|e| {
+            let conformal = e.conformal_latitude(geo.lat_rad());
+            let result = e.geodetic_latitude(conformal);
+
+            abs_diff_eq!(result.value(), geo.lat_rad().value(), epsilon = 1e-12)
+        })
+    }
+
+    #[quickcheck]
+    fn surface_normal_is_orthogonal_to_the_surface(geo: Geographic) -> bool {
+        let e = Ellipsoid::mars();
+        let surface = e.to_cartesian(geo.flatten());
+
+        abs_diff_eq!(
+            e.geodetic_surface_normal_at(surface),
+            e.geodetic_surface_normal(geo),
+            epsilon = 1e-9
+        )
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_validates_radii() {
+        let parse = |s| serde_json::from_str::<Ellipsoid>(s);
+
+        assert_eq!(
+            parse(r#"{"semi_major": 3396190.0, "semi_minor": 3376200.0}"#).unwrap(),
+            Ellipsoid::mars()
+        );
+        assert!(parse(r#"{"semi_major": 1.0, "semi_minor": 2.0}"#).is_err());
+        assert!(parse(r#"{"semi_major": 1.0, "semi_minor": 0.0}"#).is_err());
+    }
+}
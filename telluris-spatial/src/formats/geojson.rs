@@ -0,0 +1,308 @@
+//! GeoJSON geometries, as specified by RFC 7946.
+//! The reader accepts geometry objects, features (whose geometry is read) and
+//! feature collections (read as geometry collections). Properties are ignored.
+//! Features without geometry (`"geometry": null`) are read as empty geometry
+//! collections, and skipped inside feature collections.
+
+use crate::formats::json::{self, Value};
+use crate::formats::*;
+use std::fmt::Write;
+
+/// Reads a GeoJSON geometry, feature or feature collection.
+pub fn from_str(s: &str) -> Result<Geometry, FormatError> {
+    geometry(&json::parse(s)?)
+}
+
+/// Writes the geometry as a GeoJSON geometry object.
+pub fn to_string(geometry: &Geometry) -> String {
+    let mut out = String::new();
+    write_geometry(&mut out, geometry, has_elevation(geometry));
+
+    out
+}
+
+fn members<'a>(value: &'a Value, name: &str) -> Result<&'a [Value], FormatError> {
+    match value.get(name) {
+        Some(Value::Array(values)) => Ok(values),
+        Some(_) => Err(FormatError::InvalidGeometry("member is not an array")),
+        None => Err(FormatError::InvalidGeometry("missing member")),
+    }
+}
+
+fn geometry(value: &Value) -> Result<Geometry, FormatError> {
+    let kind = match value.get("type") {
+        Some(Value::String(kind)) => kind.as_str(),
+        _ => return Err(FormatError::InvalidGeometry("missing type")),
+    };
+
+    let coordinates = || members(value, "coordinates");
+    let geometry = match kind {
+        "Feature" => match value.get("geometry") {
+            Some(Value::Null) => Geometry::GeometryCollection(vec![]),
+            Some(g) => geometry(g)?,
+            None => return Err(FormatError::InvalidGeometry("feature without geometry")),
+        },
+        "FeatureCollection" => Geometry::GeometryCollection(
+            members(value, "features")?
+                .iter()
+                .filter(|f| f.get("geometry") != Some(&Value::Null))
+                .map(geometry)
+                .collect::<Result<_, _>>()?,
+        ),
+        "GeometryCollection" => Geometry::GeometryCollection(
+            members(value, "geometries")?
+                .iter()
+                .map(geometry)
+                .collect::<Result<_, _>>()?,
+        ),
+        "Point" => Geometry::Point(position(value.get("coordinates"))?),
+        "LineString" => Geometry::LineString(line_string(positions(coordinates()?)?)?),
+        "Polygon" => Geometry::Polygon(rings(coordinates()?)?),
+        "MultiPoint" => multi_point(positions(coordinates()?)?),
+        "MultiLineString" => multi_line_string(
+            coordinates()?
+                .iter()
+                .map(|l| line_string(positions(array(l)?)?))
+                .collect::<Result<_, _>>()?,
+        ),
+        "MultiPolygon" => multi_polygon(
+            coordinates()?
+                .iter()
+                .map(|p| rings(array(p)?))
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => return Err(FormatError::UnsupportedType(kind.to_string())),
+    };
+
+    Ok(geometry)
+}
+
+fn array(value: &Value) -> Result<&[Value], FormatError> {
+    match value {
+        Value::Array(values) => Ok(values),
+        _ => Err(FormatError::InvalidGeometry("coordinates are not an array")),
+    }
+}
+
+/// Reads a position, made of a longitude, a latitude and an optional elevation.
+/// Additional values are ignored.
+fn position(value: Option<&Value>) -> Result<Geographic, FormatError> {
+    let values = value
+        .map(array)
+        .unwrap_or(Err(FormatError::InvalidGeometry("missing member")))?;
+    let number = |i: usize| match values.get(i) {
+        Some(Value::Number(n)) => Ok(Some(*n)),
+        Some(_) => Err(FormatError::InvalidGeometry("position is not made of numbers")),
+        None => Ok(None),
+    };
+
+    match (number(0)?, number(1)?, number(2)?) {
+        (Some(x), Some(y), z) => coordinate(x, y, z.unwrap_or(0.0)),
+        _ => Err(FormatError::InvalidGeometry("positions need two numbers")),
+    }
+}
+
+fn positions(values: &[Value]) -> Result<Vec<Geographic>, FormatError> {
+    values.iter().map(|v| position(Some(v))).collect()
+}
+
+fn rings(values: &[Value]) -> Result<GeoPolygon, FormatError> {
+    polygon(
+        values
+            .iter()
+            .map(|r| positions(array(r)?))
+            .collect::<Result<_, _>>()?,
+    )
+}
+
+fn write_position(out: &mut String, p: &Geographic, elevation: bool) {
+    if elevation {
+        write!(out, "[{},{},{}]", p.lon(), p.lat(), p.elevation()).unwrap();
+    } else {
+        write!(out, "[{},{}]", p.lon(), p.lat()).unwrap();
+    }
+}
+
+/// Writes the items as a JSON array.
+fn write_array<T>(out: &mut String, items: &[T], mut write: impl FnMut(&mut String, &T)) {
+    out.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write(out, item);
+    }
+    out.push(']');
+}
+
+fn write_positions(out: &mut String, points: &[Geographic], elevation: bool) {
+    write_array(out, points, |out, p| write_position(out, p, elevation));
+}
+
+fn write_rings(out: &mut String, polygon: &GeoPolygon, elevation: bool) {
+    write_array(out, &closed_rings(polygon), |out, r| {
+        write_positions(out, r, elevation)
+    });
+}
+
+fn write_geometry(out: &mut String, geometry: &Geometry, elevation: bool) {
+    let kind = match geometry {
+        Geometry::Point(_) => "Point",
+        Geometry::LineString(_) => "LineString",
+        Geometry::Polygon(_) => "Polygon",
+        Geometry::MultiPoint(_) => "MultiPoint",
+        Geometry::MultiLineString(_) => "MultiLineString",
+        Geometry::MultiPolygon(_) => "MultiPolygon",
+        Geometry::GeometryCollection(_) => "GeometryCollection",
+    };
+    out.push_str("{\"type\":");
+    json::write_string(out, kind);
+
+    if let Geometry::GeometryCollection(c) = geometry {
+        out.push_str(",\"geometries\":");
+        write_array(out, c, |out, g| write_geometry(out, g, elevation));
+        out.push('}');
+        return;
+    }
+
+    out.push_str(",\"coordinates\":");
+    match geometry {
+        Geometry::Point(p) => write_position(out, p, elevation),
+        Geometry::LineString(l) => write_positions(out, l.points(), elevation),
+        Geometry::Polygon(p) => write_rings(out, p, elevation),
+        Geometry::MultiPoint(m) => write_positions(out, m.points(), elevation),
+        Geometry::MultiLineString(m) => write_array(out, m.line_strings(), |out, l| {
+            write_positions(out, l.points(), elevation)
+        }),
+        Geometry::MultiPolygon(m) => {
+            write_array(out, m.polygons(), |out, p| write_rings(out, p, elevation))
+        }
+        Geometry::GeometryCollection(_) => unreachable!(),
+    }
+    out.push('}');
+}
+
+#[cfg(test)]
+mod test {
+    use crate::formats::geojson::*;
+    use crate::formats::test::ArbitraryGeometry;
+
+    #[test]
+    fn from_str_reads_geometries() {
+        let point = from_str(r#"{"type": "Point", "coordinates": [2.2945, 48.858222]}"#);
+        let feature = from_str(
+            r#"{"type": "Feature", "properties": {"name": "a"},
+                "geometry": {"type": "LineString", "coordinates": [[0, 0, 10], [1, 1, 20]]}}"#,
+        );
+
+        assert_eq!(point, Ok(Geometry::Point(Geographic::new(48.858222, 2.2945, 0.0))));
+        assert_eq!(
+            feature,
+            Ok(Geometry::LineString(GeoLineString::new(vec![
+                Geographic::new(0.0, 0.0, 10.0),
+                Geographic::new(1.0, 1.0, 20.0)
+            ])))
+        );
+    }
+
+    #[test]
+    fn from_str_reads_feature_collections() {
+        let collection = from_str(
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": null, "geometry": {"type": "Point", "coordinates": [1, 2]}},
+                {"type": "Feature", "properties": null, "geometry": {"type": "MultiPoint", "coordinates": []}}
+            ]}"#,
+        );
+
+        assert_eq!(
+            collection,
+            Ok(Geometry::GeometryCollection(vec![
+                Geometry::Point(Geographic::new(2.0, 1.0, 0.0)),
+                multi_point(vec![])
+            ]))
+        );
+    }
+
+    #[test]
+    fn from_str_accepts_features_without_geometry() {
+        let feature = from_str(r#"{"type": "Feature", "properties": null, "geometry": null}"#);
+        let collection = from_str(
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": null, "geometry": null},
+                {"type": "Feature", "properties": null, "geometry": {"type": "Point", "coordinates": [1, 2]}}
+            ]}"#,
+        );
+
+        assert_eq!(feature, Ok(Geometry::GeometryCollection(vec![])));
+        assert_eq!(
+            collection,
+            Ok(Geometry::GeometryCollection(vec![Geometry::Point(
+                Geographic::new(2.0, 1.0, 0.0)
+            )]))
+        );
+    }
+
+    #[test]
+    fn from_str_returns_errors() {
+        let invalid = |s: &str| from_str(s).unwrap_err();
+
+        assert_eq!(invalid(r#"{"type": "Point", "coordinates": [1, 2]"#), FormatError::UnexpectedEnd);
+        assert_eq!(
+            invalid(r#"{"type": "Point" "coordinates": [1, 2]}"#),
+            FormatError::Syntax {
+                offset: 17,
+                expected: "',' or '}'"
+            }
+        );
+        assert_eq!(
+            invalid(r#"{"type": "Circle", "coordinates": [1, 2]}"#),
+            FormatError::UnsupportedType("Circle".to_string())
+        );
+        assert_eq!(
+            invalid(r#"{"type": "Point", "coordinates": [2, 91]}"#),
+            FormatError::InvalidCoordinate(2.0, 91.0, 0.0)
+        );
+        assert_eq!(
+            invalid(r#"{"type": "Point", "coordinates": [2]}"#),
+            FormatError::InvalidGeometry("positions need two numbers")
+        );
+        assert_eq!(
+            invalid(r#"{"type": "LineString", "coordinates": [[2, 1]]}"#),
+            FormatError::InvalidGeometry("line strings need at least two points")
+        );
+        assert_eq!(
+            invalid(r#"{"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 1]]]}"#),
+            FormatError::InvalidGeometry("rings must be closed")
+        );
+        assert_eq!(
+            invalid(r#"{"type": "Feature", "properties": null}"#),
+            FormatError::InvalidGeometry("feature without geometry")
+        );
+        assert_eq!(
+            invalid(r#"{"coordinates": [1, 2]}"#),
+            FormatError::InvalidGeometry("missing type")
+        );
+    }
+
+    #[test]
+    fn to_string_closes_rings() {
+        let polygon = GeoPolygon::new(
+            vec![
+                Geographic::new(0.0, 0.0, 0.0),
+                Geographic::new(0.0, 1.0, 0.0),
+                Geographic::new(1.0, 1.0, 0.0),
+            ],
+            vec![],
+        );
+
+        assert_eq!(
+            to_string(&Geometry::Polygon(polygon)),
+            r#"{"type":"Polygon","coordinates":[[[0,0],[1,0],[1,1],[0,0]]]}"#
+        );
+    }
+
+    #[quickcheck]
+    fn from_str_is_the_inverse_of_to_string(geometry: ArbitraryGeometry) -> bool {
+        from_str(&to_string(&geometry.0)) == Ok(geometry.0)
+    }
+}
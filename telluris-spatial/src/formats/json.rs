@@ -0,0 +1,354 @@
+//! A minimal JSON parser, sufficient to read GeoJSON documents.
+
+use crate::formats::FormatError;
+
+/// A JSON value. Object members are kept in document order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Returns the member of the object with the specified name.
+    pub(crate) fn get(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// The maximal nesting depth of arrays and objects, to bound the recursion.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+/// Parses a JSON document.
+pub(crate) fn parse(s: &str) -> Result<Value, FormatError> {
+    let mut parser = Parser {
+        input: s.as_bytes(),
+        offset: 0,
+    };
+
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.offset < parser.input.len() {
+        return Err(parser.error("the end of the document"));
+    }
+
+    Ok(value)
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, expected: &'static str) -> FormatError {
+        if self.offset >= self.input.len() {
+            FormatError::UnexpectedEnd
+        } else {
+            FormatError::Syntax {
+                offset: self.offset,
+                expected,
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.offset).cloned()
+    }
+
+    fn expect(&mut self, c: u8, expected: &'static str) -> Result<(), FormatError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.offset += 1;
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, FormatError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("less nested values"));
+        }
+
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'-') | Some(b'0'..=b'9') => self.number().map(Value::Number),
+            _ => {
+                for (literal, value) in [
+                    ("null", Value::Null),
+                    ("true", Value::Bool(true)),
+                    ("false", Value::Bool(false)),
+                ]
+                .iter()
+                {
+                    if self.input[self.offset..].starts_with(literal.as_bytes()) {
+                        self.offset += literal.len();
+                        return Ok(value.clone());
+                    }
+                }
+
+                Err(self.error("a value"))
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Value, FormatError> {
+        self.offset += 1;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("a member name"));
+            }
+            let name = self.string()?;
+            self.expect(b':', "':'")?;
+            let value = self.value(depth + 1)?;
+            members.push((name, value));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b'}') => {
+                    self.offset += 1;
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(self.error("',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Value, FormatError> {
+        self.offset += 1;
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value(depth + 1)?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b']') => {
+                    self.offset += 1;
+                    return Ok(Value::Array(values));
+                }
+                _ => return Err(self.error("',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, FormatError> {
+        self.offset += 1;
+        let mut bytes = Vec::new();
+
+        loop {
+            match self.peek() {
+                None => return Err(FormatError::UnexpectedEnd),
+                Some(b'"') => {
+                    self.offset += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.offset += 1;
+                    let c = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.offset += 1;
+                            let c = self.unicode_escape()?;
+                            let mut buffer = [0; 4];
+                            bytes.extend(c.encode_utf8(&mut buffer).as_bytes());
+                            continue;
+                        }
+                        _ => return Err(self.error("an escape sequence")),
+                    };
+                    self.offset += 1;
+                    bytes.push(c as u8);
+                }
+                Some(c) if c < 0x20 => return Err(self.error("'\"'")),
+                Some(c) => {
+                    self.offset += 1;
+                    bytes.push(c);
+                }
+            }
+        }
+
+        // The input is a `str`, and escapes are valid characters.
+        Ok(String::from_utf8(bytes).expect("invalid UTF-8"))
+    }
+
+    /// Reads the 4 hexadecimal digits of an escape sequence, and the low surrogate
+    /// that follows a high surrogate.
+    fn unicode_escape(&mut self) -> Result<char, FormatError> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.input[self.offset..].starts_with(b"\\u") {
+                return Err(self.error("a low surrogate"));
+            }
+            self.offset += 2;
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("a low surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+
+        std::char::from_u32(code).ok_or_else(|| self.error("a valid character"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, FormatError> {
+        let digits = self
+            .input
+            .get(self.offset..self.offset + 4)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.error("4 hexadecimal digits"))?;
+        self.offset += 4;
+
+        Ok(digits)
+    }
+
+    fn number(&mut self) -> Result<f64, FormatError> {
+        let start = self.offset;
+        let digits = |p: &mut Self| {
+            let from = p.offset;
+            while let Some(b'0'..=b'9') = p.peek() {
+                p.offset += 1;
+            }
+            p.offset > from
+        };
+
+        if self.peek() == Some(b'-') {
+            self.offset += 1;
+        }
+        // Leading zeros are not allowed.
+        if self.peek() == Some(b'0') {
+            self.offset += 1;
+        } else if !digits(self) {
+            return Err(self.error("a digit"));
+        }
+        if self.peek() == Some(b'.') {
+            self.offset += 1;
+            if !digits(self) {
+                return Err(self.error("a digit"));
+            }
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            self.offset += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.offset += 1;
+            }
+            if !digits(self) {
+                return Err(self.error("a digit"));
+            }
+        }
+
+        // The slice only contains ASCII characters of a valid number.
+        let text = std::str::from_utf8(&self.input[start..self.offset]).unwrap();
+        Ok(text.parse().unwrap())
+    }
+}
+
+/// Writes the string as a JSON string, with quotes.
+pub(crate) fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use crate::formats::json::*;
+
+    #[test]
+    fn parse_reads_all_values() {
+        let value = parse(r#" {"a": [1, -2.5e2, true, false, null], "b": {"c": "d\"é😀"}} "#)
+            .unwrap();
+
+        assert_eq!(
+            value.get("a"),
+            Some(&Value::Array(vec![
+                Value::Number(1.0),
+                Value::Number(-250.0),
+                Value::Bool(true),
+                Value::Bool(false),
+                Value::Null
+            ]))
+        );
+        assert_eq!(
+            value.get("b").and_then(|b| b.get("c")),
+            Some(&Value::String("d\"é😀".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_returns_errors() {
+        let syntax = |offset, expected| Err(FormatError::Syntax { offset, expected });
+
+        assert_eq!(parse(""), Err(FormatError::UnexpectedEnd));
+        assert_eq!(parse("[1, 2"), Err(FormatError::UnexpectedEnd));
+        assert_eq!(parse("[1 2]"), syntax(3, "',' or ']'"));
+        assert_eq!(parse("{1: 2}"), syntax(1, "a member name"));
+        assert_eq!(parse("[01]"), syntax(2, "',' or ']'"));
+        assert_eq!(parse("[1.]"), syntax(3, "a digit"));
+        assert_eq!(parse("nul"), syntax(0, "a value"));
+        assert_eq!(parse("[] []"), syntax(3, "the end of the document"));
+        assert_eq!(parse(&"[".repeat(1000)), syntax(129, "less nested values"));
+    }
+
+    #[test]
+    fn write_string_escapes_characters() {
+        let mut out = String::new();
+        write_string(&mut out, "a\"b\\c\n\u{1}é");
+
+        assert_eq!(out, r#""a\"b\\c\n\u0001é""#);
+        assert_eq!(parse(&out), Ok(Value::String("a\"b\\c\n\u{1}é".to_string())));
+    }
+}
@@ -0,0 +1,217 @@
+//! Readers and writers of geometries in the GeoJSON, WKT and WKB formats.
+//! All formats store longitudes before latitudes. Elevations are written as
+//! a third coordinate if any point of the geometry has a non-zero elevation.
+//! Rings are closed when written, and their closing point is removed when read.
+//! `GeoBounds` are written as polygons, through `Geometry::from`.
+
+use crate::geographic::*;
+use crate::geometry::linestring::GeoLineString;
+use crate::geometry::multi::{GeoMultiLineString, GeoMultiPoint, GeoMultiPolygon};
+use crate::geometry::polygon::GeoPolygon;
+use crate::geometry::Geometry;
+use std::error::Error;
+use std::fmt;
+
+pub mod geojson;
+mod json;
+pub mod wkb;
+pub mod wkt;
+
+/// The error returned when reading a geometry fails.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatError {
+    /// The input ended before the geometry was complete.
+    UnexpectedEnd,
+    /// The input does not follow the syntax of the format, at the specified offset in bytes.
+    Syntax { offset: usize, expected: &'static str },
+    /// The geometry type is unknown, or not supported.
+    UnsupportedType(String),
+    /// A coordinate is outside of the valid range, such as a latitude beyond 90°.
+    InvalidCoordinate(f64, f64, f64),
+    /// The geometry is not valid, such as a line string with a single point.
+    InvalidGeometry(&'static str),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            FormatError::Syntax { offset, expected } => {
+                write!(f, "syntax error at offset {}: expected {}", offset, expected)
+            }
+            FormatError::UnsupportedType(t) => write!(f, "unsupported geometry type: '{}'", t),
+            FormatError::InvalidCoordinate(x, y, z) => {
+                write!(f, "invalid coordinate: ({}, {}, {})", x, y, z)
+            }
+            FormatError::InvalidGeometry(message) => write!(f, "invalid geometry: {}", message),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+/// Returns the coordinate of the position, in (longitude, latitude, elevation) order.
+fn coordinate(x: f64, y: f64, z: f64) -> Result<Geographic, FormatError> {
+    if (MIN_LON..=MAX_LON).contains(&x)
+        && (MIN_LAT..=MAX_LAT).contains(&y)
+        && (MIN_ALT..=MAX_ALT).contains(&z)
+    {
+        Ok(Geographic::new(y, x, z))
+    } else {
+        Err(FormatError::InvalidCoordinate(x, y, z))
+    }
+}
+
+/// Returns a line string, which must have at least two points.
+fn line_string(points: Vec<Geographic>) -> Result<GeoLineString, FormatError> {
+    match points.len() {
+        1 => Err(FormatError::InvalidGeometry("line strings need at least two points")),
+        _ => Ok(GeoLineString::new(points)),
+    }
+}
+
+/// Returns a polygon, whose rings must be closed and have at least four points.
+fn polygon(mut rings: Vec<Vec<Geographic>>) -> Result<GeoPolygon, FormatError> {
+    for ring in &rings {
+        if ring.len() < 4 {
+            return Err(FormatError::InvalidGeometry("rings need at least four points"));
+        }
+        if ring.first() != ring.last() {
+            return Err(FormatError::InvalidGeometry("rings must be closed"));
+        }
+    }
+
+    if rings.is_empty() {
+        return Ok(GeoPolygon::default());
+    }
+    let exterior = rings.remove(0);
+
+    Ok(GeoPolygon::new(exterior, rings))
+}
+
+/// Returns the closed rings of the polygon, or nothing if the polygon is empty.
+fn closed_rings(polygon: &GeoPolygon) -> Vec<Vec<Geographic>> {
+    if polygon.exterior().is_empty() {
+        return Vec::new();
+    }
+
+    polygon
+        .rings()
+        .map(|ring| {
+            let mut closed = ring.to_vec();
+            closed.extend(ring.first());
+            closed
+        })
+        .collect()
+}
+
+/// Returns true if any point of the geometry has a non-zero elevation.
+fn has_elevation(geometry: &Geometry) -> bool {
+    geometry.points().any(|p| p.elevation() != 0.0)
+}
+
+fn multi_point(points: Vec<Geographic>) -> Geometry {
+    Geometry::MultiPoint(GeoMultiPoint::new(points))
+}
+
+fn multi_line_string(lines: Vec<GeoLineString>) -> Geometry {
+    Geometry::MultiLineString(GeoMultiLineString::new(lines))
+}
+
+fn multi_polygon(polygons: Vec<GeoPolygon>) -> Geometry {
+    Geometry::MultiPolygon(GeoMultiPolygon::new(polygons))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::formats::*;
+    use crate::geobounds::GeoBounds;
+    use quickcheck::{Arbitrary, Gen};
+    use rand::Rng;
+
+    /// A geometry whose coordinates are exactly representable in all formats.
+    #[derive(Debug, Clone)]
+    pub struct ArbitraryGeometry(pub Geometry);
+
+    fn point<G: Gen>(g: &mut G, elevation: bool) -> Geographic {
+        let lat = f64::from(g.gen_range(-90_000, 90_001)) / 1000.0;
+        let lon = f64::from(g.gen_range(-180_000, 180_001)) / 1000.0;
+        let z = if elevation {
+            f64::from(g.gen_range(-1000, 1000))
+        } else {
+            0.0
+        };
+
+        Geographic::new(lat, lon, z)
+    }
+
+    fn points<G: Gen>(g: &mut G, min: usize, elevation: bool) -> Vec<Geographic> {
+        let count = g.gen_range(min, min + 5);
+
+        (0..count).map(|_| point(g, elevation)).collect()
+    }
+
+    fn geometry<G: Gen>(g: &mut G, depth: usize, elevation: bool) -> Geometry {
+        let polygon = |g: &mut G| {
+            let rings = g.gen_range(0, 3);
+            GeoPolygon::new(
+                points(g, 3, elevation),
+                (0..rings).map(|_| points(g, 3, elevation)).collect(),
+            )
+        };
+
+        match g.gen_range(0, if depth == 0 { 7 } else { 6 }) {
+            0 => Geometry::Point(point(g, elevation)),
+            1 => Geometry::LineString(GeoLineString::new(points(g, 2, elevation))),
+            2 => Geometry::Polygon(polygon(g)),
+            3 => multi_point(points(g, 0, elevation)),
+            4 => multi_line_string(
+                (0..g.gen_range(0, 3))
+                    .map(|_| GeoLineString::new(points(g, 2, elevation)))
+                    .collect(),
+            ),
+            5 => multi_polygon((0..g.gen_range(0, 3)).map(|_| polygon(g)).collect()),
+            _ => Geometry::GeometryCollection(
+                (0..g.gen_range(0, 3))
+                    .map(|_| geometry(g, depth + 1, elevation))
+                    .collect(),
+            ),
+        }
+    }
+
+    impl Arbitrary for ArbitraryGeometry {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let elevation = g.gen();
+            ArbitraryGeometry(geometry(g, 0, elevation))
+        }
+    }
+
+    #[test]
+    fn bounds_are_written_as_polygons() {
+        let bounds = GeoBounds::new(
+            Geographic::new(-10.0, 170.0, 0.0),
+            Geographic::new(10.0, -170.0, 0.0),
+        );
+        let geometry = Geometry::from(bounds);
+
+        assert_eq!(
+            wkt::to_string(&geometry),
+            "POLYGON ((170 -10, -170 -10, -170 10, 170 10, 170 -10))"
+        );
+        assert_abs_diff_eq!(geometry.bounds().unwrap().west(), 170.0);
+        assert_abs_diff_eq!(geometry.bounds().unwrap().east(), -170.0);
+    }
+
+    #[test]
+    fn errors_have_messages() {
+        let error = FormatError::Syntax {
+            offset: 12,
+            expected: "a number",
+        };
+
+        assert_eq!(
+            format!("{}", error),
+            "syntax error at offset 12: expected a number"
+        );
+    }
+}
@@ -0,0 +1,432 @@
+//! Well-Known Binary (WKB) geometries, as specified by OGC Simple Features.
+//! The reader accepts both byte orders, the ISO dimension codes (such as 1001 for
+//! a point with elevations), and the extended WKB of PostGIS (EWKB), whose spatial
+//! reference system identifier is ignored. Measures are read and dropped.
+//! The writer produces little-endian ISO WKB.
+
+use crate::formats::*;
+use std::convert::TryInto;
+
+/// The type codes of the geometries.
+const POINT: u32 = 1;
+const LINE_STRING: u32 = 2;
+const POLYGON: u32 = 3;
+const MULTI_POINT: u32 = 4;
+const MULTI_LINE_STRING: u32 = 5;
+const MULTI_POLYGON: u32 = 6;
+const GEOMETRY_COLLECTION: u32 = 7;
+
+/// The flags of extended WKB type codes.
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// The offset added to ISO WKB type codes for geometries with elevations.
+const ISO_Z: u32 = 1000;
+
+/// The maximal nesting depth of geometry collections, to bound the recursion.
+const MAX_DEPTH: usize = 32;
+
+/// Reads a WKB or EWKB geometry.
+pub fn from_bytes(bytes: &[u8]) -> Result<Geometry, FormatError> {
+    let mut reader = Reader {
+        input: bytes,
+        offset: 0,
+        little_endian: true,
+    };
+
+    let geometry = reader.geometry(0)?;
+    if reader.offset < bytes.len() {
+        return Err(reader.error("the end of the geometry"));
+    }
+
+    Ok(geometry)
+}
+
+/// Reads a WKB or EWKB geometry encoded in hexadecimal, as found in PostGIS dumps.
+pub fn from_hex(hex: &str) -> Result<Geometry, FormatError> {
+    let hex = hex.trim().as_bytes();
+    if hex.len() % 2 == 1 {
+        return Err(FormatError::UnexpectedEnd);
+    }
+
+    let bytes = hex
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|p| u8::from_str_radix(p, 16).ok())
+                .ok_or(FormatError::Syntax {
+                    offset: 2 * i,
+                    expected: "hexadecimal digits",
+                })
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    from_bytes(&bytes)
+}
+
+/// Writes the geometry as little-endian ISO WKB.
+pub fn to_bytes(geometry: &Geometry) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_geometry(&mut out, geometry, has_elevation(geometry));
+
+    out
+}
+
+/// Writes the geometry as little-endian ISO WKB, encoded in uppercase hexadecimal.
+pub fn to_hex(geometry: &Geometry) -> String {
+    to_bytes(geometry)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect()
+}
+
+struct Reader<'a> {
+    input: &'a [u8],
+    offset: usize,
+    little_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, expected: &'static str) -> FormatError {
+        FormatError::Syntax {
+            offset: self.offset,
+            expected,
+        }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], FormatError> {
+        let bytes = self
+            .input
+            .get(self.offset..self.offset + count)
+            .ok_or(FormatError::UnexpectedEnd)?;
+        self.offset += count;
+
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, FormatError> {
+        let bytes = self.bytes(4)?.try_into().unwrap();
+
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64, FormatError> {
+        let bytes = self.bytes(8)?.try_into().unwrap();
+
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads the longitude, latitude and elevation of a position.
+    fn values(&mut self, z: bool, m: bool) -> Result<(f64, f64, f64), FormatError> {
+        let (x, y) = (self.f64()?, self.f64()?);
+        let z = if z { self.f64()? } else { 0.0 };
+        if m {
+            self.f64()?;
+        }
+
+        Ok((x, y, z))
+    }
+
+    fn position(&mut self, z: bool, m: bool) -> Result<Geographic, FormatError> {
+        let (x, y, z) = self.values(z, m)?;
+
+        coordinate(x, y, z)
+    }
+
+    /// Reads a list of items, prefixed by their count.
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, FormatError>,
+    ) -> Result<Vec<T>, FormatError> {
+        let count = self.u32()?;
+
+        // The count is not used as capacity, as it may be corrupted.
+        let mut items = Vec::new();
+        for _ in 0..count {
+            items.push(item(self)?);
+        }
+
+        Ok(items)
+    }
+
+    fn geometry(&mut self, depth: usize) -> Result<Geometry, FormatError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("less nested geometries"));
+        }
+
+        let start = self.offset;
+        self.little_endian = match self.bytes(1)? {
+            [0] => false,
+            [1] => true,
+            _ => {
+                self.offset = start;
+                return Err(self.error("a byte order"));
+            }
+        };
+
+        let code = self.u32()?;
+        if code & EWKB_SRID != 0 {
+            self.u32()?;
+        }
+        let iso = code & 0x0FFF_FFFF;
+        let (kind, dimensions) = (iso % 1000, iso / 1000);
+        let z = code & EWKB_Z != 0 || dimensions == 1 || dimensions == 3;
+        let m = code & EWKB_M != 0 || dimensions == 2 || dimensions == 3;
+        if dimensions > 3 {
+            return Err(FormatError::UnsupportedType(code.to_string()));
+        }
+
+        let member = |r: &mut Self, expected: u32, message| match r.geometry(depth + 1)? {
+            g if type_code(&g) == expected => Ok(g),
+            _ => Err(FormatError::InvalidGeometry(message)),
+        };
+
+        let geometry = match kind {
+            POINT => match self.values(z, m)? {
+                // Empty points are written with NaN coordinates.
+                (x, y, _) if x.is_nan() && y.is_nan() => {
+                    return Err(FormatError::InvalidGeometry(
+                        "empty points are not supported",
+                    ))
+                }
+                (x, y, z) => Geometry::Point(coordinate(x, y, z)?),
+            },
+            LINE_STRING => {
+                let points = self.list(|r| r.position(z, m))?;
+                match points.len() {
+                    0 => Geometry::LineString(GeoLineString::default()),
+                    _ => Geometry::LineString(line_string(points)?),
+                }
+            }
+            POLYGON => Geometry::Polygon(polygon(self.list(|r| r.list(|r| r.position(z, m)))?)?),
+            MULTI_POINT => multi_point(
+                self.list(|r| member(r, POINT, "multi point members must be points"))?
+                    .into_iter()
+                    .filter_map(|g| match g {
+                        Geometry::Point(p) => Some(p),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            MULTI_LINE_STRING => multi_line_string(
+                self.list(|r| {
+                    member(
+                        r,
+                        LINE_STRING,
+                        "multi line string members must be line strings",
+                    )
+                })?
+                .into_iter()
+                .filter_map(|g| match g {
+                    Geometry::LineString(l) => Some(l),
+                    _ => None,
+                })
+                .collect(),
+            ),
+            MULTI_POLYGON => multi_polygon(
+                self.list(|r| member(r, POLYGON, "multi polygon members must be polygons"))?
+                    .into_iter()
+                    .filter_map(|g| match g {
+                        Geometry::Polygon(p) => Some(p),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            GEOMETRY_COLLECTION => {
+                Geometry::GeometryCollection(self.list(|r| r.geometry(depth + 1))?)
+            }
+            _ => return Err(FormatError::UnsupportedType(code.to_string())),
+        };
+
+        Ok(geometry)
+    }
+}
+
+fn type_code(geometry: &Geometry) -> u32 {
+    match geometry {
+        Geometry::Point(_) => POINT,
+        Geometry::LineString(_) => LINE_STRING,
+        Geometry::Polygon(_) => POLYGON,
+        Geometry::MultiPoint(_) => MULTI_POINT,
+        Geometry::MultiLineString(_) => MULTI_LINE_STRING,
+        Geometry::MultiPolygon(_) => MULTI_POLYGON,
+        Geometry::GeometryCollection(_) => GEOMETRY_COLLECTION,
+    }
+}
+
+fn write_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_position(out: &mut Vec<u8>, p: &Geographic, elevation: bool) {
+    out.extend_from_slice(&p.lon().to_le_bytes());
+    out.extend_from_slice(&p.lat().to_le_bytes());
+    if elevation {
+        out.extend_from_slice(&p.elevation().to_le_bytes());
+    }
+}
+
+fn write_positions(out: &mut Vec<u8>, points: &[Geographic], elevation: bool) {
+    write_u32(out, points.len() as u32);
+    for p in points {
+        write_position(out, p, elevation);
+    }
+}
+
+fn write_rings(out: &mut Vec<u8>, polygon: &GeoPolygon, elevation: bool) {
+    let rings = closed_rings(polygon);
+    write_u32(out, rings.len() as u32);
+    for ring in &rings {
+        write_positions(out, ring, elevation);
+    }
+}
+
+fn write_geometry(out: &mut Vec<u8>, geometry: &Geometry, elevation: bool) {
+    out.push(1);
+    let code = type_code(geometry);
+    write_u32(out, if elevation { code + ISO_Z } else { code });
+
+    match geometry {
+        Geometry::Point(p) => write_position(out, p, elevation),
+        Geometry::LineString(l) => write_positions(out, l.points(), elevation),
+        Geometry::Polygon(p) => write_rings(out, p, elevation),
+        Geometry::MultiPoint(m) => {
+            write_u32(out, m.len() as u32);
+            for p in m.points() {
+                write_geometry(out, &Geometry::Point(*p), elevation);
+            }
+        }
+        Geometry::MultiLineString(m) => {
+            write_u32(out, m.len() as u32);
+            for l in m.line_strings() {
+                write_geometry(out, &Geometry::LineString(l.clone()), elevation);
+            }
+        }
+        Geometry::MultiPolygon(m) => {
+            write_u32(out, m.len() as u32);
+            for p in m.polygons() {
+                write_geometry(out, &Geometry::Polygon(p.clone()), elevation);
+            }
+        }
+        Geometry::GeometryCollection(c) => {
+            write_u32(out, c.len() as u32);
+            for g in c {
+                write_geometry(out, g, elevation);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::formats::test::ArbitraryGeometry;
+    use crate::formats::wkb::*;
+
+    #[test]
+    fn from_bytes_reads_both_byte_orders() {
+        let little = [
+            0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF0, 0x3F, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+        ];
+        let big = [
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let point = Ok(Geometry::Point(Geographic::new(2.0, 1.0, 0.0)));
+
+        assert_eq!(from_bytes(&little), point);
+        assert_eq!(from_bytes(&big), point);
+    }
+
+    #[test]
+    fn from_hex_reads_extended_wkb() {
+        // SRID=4326;POINT(2 48), as written by PostGIS.
+        let point = from_hex("0101000020E610000000000000000000400000000000004840");
+        // LINESTRING Z (1 2 3, 4 5 6), with the EWKB flag for elevations.
+        let line = from_hex(
+            "010200008002000000000000000000F03F00000000000000400000000000000840\
+             000000000000104000000000000014400000000000001840",
+        );
+
+        assert_eq!(point, Ok(Geometry::Point(Geographic::new(48.0, 2.0, 0.0))));
+        assert_eq!(
+            line,
+            Ok(Geometry::LineString(GeoLineString::new(vec![
+                Geographic::new(2.0, 1.0, 3.0),
+                Geographic::new(5.0, 4.0, 6.0)
+            ])))
+        );
+    }
+
+    #[test]
+    fn from_bytes_returns_errors() {
+        let invalid = |s: &str| from_hex(s).unwrap_err();
+
+        assert_eq!(
+            invalid("0101000000000000000000F03F"),
+            FormatError::UnexpectedEnd
+        );
+        assert_eq!(
+            invalid("0201000000000000000000F03F0000000000000040"),
+            FormatError::Syntax {
+                offset: 0,
+                expected: "a byte order"
+            }
+        );
+        assert_eq!(
+            invalid("0101000000000000000000F03F000000000000004000"),
+            FormatError::Syntax {
+                offset: 21,
+                expected: "the end of the geometry"
+            }
+        );
+        assert_eq!(
+            invalid("0108000000"),
+            FormatError::UnsupportedType("8".to_string())
+        );
+        assert_eq!(
+            invalid("0101000000000000000000F87F000000000000F87F"),
+            FormatError::InvalidGeometry("empty points are not supported")
+        );
+        assert_eq!(
+            invalid("0104000000010000000102000000000000000"),
+            FormatError::UnexpectedEnd
+        );
+        assert_eq!(
+            invalid("010400000001000000010200000000000000"),
+            FormatError::InvalidGeometry("multi point members must be points")
+        );
+        assert_eq!(
+            invalid("01010000000000000000000000000000000000F07F"),
+            FormatError::InvalidCoordinate(0.0, f64::INFINITY, 0.0)
+        );
+    }
+
+    #[test]
+    fn to_hex_writes_iso_wkb() {
+        let point = Geometry::Point(Geographic::new(2.0, 1.0, 0.0));
+        let elevated = Geometry::Point(Geographic::new(2.0, 1.0, 3.0));
+
+        assert_eq!(to_hex(&point), "0101000000000000000000F03F0000000000000040");
+        assert_eq!(
+            to_hex(&elevated),
+            "01E9030000000000000000F03F00000000000000400000000000000840"
+        );
+    }
+
+    #[quickcheck]
+    fn from_bytes_is_the_inverse_of_to_bytes(geometry: ArbitraryGeometry) -> bool {
+        from_bytes(&to_bytes(&geometry.0)) == Ok(geometry.0)
+    }
+}
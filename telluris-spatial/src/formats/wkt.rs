@@ -0,0 +1,442 @@
+//! Well-Known Text (WKT) geometries, as specified by OGC Simple Features.
+//! The reader also accepts the extended WKT of PostGIS, whose `SRID=...;` prefix
+//! is ignored. Measures (`M` coordinates) are read and dropped.
+
+use crate::formats::*;
+use std::fmt::Write;
+
+/// Reads a WKT geometry.
+pub fn from_str(s: &str) -> Result<Geometry, FormatError> {
+    let mut reader = Reader {
+        input: s.as_bytes(),
+        offset: 0,
+    };
+
+    // Extended WKT starts with the identifier of the spatial reference system.
+    if reader.input.len() >= 5 && reader.input[..5].eq_ignore_ascii_case(b"SRID=") {
+        match reader.input.iter().position(|c| *c == b';') {
+            Some(i) => reader.offset = i + 1,
+            None => return Err(reader.error("';'")),
+        }
+    }
+
+    let geometry = reader.geometry(0)?;
+    reader.skip_whitespace();
+    if reader.offset < reader.input.len() {
+        return Err(reader.error("the end of the geometry"));
+    }
+
+    Ok(geometry)
+}
+
+/// Writes the geometry as WKT.
+pub fn to_string(geometry: &Geometry) -> String {
+    let mut out = String::new();
+    write_geometry(&mut out, geometry, has_elevation(geometry));
+
+    out
+}
+
+/// The maximal nesting depth of geometry collections, to bound the recursion.
+const MAX_DEPTH: usize = 32;
+
+/// The dimensions of the positions of a geometry.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Dimensions {
+    /// The dimensions are given by the number of values of the positions.
+    Unspecified,
+    Z,
+    M,
+    ZM,
+}
+
+struct Reader<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, expected: &'static str) -> FormatError {
+        if self.offset >= self.input.len() {
+            FormatError::UnexpectedEnd
+        } else {
+            FormatError::Syntax {
+                offset: self.offset,
+                expected,
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.input.get(self.offset), Some(c) if c.is_ascii_whitespace()) {
+            self.offset += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.get(self.offset).cloned()
+    }
+
+    /// Consumes the character if it is the next one.
+    fn accept(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.offset += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, c: u8, expected: &'static str) -> Result<(), FormatError> {
+        if self.accept(c) {
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    /// Reads the next word, in upper case. Returns an empty word if the next
+    /// character is not a letter.
+    fn word(&mut self) -> String {
+        self.skip_whitespace();
+        let start = self.offset;
+        while matches!(self.input.get(self.offset), Some(c) if c.is_ascii_alphabetic()) {
+            self.offset += 1;
+        }
+
+        String::from_utf8_lossy(&self.input[start..self.offset]).to_ascii_uppercase()
+    }
+
+    fn number(&mut self) -> Result<f64, FormatError> {
+        self.skip_whitespace();
+        let start = self.offset;
+        while let Some(b'0'..=b'9') | Some(b'+') | Some(b'-') | Some(b'.') | Some(b'e')
+        | Some(b'E') = self.input.get(self.offset)
+        {
+            self.offset += 1;
+        }
+
+        let number = std::str::from_utf8(&self.input[start..self.offset])
+            .ok()
+            .and_then(|n| n.parse().ok());
+
+        number.ok_or_else(|| {
+            self.offset = start;
+            self.error("a number")
+        })
+    }
+
+    /// Reads the optional dimensions and the `EMPTY` keyword that follow a type.
+    /// Returns `None` for empty geometries.
+    fn header(&mut self) -> Result<Option<Dimensions>, FormatError> {
+        self.skip_whitespace();
+        let mut start = self.offset;
+        let mut word = self.word();
+        let dimensions = match word.as_str() {
+            "Z" => Dimensions::Z,
+            "M" => Dimensions::M,
+            "ZM" => Dimensions::ZM,
+            _ => Dimensions::Unspecified,
+        };
+        if dimensions != Dimensions::Unspecified {
+            self.skip_whitespace();
+            start = self.offset;
+            word = self.word();
+        }
+
+        match word.as_str() {
+            "EMPTY" => Ok(None),
+            "" => Ok(Some(dimensions)),
+            _ => {
+                self.offset = start;
+                Err(self.error("'(' or 'EMPTY'"))
+            }
+        }
+    }
+
+    fn position(&mut self, dimensions: Dimensions) -> Result<Geographic, FormatError> {
+        let mut values = vec![self.number()?, self.number()?];
+        while let Some(b'0'..=b'9') | Some(b'+') | Some(b'-') | Some(b'.') = self.peek() {
+            if values.len() == 4 {
+                return Err(self.error("',' or ')'"));
+            }
+            values.push(self.number()?);
+        }
+
+        let expected = match dimensions {
+            Dimensions::Unspecified => values.len(),
+            Dimensions::Z | Dimensions::M => 3,
+            Dimensions::ZM => 4,
+        };
+        if values.len() != expected {
+            return Err(FormatError::InvalidGeometry(
+                "positions do not match the dimensions",
+            ));
+        }
+
+        let z = match dimensions {
+            Dimensions::M => 0.0,
+            _ => values.get(2).cloned().unwrap_or(0.0),
+        };
+
+        coordinate(values[0], values[1], z)
+    }
+
+    /// Reads a comma-separated list, in parentheses.
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, FormatError>,
+    ) -> Result<Vec<T>, FormatError> {
+        self.expect(b'(', "'('")?;
+        let mut items = vec![item(self)?];
+        while self.accept(b',') {
+            items.push(item(self)?);
+        }
+        self.expect(b')', "',' or ')'")?;
+
+        Ok(items)
+    }
+
+    fn positions(&mut self, dimensions: Dimensions) -> Result<Vec<Geographic>, FormatError> {
+        self.list(|r| r.position(dimensions))
+    }
+
+    fn rings(&mut self, dimensions: Dimensions) -> Result<GeoPolygon, FormatError> {
+        polygon(self.list(|r| r.positions(dimensions))?)
+    }
+
+    fn geometry(&mut self, depth: usize) -> Result<Geometry, FormatError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("less nested geometries"));
+        }
+
+        let start = self.offset;
+        let kind = self.word();
+        if kind.is_empty() {
+            return Err(self.error("a geometry type"));
+        }
+
+        let dimensions = match kind.as_str() {
+            "POINT" | "LINESTRING" | "POLYGON" | "MULTIPOINT" | "MULTILINESTRING"
+            | "MULTIPOLYGON" | "GEOMETRYCOLLECTION" => self.header()?,
+            _ => {
+                let kind = String::from_utf8_lossy(&self.input[start..self.offset]);
+                return Err(FormatError::UnsupportedType(kind.trim().to_string()));
+            }
+        };
+
+        let d = match dimensions {
+            Some(d) => d,
+            None => {
+                return match kind.as_str() {
+                    "POINT" => Err(FormatError::InvalidGeometry("empty points are not supported")),
+                    "LINESTRING" => Ok(Geometry::LineString(GeoLineString::default())),
+                    "POLYGON" => Ok(Geometry::Polygon(GeoPolygon::default())),
+                    "MULTIPOINT" => Ok(multi_point(vec![])),
+                    "MULTILINESTRING" => Ok(multi_line_string(vec![])),
+                    "MULTIPOLYGON" => Ok(multi_polygon(vec![])),
+                    _ => Ok(Geometry::GeometryCollection(vec![])),
+                }
+            }
+        };
+
+        let geometry = match kind.as_str() {
+            "POINT" => {
+                self.expect(b'(', "'('")?;
+                let p = self.position(d)?;
+                self.expect(b')', "')'")?;
+                Geometry::Point(p)
+            }
+            "LINESTRING" => Geometry::LineString(line_string(self.positions(d)?)?),
+            "POLYGON" => Geometry::Polygon(self.rings(d)?),
+            // Points of multi points may be in parentheses or not.
+            "MULTIPOINT" => multi_point(self.list(|r| {
+                if r.accept(b'(') {
+                    let p = r.position(d)?;
+                    r.expect(b')', "')'")?;
+                    Ok(p)
+                } else {
+                    r.position(d)
+                }
+            })?),
+            "MULTILINESTRING" => multi_line_string(
+                self.list(|r| line_string(r.positions(d)?))?,
+            ),
+            "MULTIPOLYGON" => multi_polygon(self.list(|r| r.rings(d))?),
+            _ => Geometry::GeometryCollection(self.list(|r| r.geometry(depth + 1))?),
+        };
+
+        Ok(geometry)
+    }
+}
+
+fn write_position(out: &mut String, p: &Geographic, elevation: bool) {
+    if elevation {
+        write!(out, "{} {} {}", p.lon(), p.lat(), p.elevation()).unwrap();
+    } else {
+        write!(out, "{} {}", p.lon(), p.lat()).unwrap();
+    }
+}
+
+/// Writes the items as a comma-separated list in parentheses, or `EMPTY`.
+fn write_list<T>(out: &mut String, items: &[T], mut write: impl FnMut(&mut String, &T)) {
+    if items.is_empty() {
+        out.push_str("EMPTY");
+        return;
+    }
+
+    out.push('(');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write(out, item);
+    }
+    out.push(')');
+}
+
+fn write_positions(out: &mut String, points: &[Geographic], elevation: bool) {
+    write_list(out, points, |out, p| write_position(out, p, elevation));
+}
+
+fn write_rings(out: &mut String, polygon: &GeoPolygon, elevation: bool) {
+    write_list(out, &closed_rings(polygon), |out, r| {
+        write_positions(out, r, elevation)
+    });
+}
+
+fn write_geometry(out: &mut String, geometry: &Geometry, elevation: bool) {
+    out.push_str(match geometry {
+        Geometry::Point(_) => "POINT",
+        Geometry::LineString(_) => "LINESTRING",
+        Geometry::Polygon(_) => "POLYGON",
+        Geometry::MultiPoint(_) => "MULTIPOINT",
+        Geometry::MultiLineString(_) => "MULTILINESTRING",
+        Geometry::MultiPolygon(_) => "MULTIPOLYGON",
+        Geometry::GeometryCollection(_) => "GEOMETRYCOLLECTION",
+    });
+    match geometry {
+        Geometry::GeometryCollection(_) => out.push(' '),
+        _ if elevation => out.push_str(" Z "),
+        _ => out.push(' '),
+    }
+
+    match geometry {
+        Geometry::Point(p) => write_positions(out, std::slice::from_ref(p), elevation),
+        Geometry::LineString(l) => write_positions(out, l.points(), elevation),
+        Geometry::Polygon(p) => write_rings(out, p, elevation),
+        Geometry::MultiPoint(m) => write_list(out, m.points(), |out, p| {
+            write_positions(out, std::slice::from_ref(p), elevation)
+        }),
+        Geometry::MultiLineString(m) => write_list(out, m.line_strings(), |out, l| {
+            write_positions(out, l.points(), elevation)
+        }),
+        Geometry::MultiPolygon(m) => {
+            write_list(out, m.polygons(), |out, p| write_rings(out, p, elevation))
+        }
+        Geometry::GeometryCollection(c) => {
+            write_list(out, c, |out, g| write_geometry(out, g, elevation))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::formats::test::ArbitraryGeometry;
+    use crate::formats::wkt::*;
+
+    fn geo(lat: f64, lon: f64, z: f64) -> Geographic {
+        Geographic::new(lat, lon, z)
+    }
+
+    #[test]
+    fn from_str_reads_geometries() {
+        assert_eq!(
+            from_str("POINT (2.2945 48.858222)"),
+            Ok(Geometry::Point(geo(48.858222, 2.2945, 0.0)))
+        );
+        assert_eq!(
+            from_str("SRID=4326;point z(1 2 3)"),
+            Ok(Geometry::Point(geo(2.0, 1.0, 3.0)))
+        );
+        assert_eq!(
+            from_str("POINT ZM (1 2 3 4)"),
+            Ok(Geometry::Point(geo(2.0, 1.0, 3.0)))
+        );
+        assert_eq!(
+            from_str("POINT M (1 2 4)"),
+            Ok(Geometry::Point(geo(2.0, 1.0, 0.0)))
+        );
+        assert_eq!(
+            from_str("MULTIPOINT (1 2, 3 4)"),
+            from_str("MULTIPOINT ((1 2), (3 4))")
+        );
+        assert_eq!(
+            from_str("GEOMETRYCOLLECTION (LINESTRING EMPTY, POLYGON ((0 0, 1 0, 1 1, 0 0)))"),
+            Ok(Geometry::GeometryCollection(vec![
+                Geometry::LineString(GeoLineString::default()),
+                Geometry::Polygon(GeoPolygon::new(
+                    vec![geo(0.0, 0.0, 0.0), geo(0.0, 1.0, 0.0), geo(1.0, 1.0, 0.0)],
+                    vec![]
+                ))
+            ]))
+        );
+    }
+
+    #[test]
+    fn from_str_returns_errors() {
+        let syntax = |offset, expected| Err(FormatError::Syntax { offset, expected });
+
+        assert_eq!(from_str(""), Err(FormatError::UnexpectedEnd));
+        assert_eq!(from_str("POINT (1 2"), Err(FormatError::UnexpectedEnd));
+        assert_eq!(from_str("POINT (1 a)"), syntax(9, "a number"));
+        assert_eq!(from_str("POINT (1 2) x"), syntax(12, "the end of the geometry"));
+        assert_eq!(from_str("POINT FULL"), syntax(6, "'(' or 'EMPTY'"));
+        assert_eq!(from_str("LINESTRING (1 2, 3 4"), Err(FormatError::UnexpectedEnd));
+        assert_eq!(
+            from_str("CIRCULARSTRING (1 2, 3 4, 5 6)"),
+            Err(FormatError::UnsupportedType("CIRCULARSTRING".to_string()))
+        );
+        assert_eq!(
+            from_str("POINT (200 2)"),
+            Err(FormatError::InvalidCoordinate(200.0, 2.0, 0.0))
+        );
+        assert_eq!(
+            from_str("POINT Z (1 2)"),
+            Err(FormatError::InvalidGeometry(
+                "positions do not match the dimensions"
+            ))
+        );
+        assert_eq!(
+            from_str("POLYGON ((0 0, 1 0, 0 0))"),
+            Err(FormatError::InvalidGeometry("rings need at least four points"))
+        );
+        assert_eq!(
+            from_str("POINT EMPTY"),
+            Err(FormatError::InvalidGeometry("empty points are not supported"))
+        );
+    }
+
+    #[test]
+    fn to_string_returns_correct_values() {
+        let line = GeoLineString::new(vec![geo(2.0, 1.0, 0.0), geo(4.0, 3.0, 10.5)]);
+        let collection = Geometry::GeometryCollection(vec![
+            Geometry::Point(geo(2.0, 1.0, 0.0)),
+            multi_point(vec![]),
+        ]);
+
+        assert_eq!(
+            to_string(&Geometry::LineString(line)),
+            "LINESTRING Z (1 2 0, 3 4 10.5)"
+        );
+        assert_eq!(
+            to_string(&collection),
+            "GEOMETRYCOLLECTION (POINT (1 2), MULTIPOINT EMPTY)"
+        );
+    }
+
+    #[quickcheck]
+    fn from_str_is_the_inverse_of_to_string(geometry: ArbitraryGeometry) -> bool {
+        from_str(&to_string(&geometry.0)) == Ok(geometry.0)
+    }
+}
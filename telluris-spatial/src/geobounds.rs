@@ -1,13 +1,21 @@
 use num::clamp;
+use crate::angle::Degrees;
 use crate::geographic::*;
+use crate::index::Quadrant;
 
 use quickcheck::{Arbitrary, Gen};
 
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize};
+
 #[cfg(test)]
 use approx::AbsDiffEq;
 
 /// Represents a volume bounded by two geographic corners.
+/// Bounds whose western edge is greater than their eastern edge cross the
+/// antimeridian: they span from `west` to `MAX_LON`, then from `MIN_LON` to `east`.
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct GeoBounds {
     min: Geographic,
     max: Geographic,
@@ -42,11 +50,17 @@ impl GeoBounds {
     }
 
     /// Returns the union between this bounds and the other.
+    /// If either bounds crosses the antimeridian, the longitude range of the union
+    /// is the smallest one that covers both, which may cross the antimeridian as well.
     pub fn expand(self, other: &GeoBounds) -> Self {
         let new_min_lat = self.south().min(other.south());
         let new_max_lat = self.north().max(other.north());
-        let new_min_lon = self.west().min(other.west());
-        let new_max_lon = self.east().max(other.east());
+        let (new_min_lon, new_max_lon) =
+            if self.crosses_antimeridian() || other.crosses_antimeridian() {
+                self.wrapped_lon_union(other)
+            } else {
+                (self.west().min(other.west()), self.east().max(other.east()))
+            };
         let new_min_alt = self.floor().min(other.floor());
         let new_max_alt = self.top().max(other.top());
 
@@ -61,13 +75,44 @@ impl GeoBounds {
         a.expand(b)
     }
 
+    /// Returns the longitude range of the smallest arc that covers both bounds.
+    fn wrapped_lon_union(&self, other: &GeoBounds) -> (f64, f64) {
+        // The smallest arc starts at the western edge of either bounds.
+        let (self_west, other_west) = (self.min.longitude(), other.min.longitude());
+        let from_self = self
+            .span_lon()
+            .max(eastward(self_west, other_west).value() + other.span_lon());
+        let from_other = other
+            .span_lon()
+            .max(eastward(other_west, self_west).value() + self.span_lon());
+
+        let (west, span) = if from_self <= from_other {
+            (self_west, from_self)
+        } else {
+            (other_west, from_other)
+        };
+
+        if span >= 360.0 {
+            (MIN_LON, MAX_LON)
+        } else {
+            (west.value(), wrap_lon(west + Degrees(span)).value())
+        }
+    }
+
     /// Grows the bounds in horizontal and vertical directions with the given values, in degrees.
     /// Altitudes are untouched.
+    /// Longitudes wrap around the antimeridian, up to the whole longitude range.
     pub fn grow(self, horizontal: f64, vertical: f64) -> Self {
         let new_min_lat = clamp(self.south() - vertical as f64, MIN_LAT, MAX_LAT);
         let new_max_lat = clamp(self.north() + vertical as f64, MIN_LAT, MAX_LAT);
-        let new_min_lon = clamp(self.west() - horizontal as f64, MIN_LON, MAX_LON);
-        let new_max_lon = clamp(self.east() + horizontal as f64, MIN_LON, MAX_LON);
+        let (new_min_lon, new_max_lon) = if self.span_lon() + 2.0 * horizontal >= 360.0 {
+            (MIN_LON, MAX_LON)
+        } else {
+            (
+                wrap_lon(self.min.longitude() - Degrees(horizontal)).value(),
+                wrap_lon(self.max.longitude() + Degrees(horizontal)).value(),
+            )
+        };
 
         GeoBounds {
             min: Geographic::new(new_min_lat, new_min_lon, self.floor()),
@@ -85,8 +130,8 @@ impl GeoBounds {
 
         let mut new_min_lat = self.south() + vertical as f64;
         let mut new_max_lat = self.north() - vertical as f64;
-        let mut new_min_lon = self.west() + horizontal as f64;
-        let mut new_max_lon = self.east() - horizontal as f64;
+        let mut new_min_lon = wrap_lon(self.min.longitude() + Degrees(horizontal)).value();
+        let mut new_max_lon = wrap_lon(self.max.longitude() - Degrees(horizontal)).value();
 
         let center = self.center();
 
@@ -95,7 +140,7 @@ impl GeoBounds {
             new_min_lat = center.lat();
         }
 
-        if new_min_lon > new_max_lon {
+        if 2.0 * horizontal > self.span_lon() {
             new_max_lon = center.lon();
             new_min_lon = center.lon();
         }
@@ -139,22 +184,24 @@ impl GeoBounds {
         self.sample(0.5f64, 0.5f64, 0.5f64)
     }
 
-    /// Returns the western edge (or minimal longitude) of this bounds
+    /// Returns the western edge (in degrees) of this bounds. It is the minimal
+    /// longitude, unless the bounds cross the antimeridian
     pub fn west(&self) -> f64 {
         self.min.lon()
     }
 
-    /// Returns the eastern edge (or maximal longitude) of this bounds
+    /// Returns the eastern edge (in degrees) of this bounds. It is the maximal
+    /// longitude, unless the bounds cross the antimeridian
     pub fn east(&self) -> f64 {
         self.max.lon()
     }
 
-    /// Returns the southern edge (or minimal latitude) of this bounds
+    /// Returns the southern edge (or minimal latitude, in degrees) of this bounds
     pub fn south(&self) -> f64 {
         self.min.lat()
     }
 
-    /// Returns the northern edge (or maximal latitude) of this bounds
+    /// Returns the northern edge (or maximal latitude, in degrees) of this bounds
     pub fn north(&self) -> f64 {
         self.max.lat()
     }
@@ -170,9 +217,14 @@ impl GeoBounds {
     }
 
     /// Returns the longitude span (in degrees) of this bounds. That is, the
-    /// difference between the `east` and `west` values.
+    /// difference between the `east` and `west` values, plus a full turn
+    /// if the bounds cross the antimeridian.
     pub fn span_lon(&self) -> f64 {
-        self.east() - self.west()
+        if self.crosses_antimeridian() {
+            self.east() - self.west() + 360.0
+        } else {
+            self.east() - self.west()
+        }
     }
 
     /// Returns the latitude span (in degrees) of this bounds. That is, the
@@ -187,10 +239,44 @@ impl GeoBounds {
         self.top() - self.floor()
     }
 
+    /// Returns true if the bounds cross the antimeridian, i.e its western edge
+    /// is greater than its eastern edge.
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.west() > self.east()
+    }
+
+    /// Splits the bounds along the antimeridian. If the bounds cross it, returns
+    /// the western part (from `west` to `MAX_LON`) and the eastern part (from `MIN_LON`
+    /// to `east`). Otherwise, returns the bounds unchanged and `None`.
+    pub fn split_at_antimeridian(&self) -> (GeoBounds, Option<GeoBounds>) {
+        if !self.crosses_antimeridian() {
+            return (*self, None);
+        }
+
+        let western = GeoBounds {
+            min: self.min,
+            max: Geographic::new(self.north(), MAX_LON, self.top()),
+        };
+        let eastern = GeoBounds {
+            min: Geographic::new(self.south(), MIN_LON, self.floor()),
+            max: self.max,
+        };
+
+        (western, Some(eastern))
+    }
+
     /// Returns true if the two bounds intersect.
     pub fn intersects(&self, other: &GeoBounds) -> bool {
-        (self.west() < other.east())
-            && (self.east() > other.west())
+        let (a, a2) = self.split_at_antimeridian();
+        let (b, b2) = other.split_at_antimeridian();
+        let overlap = |a: &GeoBounds, b: &GeoBounds| a.west() < b.east() && a.east() > b.west();
+
+        let lon_overlap = [Some(a), a2]
+            .iter()
+            .flatten()
+            .any(|a| [Some(b), b2].iter().flatten().any(|b| overlap(a, b)));
+
+        lon_overlap
             && (self.south() < other.north())
             && (self.north() > other.south())
             && (self.floor() < other.top())
@@ -199,10 +285,14 @@ impl GeoBounds {
 
     /// Returns true if the coordinate is contained in this bounds, including its borders.
     pub fn contains(&self, v: Geographic) -> bool {
-        v.lat() <= self.north()
-            && v.lon() <= self.east()
+        let lon = if self.crosses_antimeridian() {
+            v.lon() >= self.west() || v.lon() <= self.east()
+        } else {
+            v.lon() >= self.west() && v.lon() <= self.east()
+        };
+
+        lon && v.lat() <= self.north()
             && v.lat() >= self.south()
-            && v.lon() >= self.west()
             && v.elevation() >= self.floor()
             && v.elevation() <= self.top()
     }
@@ -210,12 +300,176 @@ impl GeoBounds {
     /// Returns the geographic coordinate sampled at the given (x, y, z) coordinates.
     /// The given values must be normalized (e.g each value must be in the [0, 1] range)
     pub fn sample(&self, x: f64, y: f64, z: f64) -> Geographic {
-        let lon = self.west() + self.span_lon() * x;
+        let lon = wrap_lon(self.min.longitude() + Degrees(self.span_lon() * x)).value();
         let lat = self.south() + self.span_lat() * y;
         let alt = self.floor() + self.height() * z;
 
         Geographic::new(lat, lon, alt)
     }
+
+    /// Returns the quarter of this bounds in the quadrant. Bounds are divided at the
+    /// middle of their longitude and latitude spans. Altitudes are untouched.
+    pub fn quadrant(&self, quadrant: Quadrant) -> GeoBounds {
+        match quadrant {
+            Quadrant::NorthWest => self.cell(0, 1, 2, 2),
+            Quadrant::NorthEast => self.cell(1, 1, 2, 2),
+            Quadrant::SouthWest => self.cell(0, 0, 2, 2),
+            Quadrant::SouthEast => self.cell(1, 0, 2, 2),
+        }
+    }
+
+    /// Returns the four quarters of this bounds, ordered as in `Quadrant::ALL`.
+    /// The quarters cover this bounds exactly, and only share their edges.
+    pub fn subdivide(&self) -> [GeoBounds; 4] {
+        [
+            self.quadrant(Quadrant::NorthWest),
+            self.quadrant(Quadrant::NorthEast),
+            self.quadrant(Quadrant::SouthWest),
+            self.quadrant(Quadrant::SouthEast),
+        ]
+    }
+
+    /// Returns the quadrant of this bounds in which the child lies, or `None` if
+    /// the child is outside of this bounds. The child is expected to lie in a single
+    /// quadrant, as the cells of `quadrant` and `split` (with even counts) do.
+    /// Otherwise, the quadrant that contains the center of the child is returned.
+    pub fn quadrant_of(&self, child: &GeoBounds) -> Option<Quadrant> {
+        let center = child.center();
+        if !self.contains(Geographic::new(center.lat(), center.lon(), self.floor())) {
+            return None;
+        }
+
+        let east = eastward(self.min.longitude(), center.longitude()).value()
+            >= self.span_lon() / 2.0;
+        let north = center.lat() >= self.south() + self.span_lat() / 2.0;
+
+        Some(match (north, east) {
+            (true, false) => Quadrant::NorthWest,
+            (true, true) => Quadrant::NorthEast,
+            (false, false) => Quadrant::SouthWest,
+            (false, true) => Quadrant::SouthEast,
+        })
+    }
+
+    /// Returns an iterator over the cells of a regular grid of `nx` columns and
+    /// `ny` rows covering this bounds. Cells are laid out row after row, from west
+    /// to east, then south to north, as in `grid`. The cells cover this bounds
+    /// exactly, and neighbouring cells share the same edges.
+    pub fn split(&self, nx: usize, ny: usize) -> Split {
+        assert!(nx > 0);
+        assert!(ny > 0);
+
+        Split {
+            bounds: *self,
+            nx,
+            ny,
+            index: 0,
+        }
+    }
+
+    /// Returns the cell at the column and row of a regular grid of `nx` columns and `ny` rows.
+    /// Edges are computed from their index only, so that neighbouring cells share them exactly.
+    fn cell(&self, column: usize, row: usize, nx: usize, ny: usize) -> GeoBounds {
+        let lon = |i: usize| match i {
+            0 => self.west(),
+            i if i == nx => self.east(),
+            i => {
+                let offset = Degrees(self.span_lon() * i as f64 / nx as f64);
+                wrap_lon(self.min.longitude() + offset).value()
+            }
+        };
+        let lat = |i: usize| match i {
+            0 => self.south(),
+            i if i == ny => self.north(),
+            i => self.south() + self.span_lat() * i as f64 / ny as f64,
+        };
+
+        // A cell that starts on the antimeridian does not cross it.
+        let west = match lon(column) {
+            w if column > 0 && w == MAX_LON => MIN_LON,
+            w => w,
+        };
+
+        GeoBounds {
+            min: Geographic::new(lat(row), west, self.floor()),
+            max: Geographic::new(lat(row + 1), lon(column + 1), self.top()),
+        }
+    }
+}
+
+/// An iterator over the cells of a regular grid covering geographic bounds.
+/// See `GeoBounds::split`.
+#[derive(Debug, Clone)]
+pub struct Split {
+    bounds: GeoBounds,
+    nx: usize,
+    ny: usize,
+    index: usize,
+}
+
+impl Iterator for Split {
+    type Item = GeoBounds;
+
+    fn next(&mut self) -> Option<GeoBounds> {
+        if self.index >= self.nx * self.ny {
+            return None;
+        }
+
+        let (column, row) = (self.index % self.nx, self.index / self.nx);
+        self.index += 1;
+
+        Some(self.bounds.cell(column, row, self.nx, self.ny))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.nx * self.ny - self.index;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Split {}
+
+/// Wraps a longitude that went past the antimeridian back into the valid range.
+fn wrap_lon(lon: Degrees) -> Degrees {
+    if lon.value() > MAX_LON {
+        lon - Degrees(360.0)
+    } else if lon.value() < MIN_LON {
+        lon + Degrees(360.0)
+    } else {
+        lon
+    }
+}
+
+/// Returns the angle from the longitude `from` eastward to the longitude `to`,
+/// in the [0, 360) range.
+fn eastward(from: Degrees, to: Degrees) -> Degrees {
+    Degrees((to - from).value().rem_euclid(360.0))
+}
+
+/// Deserializes bounds, whose corners must be valid coordinates. The southern edge must not
+/// be above the northern edge, nor the floor above the top. The western edge may be greater
+/// than the eastern edge, for bounds that cross the antimeridian.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for GeoBounds {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "GeoBounds", deny_unknown_fields)]
+        struct Fields {
+            min: Geographic,
+            max: Geographic,
+        }
+
+        let f = Fields::deserialize(deserializer)?;
+        if f.min.lat() > f.max.lat() {
+            return Err(de::Error::custom("the southern edge is above the northern edge"));
+        }
+        if f.min.elevation() > f.max.elevation() {
+            return Err(de::Error::custom("the floor is above the top"));
+        }
+
+        Ok(GeoBounds::new(f.min, f.max))
+    }
 }
 
 impl Arbitrary for GeoBounds {
@@ -357,6 +611,192 @@ mod test {
         );
     }
 
+    /// Returns the bounds with the same edges, but swapped western and eastern edges,
+    /// so that the result crosses the antimeridian.
+    fn wrap(g: GeoBounds) -> GeoBounds {
+        GeoBounds::new(
+            Geographic::new(g.south(), g.east(), g.floor()),
+            Geographic::new(g.north(), g.west(), g.top()),
+        )
+    }
+
+    #[test]
+    fn span_lon_across_the_antimeridian() {
+        let fiji = GeoBounds::new(
+            Geographic::new(-21.0, 176.0, 0.0),
+            Geographic::new(-12.0, -178.0, 0.0),
+        );
+
+        assert!(fiji.crosses_antimeridian());
+        assert_abs_diff_eq!(fiji.span_lon(), 6.0);
+        assert_abs_diff_eq!(fiji.center(), Geographic::new(-16.5, 179.0, 0.0));
+        assert!(fiji.contains(Geographic::new(-17.0, 178.4, 0.0)));
+        assert!(fiji.contains(Geographic::new(-16.0, -179.9, 0.0)));
+        assert!(!fiji.contains(Geographic::new(-17.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn split_at_antimeridian_returns_correct_values() {
+        let b = GeoBounds::new(
+            Geographic::new(-10.0, 170.0, 0.0),
+            Geographic::new(10.0, -160.0, 0.0),
+        );
+
+        let (western, eastern) = b.split_at_antimeridian();
+        let eastern = eastern.unwrap();
+
+        assert_abs_diff_eq!(western.west(), 170.0);
+        assert_abs_diff_eq!(western.east(), MAX_LON);
+        assert_abs_diff_eq!(eastern.west(), MIN_LON);
+        assert_abs_diff_eq!(eastern.east(), -160.0);
+
+        let world = GeoBounds::world();
+        assert_eq!(world.split_at_antimeridian(), (world, None));
+    }
+
+    #[test]
+    fn expand_across_the_antimeridian_returns_the_smallest_union() {
+        let east = GeoBounds::new(
+            Geographic::new(0.0, 170.0, 0.0),
+            Geographic::new(10.0, 175.0, 0.0),
+        );
+        let wrapped = GeoBounds::new(
+            Geographic::new(-10.0, 178.0, 0.0),
+            Geographic::new(0.0, -170.0, 0.0),
+        );
+
+        let result = east.expand(&wrapped);
+
+        assert_abs_diff_eq!(result.west(), 170.0);
+        assert_abs_diff_eq!(result.east(), -170.0);
+        assert_abs_diff_eq!(result.span_lon(), 20.0);
+        assert_eq!(result, wrapped.expand(&east));
+    }
+
+    #[test]
+    fn intersects_across_the_antimeridian() {
+        let wrapped = GeoBounds::new(
+            Geographic::new(-10.0, 170.0, 0.0),
+            Geographic::new(10.0, -170.0, 1.0),
+        );
+        let east = GeoBounds::new(
+            Geographic::new(-5.0, -175.0, 0.0),
+            Geographic::new(5.0, -160.0, 1.0),
+        );
+        let greenwich = GeoBounds::new(
+            Geographic::new(-5.0, -5.0, 0.0),
+            Geographic::new(5.0, 5.0, 1.0),
+        );
+
+        assert!(wrapped.intersects(&east));
+        assert!(east.intersects(&wrapped));
+        assert!(!wrapped.intersects(&greenwich));
+        assert!(!greenwich.intersects(&wrapped));
+    }
+
+    #[quickcheck]
+    fn wrapped_bounds_contain_their_corners(g: GeoBounds) -> bool {
+        let g = wrap(g);
+
+        g.contains(Geographic::new(g.south(), g.west(), g.floor()))
+            && g.contains(Geographic::new(g.north(), g.west(), g.top()))
+            && g.contains(Geographic::new(g.south(), g.east(), g.floor()))
+            && g.contains(Geographic::new(g.north(), g.east(), g.top()))
+    }
+
+    #[quickcheck]
+    fn wrapped_bounds_contain_their_samples(g: GeoBounds) -> bool {
+        let g = wrap(g);
+        let mut rng = rand::thread_rng();
+        let x = rng.gen_range(0.0f64, 1.0f64);
+        let y = rng.gen_range(0.0f64, 1.0f64);
+        let z = rng.gen_range(0.0f64, 1.0f64);
+
+        g.contains(g.sample(x, y, z))
+    }
+
+    #[quickcheck]
+    fn split_at_antimeridian_preserves_the_span(g: GeoBounds) -> bool {
+        let g = wrap(g);
+
+        match g.split_at_antimeridian() {
+            (western, Some(eastern)) => {
+                !western.crosses_antimeridian()
+                    && !eastern.crosses_antimeridian()
+                    && abs_diff_eq!(
+                        western.span_lon() + eastern.span_lon(),
+                        g.span_lon(),
+                        epsilon = 1e-9
+                    )
+            }
+            (bounds, None) => bounds == g && !g.crosses_antimeridian(),
+        }
+    }
+
+    #[quickcheck]
+    fn split_parts_contain_the_same_points(g: GeoBounds, p: Geographic) -> bool {
+        let (g, p) = (wrap(g).flatten(), p.flatten());
+        let (western, eastern) = g.split_at_antimeridian();
+        let in_parts = western.contains(p) || eastern.iter().any(|e| e.contains(p));
+
+        in_parts == g.contains(p)
+    }
+
+    #[quickcheck]
+    fn expand_contains_both_bounds(a: GeoBounds, b: GeoBounds, wrap_a: bool, wrap_b: bool) -> bool {
+        let a = if wrap_a { wrap(a) } else { a };
+        let b = if wrap_b { wrap(b) } else { b };
+        let result = a.expand(&b);
+
+        // Slightly grow the result to account for rounding errors.
+        let result = result.grow(1e-9, 1e-9);
+
+        result.contains(Geographic::new(a.south(), a.west(), a.floor()))
+            && result.contains(a.center())
+            && result.contains(b.center())
+            && result.contains(Geographic::new(b.north(), b.east(), b.top()))
+            && result.span_lon() <= a.span_lon() + b.span_lon() + 360.0
+    }
+
+    #[quickcheck]
+    fn intersects_is_symmetric(a: GeoBounds, b: GeoBounds, wrap_a: bool, wrap_b: bool) -> bool {
+        let a = if wrap_a { wrap(a) } else { a };
+        let b = if wrap_b { wrap(b) } else { b };
+
+        a.intersects(&b) == b.intersects(&a)
+    }
+
+    #[quickcheck]
+    fn shrink_then_grow_wrapped_bounds_produces_the_same_bounds(g: GeoBounds) -> bool {
+        let g = wrap(g);
+
+        let x = g.span_lon() * 0.3;
+        let y = g.span_lat() * 0.3;
+
+        let result = g.shrink(x, y).grow(x, y);
+
+        abs_diff_eq!(result.min, g.min, epsilon = 0.001)
+            && abs_diff_eq!(result.span_lon(), g.span_lon(), epsilon = 0.001)
+    }
+
+    #[quickcheck]
+    fn all_points_from_grid_of_wrapped_bounds_are_contained_in_the_bounds(
+        r: GeoBounds,
+        x_count: u8,
+        y_count: u8,
+    ) -> bool {
+        let r = wrap(r);
+        let x_count = (x_count as usize) + 2;
+        let y_count = (y_count as usize) + 2;
+        let mut array = vec![Geographic::default(); x_count * y_count];
+
+        r.grid(&mut array[..], x_count, y_count);
+
+        let r1 = r.grow(r.span_lon() * 0.00001, r.span_lat() * 0.00001);
+
+        array.iter().all(|p| r1.contains(*p))
+    }
+
     #[quickcheck]
     fn all_points_from_grid_are_contained_in_the_bounds(
         r: GeoBounds,
@@ -379,4 +819,172 @@ mod test {
 
         true
     }
+
+    #[cfg(feature = "serde")]
+    #[quickcheck]
+    fn deserialize_is_the_inverse_of_serialize(b: GeoBounds) -> bool {
+        let json = serde_json::to_string(&b).unwrap();
+
+        serde_json::from_str::<GeoBounds>(&json).unwrap() == b
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_validates_edges() {
+        let parse = |s| serde_json::from_str::<GeoBounds>(s);
+        let crossing = parse(
+            r#"{"min": {"latitude": -10.0, "longitude": 170.0},
+                "max": {"latitude": 10.0, "longitude": -170.0}}"#,
+        );
+        let inverted = parse(
+            r#"{"min": {"latitude": 10.0, "longitude": 0.0},
+                "max": {"latitude": -10.0, "longitude": 1.0}}"#,
+        );
+
+        assert!(crossing.unwrap().crosses_antimeridian());
+        assert!(inverted.is_err());
+    }
+
+    /// Returns true if every point of the bounds lies in exactly one of the cells,
+    /// ignoring their edges, and if the outer edges of the cells are those of the bounds.
+    fn tile_exactly(g: &GeoBounds, cells: &[GeoBounds]) -> bool {
+        let mut rng = rand::thread_rng();
+        let p = g.sample(rng.gen(), rng.gen(), 0.5);
+        let containing = cells.iter().filter(|c| c.contains(p)).count();
+        let span_lon: f64 = cells.iter().map(|c| c.span_lon()).sum();
+        let span_lat: f64 = cells.iter().map(|c| c.span_lat()).sum();
+        let area = g.span_lon() * g.span_lat();
+
+        containing == 1
+            && cells
+                .iter()
+                .any(|c| c.west() == g.west() && c.south() == g.south())
+            && cells
+                .iter()
+                .any(|c| c.east() == g.east() && c.north() == g.north())
+            && abs_diff_eq!(
+                cells
+                    .iter()
+                    .map(|c| c.span_lon() * c.span_lat())
+                    .sum::<f64>(),
+                area,
+                epsilon = 1e-6
+            )
+            && span_lon > 0.0
+            && span_lat >= 0.0
+    }
+
+    #[test]
+    fn subdivide_returns_the_quarters() {
+        let g = GeoBounds::new(
+            Geographic::new(-10.0, 170.0, 0.0),
+            Geographic::new(10.0, -170.0, 100.0),
+        );
+        let [nw, ne, sw, se] = g.subdivide();
+
+        assert_eq!(
+            nw,
+            GeoBounds::new(
+                Geographic::new(0.0, 170.0, 0.0),
+                Geographic::new(10.0, 180.0, 100.0)
+            )
+        );
+        assert_eq!(
+            ne,
+            GeoBounds::new(
+                Geographic::new(0.0, -180.0, 0.0),
+                Geographic::new(10.0, -170.0, 100.0)
+            )
+        );
+        assert_eq!(sw, g.quadrant(Quadrant::SouthWest));
+        assert_eq!((se.west(), se.south(), se.north()), (-180.0, -10.0, 0.0));
+        assert!(!ne.crosses_antimeridian());
+    }
+
+    #[test]
+    fn split_returns_cells_from_the_south_west() {
+        let g = GeoBounds::new(
+            Geographic::new(0.0, 0.0, 0.0),
+            Geographic::new(20.0, 30.0, 0.0),
+        );
+        let cells: Vec<GeoBounds> = g.split(3, 2).collect();
+
+        assert_eq!(g.split(3, 2).len(), 6);
+        assert_eq!(cells.len(), 6);
+        assert_eq!(
+            cells[0],
+            GeoBounds::new(
+                Geographic::new(0.0, 0.0, 0.0),
+                Geographic::new(10.0, 10.0, 0.0)
+            )
+        );
+        assert_eq!(
+            cells[5],
+            GeoBounds::new(
+                Geographic::new(10.0, 20.0, 0.0),
+                Geographic::new(20.0, 30.0, 0.0)
+            )
+        );
+    }
+
+    #[quickcheck]
+    fn quarters_tile_the_bounds_exactly(g: GeoBounds, wrapped: bool) -> bool {
+        let g = if wrapped { wrap(g) } else { g };
+
+        // Degenerate bounds have no interior to tile.
+        g.span_lon() < 1e-6 || g.span_lat() < 1e-6 || tile_exactly(&g, &g.subdivide())
+    }
+
+    #[quickcheck]
+    fn quarters_share_their_edges(g: GeoBounds, wrapped: bool) -> bool {
+        let g = if wrapped { wrap(g) } else { g };
+        let [nw, ne, sw, se] = g.subdivide();
+        let same_meridian = |a: f64, b: f64| a == b || (a == MAX_LON && b == MIN_LON);
+
+        same_meridian(nw.east(), ne.west())
+            && same_meridian(sw.east(), se.west())
+            && nw.south() == sw.north()
+            && ne.south() == se.north()
+            && nw.east() == sw.east()
+    }
+
+    #[quickcheck]
+    fn cells_tile_the_bounds_exactly(g: GeoBounds, wrapped: bool, nx: u8, ny: u8) -> bool {
+        let g = if wrapped { wrap(g) } else { g };
+        let (nx, ny) = (usize::from(nx % 7) + 1, usize::from(ny % 7) + 1);
+        let cells: Vec<GeoBounds> = g.split(nx, ny).collect();
+
+        cells.len() == nx * ny
+            && (g.span_lon() < 1e-6 || g.span_lat() < 1e-6 || tile_exactly(&g, &cells))
+    }
+
+    #[quickcheck]
+    fn quadrant_of_returns_the_quadrant_of_the_child(g: GeoBounds, wrapped: bool) -> bool {
+        let g = if wrapped { wrap(g) } else { g };
+
+        g.span_lon() < 1e-6
+            || g.span_lat() < 1e-6
+            || Quadrant::ALL.iter().all(|&q| {
+                let child = g.quadrant(q);
+                g.quadrant_of(&child) == Some(q)
+                    && child
+                        .subdivide()
+                        .iter()
+                        .all(|grandchild| g.quadrant_of(grandchild) == Some(q))
+            })
+    }
+
+    #[test]
+    fn quadrant_of_returns_none_outside_of_the_bounds() {
+        let g = GeoBounds::new(
+            Geographic::new(0.0, 0.0, 0.0),
+            Geographic::new(10.0, 10.0, 0.0),
+        );
+        let outside = GeoBounds::new(
+            Geographic::new(0.0, 20.0, 0.0),
+            Geographic::new(10.0, 30.0, 0.0),
+        );
+
+        assert_eq!(g.quadrant_of(&outside), None);
+    }
 }
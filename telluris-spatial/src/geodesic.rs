@@ -0,0 +1,476 @@
+//! Geodesic computations: distances and azimuths between geographic coordinates.
+//! The ellipsoidal solutions use Vincenty's formulae, accurate to a fraction
+//! of a millimeter on the earth ellipsoid. The spherical haversine formula is
+//! much cheaper, but its error reaches about 0.5% of the distance.
+//! Azimuths are expressed in degrees, clockwise from the north, in the [-180, 180] range.
+//! Elevations are ignored.
+
+use crate::angle::{Degrees, Radians};
+use crate::ellipsoid::Ellipsoid;
+use crate::geographic::*;
+use std::f64::consts::PI;
+
+/// The convergence threshold of the iterative solutions, in radians.
+const EPSILON: f64 = 1e-12;
+
+/// The maximal number of iterations of the iterative solutions.
+const MAX_ITERATIONS: usize = 200;
+
+/// The solution of the inverse geodesic problem.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InverseSolution {
+    /// The length of the geodesic, in meters.
+    pub distance: f64,
+    /// The azimuth of the geodesic at the start point, in degrees.
+    pub initial_azimuth: f64,
+    /// The azimuth of the geodesic at the end point, in degrees.
+    pub final_azimuth: f64,
+}
+
+impl InverseSolution {
+    /// Returns the azimuth from the end point back to the start point, in degrees.
+    pub fn back_azimuth(&self) -> f64 {
+        normalize_azimuth(self.final_azimuth + 180.0)
+    }
+}
+
+/// The solution of the direct geodesic problem.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DirectSolution {
+    /// The end point of the geodesic, at the elevation of the start point.
+    pub destination: Geographic,
+    /// The azimuth of the geodesic at the end point, in degrees.
+    pub final_azimuth: f64,
+}
+
+/// Solves geodesic problems on an ellipsoid.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Geodesic {
+    ellipsoid: Ellipsoid,
+}
+
+impl Geodesic {
+    /// Creates a solver on the provided ellipsoid.
+    pub fn new(ellipsoid: Ellipsoid) -> Self {
+        Geodesic { ellipsoid }
+    }
+
+    /// Returns the ellipsoid of this solver.
+    pub fn ellipsoid(&self) -> &Ellipsoid {
+        &self.ellipsoid
+    }
+
+    /// Solves the inverse problem: returns the distance between the two points
+    /// and the azimuths at both ends of the geodesic.
+    /// Returns `None` when the solution does not converge, which only happens
+    /// for nearly antipodal points.
+    pub fn inverse(&self, from: Geographic, to: Geographic) -> Option<InverseSolution> {
+        // Implementation taken from "Direct and inverse solutions of geodesics
+        // on the ellipsoid with application of nested equations", by T. Vincenty.
+
+        let a = self.ellipsoid.semi_major_axis();
+        let b = self.ellipsoid.semi_minor_axis();
+        let f = self.ellipsoid.flattening();
+
+        let l = (to.lon_rad() - from.lon_rad()).normalize().value();
+        let (sin_u1, cos_u1) = reduced_latitude(from.lat_rad(), f).sin_cos();
+        let (sin_u2, cos_u2) = reduced_latitude(to.lat_rad(), f).sin_cos();
+
+        let mut lambda = l;
+        let mut iterations = 0;
+
+        let (sin_sigma, cos_sigma, sigma, cos_sq_alpha, cos_2sigma_m, sin_lambda, cos_lambda) = loop {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+
+            if sin_sigma == 0.0 {
+                // Coincident points.
+                return Some(InverseSolution {
+                    distance: 0.0,
+                    initial_azimuth: 0.0,
+                    final_azimuth: 0.0,
+                });
+            }
+
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+
+            // On the equator, cos_sq_alpha is zero and cos_2sigma_m is undefined.
+            let cos_2sigma_m = if cos_sq_alpha != 0.0 {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+            } else {
+                0.0
+            };
+
+            let c = f / 16.0 * cos_sq_alpha * (4.0 + f * (4.0 - 3.0 * cos_sq_alpha));
+            let previous = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * f
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m
+                                + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+            iterations += 1;
+            if lambda.abs() > PI || iterations > MAX_ITERATIONS {
+                return None;
+            }
+
+            if (lambda - previous).abs() < EPSILON {
+                break (
+                    sin_sigma,
+                    cos_sigma,
+                    sigma,
+                    cos_sq_alpha,
+                    cos_2sigma_m,
+                    sin_lambda,
+                    cos_lambda,
+                );
+            }
+        };
+
+        let u_sq = cos_sq_alpha * (a * a - b * b) / (b * b);
+        let (big_a, big_b) = series_coefficients(u_sq);
+        let delta_sigma = delta_sigma(big_b, sin_sigma, cos_sigma, cos_2sigma_m);
+
+        let distance = b * big_a * (sigma - delta_sigma);
+
+        let alpha1 = Radians::atan2(
+            cos_u2 * sin_lambda,
+            cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda,
+        );
+        let alpha2 = Radians::atan2(
+            cos_u1 * sin_lambda,
+            -sin_u1 * cos_u2 + cos_u1 * sin_u2 * cos_lambda,
+        );
+
+        Some(InverseSolution {
+            distance,
+            initial_azimuth: alpha1.to_degrees().value(),
+            final_azimuth: alpha2.to_degrees().value(),
+        })
+    }
+
+    /// Solves the direct problem: returns the point reached by following the geodesic
+    /// starting at `from` with the given azimuth (in degrees), for `distance` meters.
+    pub fn direct(&self, from: Geographic, azimuth: f64, distance: f64) -> DirectSolution {
+        // Implementation taken from "Direct and inverse solutions of geodesics
+        // on the ellipsoid with application of nested equations", by T. Vincenty.
+
+        let a = self.ellipsoid.semi_major_axis();
+        let b = self.ellipsoid.semi_minor_axis();
+        let f = self.ellipsoid.flattening();
+
+        let (sin_alpha1, cos_alpha1) = Degrees(azimuth).to_radians().sin_cos();
+        let u1 = reduced_latitude(from.lat_rad(), f);
+        let (sin_u1, cos_u1) = u1.sin_cos();
+
+        let sigma1 = sin_u1.atan2(cos_u1 * cos_alpha1);
+        let sin_alpha = cos_u1 * sin_alpha1;
+        let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+        let u_sq = cos_sq_alpha * (a * a - b * b) / (b * b);
+        let (big_a, big_b) = series_coefficients(u_sq);
+
+        let mut sigma = distance / (b * big_a);
+        let (mut sin_sigma, mut cos_sigma, mut cos_2sigma_m);
+        let mut iterations = 0;
+
+        loop {
+            cos_2sigma_m = (2.0 * sigma1 + sigma).cos();
+            sin_sigma = sigma.sin();
+            cos_sigma = sigma.cos();
+
+            let previous = sigma;
+            sigma = distance / (b * big_a) + delta_sigma(big_b, sin_sigma, cos_sigma, cos_2sigma_m);
+
+            iterations += 1;
+            if (sigma - previous).abs() < EPSILON || iterations > MAX_ITERATIONS {
+                break;
+            }
+        }
+
+        let (sin_sigma, cos_sigma) = sigma.sin_cos();
+        let cos_2sigma_m = (2.0 * sigma1 + sigma).cos();
+
+        let x = sin_u1 * sin_sigma - cos_u1 * cos_sigma * cos_alpha1;
+        let lat2 = Radians::atan2(
+            sin_u1 * cos_sigma + cos_u1 * sin_sigma * cos_alpha1,
+            (1.0 - f) * (sin_alpha * sin_alpha + x * x).sqrt(),
+        );
+        let lambda =
+            (sin_sigma * sin_alpha1).atan2(cos_u1 * cos_sigma - sin_u1 * sin_sigma * cos_alpha1);
+        let c = f / 16.0 * cos_sq_alpha * (4.0 + f * (4.0 - 3.0 * cos_sq_alpha));
+        let l = lambda
+            - (1.0 - c)
+                * f
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m
+                            + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+        let lon2 = (from.lon_rad() + Radians(l)).normalize();
+        let alpha2 = Radians::atan2(sin_alpha, -x);
+
+        DirectSolution {
+            destination: Geographic::from_radians(lat2, lon2, from.elevation()),
+            final_azimuth: alpha2.to_degrees().value(),
+        }
+    }
+}
+
+/// Returns the great-circle distance in meters between the two points,
+/// on a sphere of the given radius.
+pub fn haversine(from: Geographic, to: Geographic, radius: f64) -> f64 {
+    let lat1 = from.lat_rad();
+    let lat2 = to.lat_rad();
+    let d_lat = (lat2 - lat1).value();
+    let d_lon = (to.lon_rad() - from.lon_rad()).value();
+
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+
+    2.0 * radius * Radians::asin(a.sqrt()).value()
+}
+
+/// Returns the initial azimuth in degrees of the great circle from the start point
+/// to the end point, on a sphere.
+pub fn spherical_azimuth(from: Geographic, to: Geographic) -> f64 {
+    let lat1 = from.lat_rad();
+    let lat2 = to.lat_rad();
+    let d_lon = (to.lon_rad() - from.lon_rad()).value();
+
+    let y = d_lon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+
+    Radians::atan2(y, x).to_degrees().value()
+}
+
+/// Returns the reduced latitude (latitude on the auxiliary sphere).
+fn reduced_latitude(lat: Radians, f: f64) -> Radians {
+    Radians::atan((1.0 - f) * lat.tan())
+}
+
+/// Returns the `A` and `B` coefficients of Vincenty's series.
+fn series_coefficients(u_sq: f64) -> (f64, f64) {
+    let a = 1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+    let b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+    (a, b)
+}
+
+fn delta_sigma(b: f64, sin_sigma: f64, cos_sigma: f64, cos_2sigma_m: f64) -> f64 {
+    let c2 = cos_2sigma_m * cos_2sigma_m;
+    b * sin_sigma
+        * (cos_2sigma_m
+            + b / 4.0
+                * (cos_sigma * (-1.0 + 2.0 * c2)
+                    - b / 6.0
+                        * cos_2sigma_m
+                        * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                        * (-3.0 + 4.0 * c2)))
+}
+
+/// Wraps an azimuth in degrees in the [-180, 180] range.
+fn normalize_azimuth(azimuth: f64) -> f64 {
+    Degrees(azimuth)
+        .to_radians()
+        .normalize()
+        .to_degrees()
+        .value()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geodesic::*;
+
+    type Quad = (f64, f64, f64, f64);
+    type Triple = (f64, f64, f64);
+
+    /// Inverse problems on WGS 84, computed with GeographicLib:
+    /// (lat1, lon1, lat2, lon2) and (distance, initial azimuth, final azimuth).
+    const INVERSE_VECTORS: [(Quad, Triple); 6] = [
+        (
+            (40.64, -73.78, 1.36, 103.99),
+            (15_347_512.940_513, 3.305_773_478, 177.487_840_208),
+        ),
+        (
+            (-33.8568, 151.2153, 51.5074, -0.1278),
+            (16_988_648.656_495, -40.688_041_220, -119.663_707_009),
+        ),
+        (
+            (10.0, 20.0, 10.0, 20.000_001),
+            (0.109_639, 89.999_999_913, 90.000_000_087),
+        ),
+        (
+            (89.0, 0.0, -89.0, 90.0),
+            (19_845_981.636_939, 134.991_902_694, 134.991_902_694),
+        ),
+        ((0.0, 0.0, 0.0, 90.0), (10_018_754.171_395, 90.0, 90.0)),
+        (
+            (35.0, 135.0, 35.0, -60.0),
+            (12_100_398.166_456, 12.899_038_836, 167.100_961_164),
+        ),
+    ];
+
+    /// Direct problems on WGS 84, computed with GeographicLib:
+    /// (lat1, lon1, azimuth, distance) and (lat2, lon2, final azimuth).
+    const DIRECT_VECTORS: [(Quad, Triple); 3] = [
+        (
+            (40.64, -73.78, 3.0, 15_000_000.0),
+            (4.508_898_115, 104.071_637_647, 177.713_718_567),
+        ),
+        (
+            (-45.0, 170.0, 90.0, 2_000_000.0),
+            (-42.269_247_533, -165.403_264_386, 72.884_311_125),
+        ),
+        (
+            (0.0, 0.0, 45.0, 10_000_000.0),
+            (45.096_182_935, 89.868_408_537, 90.057_860_806),
+        ),
+    ];
+
+    #[test]
+    fn inverse_matches_reference_values() {
+        let g = Geodesic::default();
+
+        for ((lat1, lon1, lat2, lon2), (s12, azi1, azi2)) in INVERSE_VECTORS.iter() {
+            let from = Geographic::new(*lat1, *lon1, 0.0);
+            let to = Geographic::new(*lat2, *lon2, 0.0);
+            let result = g.inverse(from, to).unwrap();
+
+            assert_abs_diff_eq!(result.distance, *s12, epsilon = 0.001);
+            assert_abs_diff_eq!(result.initial_azimuth, *azi1, epsilon = 1e-6);
+            assert_abs_diff_eq!(result.final_azimuth, *azi2, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn direct_matches_reference_values() {
+        let g = Geodesic::default();
+
+        for ((lat1, lon1, azi1, s12), (lat2, lon2, azi2)) in DIRECT_VECTORS.iter() {
+            let result = g.direct(Geographic::new(*lat1, *lon1, 0.0), *azi1, *s12);
+
+            assert_abs_diff_eq!(result.destination.lat(), *lat2, epsilon = 1e-8);
+            assert_abs_diff_eq!(result.destination.lon(), *lon2, epsilon = 1e-8);
+            assert_abs_diff_eq!(result.final_azimuth, *azi2, epsilon = 1e-8);
+        }
+    }
+
+    #[test]
+    fn inverse_of_nearly_antipodal_points_returns_the_known_distance() {
+        let g = Geodesic::default();
+        let result = g
+            .inverse(
+                Geographic::new(0.0, 0.0, 0.0),
+                Geographic::new(0.5, 179.5, 0.0),
+            )
+            .unwrap();
+
+        // Vincenty's method converges slowly for this pair, whose distance
+        // is 19 936 288.579 meters according to GeographicLib.
+        assert_abs_diff_eq!(result.distance, 19_936_288.579, epsilon = 0.01);
+    }
+
+    #[test]
+    fn inverse_of_antipodal_points_on_the_equator_does_not_converge() {
+        let g = Geodesic::default();
+
+        assert!(g
+            .inverse(
+                Geographic::new(0.0, 0.0, 0.0),
+                Geographic::new(0.0, 179.7, 0.0)
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn back_azimuth_points_to_the_start() {
+        let g = Geodesic::default();
+        let result = g
+            .inverse(
+                Geographic::new(0.0, 0.0, 0.0),
+                Geographic::new(0.0, 90.0, 0.0),
+            )
+            .unwrap();
+
+        assert_abs_diff_eq!(result.back_azimuth(), -90.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn haversine_on_the_equator_returns_the_arc_length() {
+        let d = haversine(
+            Geographic::new(0.0, 0.0, 0.0),
+            Geographic::new(0.0, 90.0, 0.0),
+            MEAN_EARTH_RADIUS,
+        );
+
+        assert_abs_diff_eq!(d, MEAN_EARTH_RADIUS * PI / 2.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn spherical_azimuth_returns_correct_values() {
+        let origin = Geographic::new(0.0, 0.0, 0.0);
+
+        assert_abs_diff_eq!(
+            spherical_azimuth(origin, Geographic::new(10.0, 0.0, 0.0)),
+            0.0
+        );
+        assert_abs_diff_eq!(
+            spherical_azimuth(origin, Geographic::new(0.0, 10.0, 0.0)),
+            90.0
+        );
+        assert_abs_diff_eq!(
+            spherical_azimuth(origin, Geographic::new(0.0, -10.0, 0.0)),
+            -90.0
+        );
+    }
+
+    #[quickcheck]
+    fn direct_is_the_inverse_of_inverse(a: Geographic, b: Geographic) -> bool {
+        let g = Geodesic::default();
+        let (a, b) = (a.flatten(), b.flatten());
+
+        let inverse = match g.inverse(a, b) {
+            Some(i) => i,
+            None => return true,
+        };
+        let direct = g.direct(a, inverse.initial_azimuth, inverse.distance);
+
+        // Compare positions on the ground, since longitudes are meaningless at the poles.
+        let error = g
+            .inverse(direct.destination, b)
+            .map(|i| i.distance)
+            .unwrap_or(f64::MAX);
+
+        error < 0.001
+    }
+
+    #[quickcheck]
+    fn inverse_is_symmetric(a: Geographic, b: Geographic) -> bool {
+        let g = Geodesic::default();
+
+        match (g.inverse(a, b), g.inverse(b, a)) {
+            (Some(ab), Some(ba)) => abs_diff_eq!(ab.distance, ba.distance, epsilon = 0.001),
+            _ => true,
+        }
+    }
+
+    #[quickcheck]
+    fn haversine_is_close_to_the_ellipsoidal_distance(a: Geographic, b: Geographic) -> bool {
+        let g = Geodesic::default();
+
+        match g.inverse(a, b) {
+            Some(i) => {
+                (haversine(a, b, MEAN_EARTH_RADIUS) - i.distance).abs() <= i.distance * 0.006
+            }
+            None => true,
+        }
+    }
+}
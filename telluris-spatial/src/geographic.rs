@@ -1,7 +1,16 @@
+use crate::angle::{Degrees, Radians};
+use crate::datum::Datum;
+use crate::geodesic;
+use crate::geoid::Geoid;
+use crate::notation::{GeographicDisplay, Notation};
+use crate::route::{Densify, Route};
 use quickcheck::{Arbitrary, Gen};
 
 use rand::{self, Rng};
 
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize};
+
 #[cfg(test)]
 use approx::AbsDiffEq;
 
@@ -17,12 +26,23 @@ pub const MAX_LAT: f64 = 90.0;
 pub const MIN_ALT: f64 = -11_000.0;
 /// The maximal supported elevation, way above the geostationary orbit.
 pub const MAX_ALT: f64 = 50_000_000.0;
+/// The mean radius of the earth, in meters, as defined by the IUGG.
+pub const MEAN_EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// One of the two halves of the globe, on either side of the equator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Hemisphere {
+    North,
+    South,
+}
 
 /// Geographic coordinates, expressed in degrees for angles and meters for elevation.
 /// Represents angles to/from the equator for latitudes,
 /// angles to/from the reference meridian for longitudes,
 /// and meters above or below the ellipsoid for elevation.
 #[derive(Debug, Default, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Geographic {
     latitude: f64,
     longitude: f64,
@@ -46,6 +66,16 @@ impl Geographic {
         }
     }
 
+    /// Creates a geographic coordinate from angles expressed in radians.
+    /// Angles are clamped in the valid range, to absorb rounding errors of the conversion.
+    pub fn from_radians(lat: Radians, lon: Radians, elevation: f64) -> Geographic {
+        Self::new(
+            num::clamp(lat.to_degrees().value(), MIN_LAT, MAX_LAT),
+            num::clamp(lon.to_degrees().value(), MIN_LON, MAX_LON),
+            elevation,
+        )
+    }
+
     /// Returns the longitude in degrees of this coordinate.
     pub fn lon(&self) -> f64 {
         self.longitude
@@ -56,11 +86,40 @@ impl Geographic {
         self.latitude
     }
 
+    /// Returns the longitude of this coordinate.
+    pub fn longitude(&self) -> Degrees {
+        Degrees(self.longitude)
+    }
+
+    /// Returns the latitude of this coordinate.
+    pub fn latitude(&self) -> Degrees {
+        Degrees(self.latitude)
+    }
+
+    /// Returns the longitude in radians of this coordinate.
+    pub fn lon_rad(&self) -> Radians {
+        self.longitude().to_radians()
+    }
+
+    /// Returns the latitude in radians of this coordinate.
+    pub fn lat_rad(&self) -> Radians {
+        self.latitude().to_radians()
+    }
+
     /// Returns the elevation in meters of this coordinate.
     pub fn elevation(&self) -> f64 {
         self.elevation
     }
 
+    /// Returns the hemisphere of this coordinate. The equator belongs to the northern hemisphere.
+    pub fn hemisphere(&self) -> Hemisphere {
+        if self.latitude >= 0.0 {
+            Hemisphere::North
+        } else {
+            Hemisphere::South
+        }
+    }
+
     /// Returns a Geographic coordinate with elevation raised (or lowered) by
     /// the specified value. The new elevation is clamped in the domain specified
     /// by `MIN_ALT` and `MAX_ALT`.
@@ -72,6 +131,26 @@ impl Geographic {
         }
     }
 
+    /// Returns a Geographic coordinate whose elevation, interpreted as a height above
+    /// the geoid (mean sea level), is converted into a height above the ellipsoid.
+    /// As with `raise`, the new elevation is clamped.
+    pub fn to_ellipsoidal_height<G: Geoid + ?Sized>(self, geoid: &G) -> Self {
+        self.raise(geoid.undulation(self))
+    }
+
+    /// Returns a Geographic coordinate whose elevation, interpreted as a height above
+    /// the ellipsoid, is converted into a height above the geoid (mean sea level).
+    /// As with `raise`, the new elevation is clamped.
+    pub fn to_orthometric_height<G: Geoid + ?Sized>(self, geoid: &G) -> Self {
+        self.raise(-geoid.undulation(self))
+    }
+
+    /// Returns this coordinate, expressed in the `from` datum, converted to the `to` datum.
+    /// Elevations are clamped in the domain specified by `MIN_ALT` and `MAX_ALT`.
+    pub fn to_datum(self, from: &Datum, to: &Datum) -> Self {
+        from.convert(self, to)
+    }
+
     /// Returns a Geographic coordinate with elevation set at zero.
     pub fn flatten(self) -> Self {
         Geographic {
@@ -80,6 +159,81 @@ impl Geographic {
             elevation: 0.0,
         }
     }
+
+    /// Returns the great-circle distance in meters between this coordinate
+    /// and the other, on a sphere of radius `MEAN_EARTH_RADIUS`.
+    /// Elevations are ignored.
+    pub fn great_circle_distance(&self, other: &Geographic) -> f64 {
+        geodesic::haversine(*self, *other, MEAN_EARTH_RADIUS)
+    }
+
+    /// Returns the length in meters of the rhumb line (of constant azimuth) between
+    /// this coordinate and the other, on a sphere of radius `MEAN_EARTH_RADIUS`.
+    /// Elevations are ignored.
+    pub fn rhumb_distance(&self, other: &Geographic) -> f64 {
+        Route::Rhumb.distance(self, other)
+    }
+
+    /// Returns the point at the fraction `t` (in the [0, 1] range) of the great circle
+    /// from this coordinate to the other. Elevations are interpolated linearly.
+    pub fn interpolate_great_circle(&self, other: &Geographic, t: f64) -> Geographic {
+        Route::GreatCircle.interpolate(self, other, t)
+    }
+
+    /// Returns the point at the fraction `t` (in the [0, 1] range) of the rhumb line
+    /// from this coordinate to the other. Elevations are interpolated linearly.
+    pub fn interpolate_rhumb(&self, other: &Geographic, t: f64) -> Geographic {
+        Route::Rhumb.interpolate(self, other, t)
+    }
+
+    /// Returns an iterator over the points of the route from this coordinate to the other,
+    /// spaced by at most `step` meters, both ends included.
+    pub fn densify(&self, other: &Geographic, step: f64, route: Route) -> Densify {
+        Densify::new(*self, *other, step, route)
+    }
+
+    /// Returns an object that formats this coordinate in the specified notation.
+    pub fn display(&self, notation: Notation) -> GeographicDisplay {
+        GeographicDisplay::new(*self, notation)
+    }
+}
+
+/// Returns the value if it lies within `[min, max]`, or a deserialization error naming
+/// the field otherwise. NaN values are rejected.
+#[cfg(feature = "serde")]
+pub(crate) fn check_range<E: de::Error>(name: &str, value: f64, min: f64, max: f64) -> Result<f64, E> {
+    if (min..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(E::custom(format_args!(
+            "{} {} is outside of [{}, {}]",
+            name, value, min, max
+        )))
+    }
+}
+
+/// Deserializes a coordinate, whose values must lie within the `MIN_*` and `MAX_*` constants.
+/// The elevation is optional, and defaults to zero.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Geographic {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Geographic", deny_unknown_fields)]
+        struct Fields {
+            latitude: f64,
+            longitude: f64,
+            #[serde(default)]
+            elevation: f64,
+        }
+
+        let f = Fields::deserialize(deserializer)?;
+
+        Ok(Geographic {
+            latitude: check_range("latitude", f.latitude, MIN_LAT, MAX_LAT)?,
+            longitude: check_range("longitude", f.longitude, MIN_LON, MAX_LON)?,
+            elevation: check_range("elevation", f.elevation, MIN_ALT, MAX_ALT)?,
+        })
+    }
 }
 
 impl Arbitrary for Geographic {
@@ -134,4 +288,58 @@ mod test {
         let f = p.flatten();
         f.elevation() == 0.0 && f.lat() == p.lat() && f.lon() == p.lon()
     }
+
+    #[quickcheck]
+    fn from_radians_returns_the_same_coordinate(p: Geographic) -> bool {
+        let result = Geographic::from_radians(p.lat_rad(), p.lon_rad(), p.elevation());
+
+        abs_diff_eq!(result, p, epsilon = 1e-9)
+    }
+
+    #[test]
+    fn great_circle_distance_between_antipodes_is_half_the_circumference() {
+        let a = Geographic::new(0.0, 0.0, 0.0);
+        let b = Geographic::new(0.0, 180.0, 0.0);
+
+        assert_abs_diff_eq!(
+            a.great_circle_distance(&b),
+            std::f64::consts::PI * MEAN_EARTH_RADIUS,
+            epsilon = 0.001
+        );
+    }
+
+    #[quickcheck]
+    fn great_circle_distance_is_symmetric(a: Geographic, b: Geographic) -> bool {
+        abs_diff_eq!(
+            a.great_circle_distance(&b),
+            b.great_circle_distance(&a),
+            epsilon = 0.001
+        )
+    }
+
+    #[cfg(feature = "serde")]
+    #[quickcheck]
+    fn deserialize_is_the_inverse_of_serialize(p: Geographic) -> bool {
+        let json = serde_json::to_string(&p).unwrap();
+
+        serde_json::from_str::<Geographic>(&json).unwrap() == p
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_validates_ranges() {
+        let parse = |s| serde_json::from_str::<Geographic>(s);
+        let error = parse(r#"{"latitude": 91.0, "longitude": 2.0}"#).unwrap_err();
+
+        assert_eq!(
+            parse(r#"{"latitude": 48.8, "longitude": 2.3}"#).unwrap(),
+            Geographic::new(48.8, 2.3, 0.0)
+        );
+        assert!(error
+            .to_string()
+            .starts_with("latitude 91 is outside of [-90, 90]"));
+        assert!(parse(r#"{"latitude": 0.0, "longitude": -180.5}"#).is_err());
+        assert!(parse(r#"{"latitude": 0.0, "longitude": 0.0, "elevation": -12000.0}"#).is_err());
+        assert!(parse(r#"{"latitude": 0.0, "longitude": 0.0, "height": 0.0}"#).is_err());
+    }
 }
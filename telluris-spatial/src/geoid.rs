@@ -0,0 +1,381 @@
+//! Geoid models, to convert between heights above the ellipsoid (as returned by GNSS
+//! receivers, and as stored in `Geographic`) and heights above the mean sea level
+//! (as provided by most elevation datasets, such as SRTM).
+//! The geoid undulation `N` is the height of the geoid above the ellipsoid, so that
+//! the ellipsoidal height `h` and the orthometric height `H` are related by `h = H + N`.
+
+use crate::geographic::*;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Provides the height of the geoid above the ellipsoid.
+pub trait Geoid: fmt::Debug {
+    /// Returns the undulation of the geoid at the coordinate, in meters.
+    /// Elevations are ignored.
+    fn undulation(&self, geo: Geographic) -> f64;
+}
+
+/// A geoid model that is identical to the ellipsoid. Useful when heights are
+/// already ellipsoidal, or when the accuracy of the heights does not matter.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Ellipsoidal {}
+
+impl Geoid for Ellipsoidal {
+    fn undulation(&self, _geo: Geographic) -> f64 {
+        0.0
+    }
+}
+
+/// The error returned when reading a geoid grid fails.
+#[derive(Debug)]
+pub enum GeoidError {
+    /// The grid could not be read.
+    Io(io::Error),
+    /// The header is missing or does not describe a valid grid.
+    InvalidHeader,
+    /// A value of the grid is not a number.
+    InvalidValue(String),
+    /// The grid does not have the number of values described by its header.
+    InvalidSize { expected: usize, actual: usize },
+}
+
+impl fmt::Display for GeoidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GeoidError::Io(e) => write!(f, "cannot read the geoid grid: {}", e),
+            GeoidError::InvalidHeader => write!(f, "invalid geoid grid header"),
+            GeoidError::InvalidValue(v) => write!(f, "invalid geoid grid value: '{}'", v),
+            GeoidError::InvalidSize { expected, actual } => write!(
+                f,
+                "invalid geoid grid size: expected {} values, got {}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl Error for GeoidError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GeoidError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GeoidError {
+    fn from(e: io::Error) -> Self {
+        GeoidError::Io(e)
+    }
+}
+
+/// A geoid model defined by undulations sampled on a regular grid of latitudes
+/// and longitudes, such as the EGM96 model. Undulations are bilinearly interpolated
+/// between the nodes of the grid. Positions outside of the grid use the undulations
+/// of the nearest edge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoidGrid {
+    north: f64,
+    west: f64,
+    lat_spacing: f64,
+    lon_spacing: f64,
+    rows: usize,
+    columns: usize,
+    // Undulations, in meters, row by row from north to south, then from west to east.
+    undulations: Vec<f32>,
+}
+
+impl GeoidGrid {
+    /// Creates a grid whose north-western node is at the specified latitude and longitude,
+    /// with the specified spacing between nodes, in degrees. Undulations are stored row
+    /// by row, from north to south, then from west to east within each row.
+    /// Longitudes of the grid may extend beyond 180°, such as the [0°, 360°] range of EGM96.
+    pub fn new(
+        north: f64,
+        west: f64,
+        lat_spacing: f64,
+        lon_spacing: f64,
+        columns: usize,
+        undulations: Vec<f32>,
+    ) -> Self {
+        assert!(lat_spacing > 0.0 && lon_spacing > 0.0);
+        assert!(columns >= 2, "a geoid grid needs at least two columns");
+
+        let rows = undulations.len() / columns;
+        assert!(
+            rows >= 2 && rows * columns == undulations.len(),
+            "a geoid grid needs at least two complete rows"
+        );
+
+        GeoidGrid {
+            north,
+            west,
+            lat_spacing,
+            lon_spacing,
+            rows,
+            columns,
+            undulations,
+        }
+    }
+
+    /// Reads a grid in the ASCII format of the NGA, such as the `WW15MGH.GRD` file of
+    /// the EGM96 model. The header is made of the south, north, west and east limits
+    /// of the grid, followed by the latitude and longitude spacing, in degrees.
+    /// Values follow the header, row by row from north to south, then from west to east.
+    pub fn from_grd<R: Read>(reader: R) -> Result<Self, GeoidError> {
+        let mut lines = BufReader::new(reader).lines();
+
+        let header = loop {
+            match lines.next() {
+                Some(line) => {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        break line;
+                    }
+                }
+                None => return Err(GeoidError::InvalidHeader),
+            }
+        };
+
+        let header: Vec<f64> = header
+            .split_whitespace()
+            .map(|v| v.parse().map_err(|_| GeoidError::InvalidHeader))
+            .collect::<Result<_, _>>()?;
+
+        let (south, north, west, east, lat_spacing, lon_spacing) = match header.as_slice() {
+            [s, n, w, e, dlat, dlon] => (*s, *n, *w, *e, *dlat, *dlon),
+            _ => return Err(GeoidError::InvalidHeader),
+        };
+
+        if header.iter().any(|v| !v.is_finite())
+            || lat_spacing <= 0.0
+            || lon_spacing <= 0.0
+            || north <= south
+            || east <= west
+        {
+            return Err(GeoidError::InvalidHeader);
+        }
+
+        let rows = node_count(north - south, lat_spacing).ok_or(GeoidError::InvalidHeader)?;
+        let columns = node_count(east - west, lon_spacing).ok_or(GeoidError::InvalidHeader)?;
+        let nodes = rows.checked_mul(columns).ok_or(GeoidError::InvalidHeader)?;
+
+        let mut undulations = Vec::new();
+        for line in lines {
+            for value in line?.split_whitespace() {
+                let v = value
+                    .parse()
+                    .map_err(|_| GeoidError::InvalidValue(value.to_string()))?;
+                undulations.push(v);
+            }
+        }
+
+        if undulations.len() != nodes {
+            return Err(GeoidError::InvalidSize {
+                expected: nodes,
+                actual: undulations.len(),
+            });
+        }
+
+        Ok(Self::new(
+            north,
+            west,
+            lat_spacing,
+            lon_spacing,
+            columns,
+            undulations,
+        ))
+    }
+
+    /// Reads a grid from a file in the ASCII format of the NGA. See `from_grd`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, GeoidError> {
+        Self::from_grd(File::open(path)?)
+    }
+
+    /// Returns the number of rows (parallels) of the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns (meridians) of the grid.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    fn value(&self, row: usize, column: usize) -> f64 {
+        f64::from(self.undulations[row * self.columns + column])
+    }
+}
+
+impl Geoid for GeoidGrid {
+    fn undulation(&self, geo: Geographic) -> f64 {
+        let last_row = (self.rows - 1) as f64;
+        let last_column = (self.columns - 1) as f64;
+
+        // Bring the longitude in the range of the grid, or next to its nearest edge.
+        let mut lon = geo.lon();
+        while lon < self.west {
+            lon += 360.0;
+        }
+        while lon - 360.0 >= self.west {
+            lon -= 360.0;
+        }
+        let east = self.west + last_column * self.lon_spacing;
+        if lon > east && lon - east > self.west + 360.0 - lon {
+            lon -= 360.0;
+        }
+
+        let y = num::clamp((self.north - geo.lat()) / self.lat_spacing, 0.0, last_row);
+        let x = num::clamp((lon - self.west) / self.lon_spacing, 0.0, last_column);
+
+        let row = (y.floor() as usize).min(self.rows - 2);
+        let column = (x.floor() as usize).min(self.columns - 2);
+        let v = y - row as f64;
+        let u = x - column as f64;
+
+        let north = self.value(row, column) * (1.0 - u) + self.value(row, column + 1) * u;
+        let south = self.value(row + 1, column) * (1.0 - u) + self.value(row + 1, column + 1) * u;
+
+        north * (1.0 - v) + south * v
+    }
+}
+
+/// Returns the number of nodes spaced along the extent, or `None` if the extent
+/// does not span at least two nodes or spans too many nodes.
+/// Grids of geoid heights and grid shifts share this check.
+pub(crate) fn node_count(extent: f64, spacing: f64) -> Option<usize> {
+    let intervals = (extent / spacing).round();
+
+    if intervals >= 1.0 && intervals < f64::from(u32::MAX) {
+        (intervals as usize).checked_add(1)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geoid::*;
+
+    // A 3x4 grid covering [0°N, 2°N] and [10°E, 13°E].
+    const GRID: &str = "
+        0.0 2.0 10.0 13.0 1.0 1.0
+        1.0 2.0 3.0 4.0
+        5.0 6.0 7.0 8.0
+
+        9.0 10.0 11.0 12.0
+    ";
+
+    // A global grid with 90° spacing, over longitudes [0°, 360°].
+    const WORLD: &str = "
+        -90.0 90.0 0.0 360.0 90.0 90.0
+        0.0 0.0 0.0 0.0 0.0
+        10.0 20.0 30.0 40.0 10.0
+        0.0 0.0 0.0 0.0 0.0
+    ";
+
+    fn undulation(grid: &GeoidGrid, lat: f64, lon: f64) -> f64 {
+        grid.undulation(Geographic::new(lat, lon, 0.0))
+    }
+
+    #[test]
+    fn from_grd_reads_the_values() {
+        let grid = GeoidGrid::from_grd(GRID.as_bytes()).unwrap();
+
+        assert_eq!(grid.rows(), 3);
+        assert_eq!(grid.columns(), 4);
+        assert_abs_diff_eq!(undulation(&grid, 2.0, 10.0), 1.0);
+        assert_abs_diff_eq!(undulation(&grid, 2.0, 13.0), 4.0);
+        assert_abs_diff_eq!(undulation(&grid, 0.0, 10.0), 9.0);
+        assert_abs_diff_eq!(undulation(&grid, 1.0, 12.0), 7.0);
+    }
+
+    #[test]
+    fn from_grd_returns_errors_for_invalid_grids() {
+        let header = GeoidGrid::from_grd("0.0 2.0 10.0\n1.0".as_bytes());
+        let value = GeoidGrid::from_grd("0.0 1.0 0.0 1.0 1.0 1.0\n1.0 2.0 x 4.0".as_bytes());
+        let size = GeoidGrid::from_grd("0.0 1.0 0.0 1.0 1.0 1.0\n1.0 2.0 3.0".as_bytes());
+
+        match header {
+            Err(GeoidError::InvalidHeader) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        for header in &[
+            "0.0 0.1 0.0 1.0 1.0 1.0",
+            "0.0 1.0 0.0 0.1 1.0 1.0",
+            "0 1e12 0 1e12 1e-9 1e-9",
+            "0.0 1.0 0.0 inf 1.0 1.0",
+            "NaN 1.0 0.0 1.0 1.0 1.0",
+        ] {
+            match GeoidGrid::from_grd(format!("{}\n1.0 2.0", header).as_bytes()) {
+                Err(GeoidError::InvalidHeader) => (),
+                r => panic!("unexpected result for '{}': {:?}", header, r),
+            }
+        }
+        match value {
+            Err(GeoidError::InvalidValue(v)) => assert_eq!(v, "x"),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match size {
+            Err(GeoidError::InvalidSize { expected, actual }) => {
+                assert_eq!((expected, actual), (4, 3))
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn open_returns_an_error_for_missing_files() {
+        match GeoidGrid::open("/nonexistent/WW15MGH.GRD") {
+            Err(GeoidError::Io(_)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn undulation_is_interpolated_between_nodes() {
+        let grid = GeoidGrid::from_grd(GRID.as_bytes()).unwrap();
+
+        assert_abs_diff_eq!(undulation(&grid, 1.5, 10.5), 3.5);
+        assert_abs_diff_eq!(undulation(&grid, 0.25, 12.0), 10.0);
+    }
+
+    #[test]
+    fn undulation_is_clamped_outside_the_grid() {
+        let grid = GeoidGrid::from_grd(GRID.as_bytes()).unwrap();
+
+        assert_abs_diff_eq!(undulation(&grid, 45.0, 10.0), 1.0);
+        assert_abs_diff_eq!(undulation(&grid, -45.0, 0.0), 9.0);
+        assert_abs_diff_eq!(undulation(&grid, 1.0, 20.0), 8.0);
+    }
+
+    #[test]
+    fn undulation_wraps_longitudes_around_the_globe() {
+        let grid = GeoidGrid::from_grd(WORLD.as_bytes()).unwrap();
+
+        assert_abs_diff_eq!(undulation(&grid, 0.0, 0.0), 10.0);
+        assert_abs_diff_eq!(undulation(&grid, 0.0, -90.0), 40.0);
+        assert_abs_diff_eq!(undulation(&grid, 0.0, MIN_LON), 30.0);
+        assert_abs_diff_eq!(undulation(&grid, 0.0, MAX_LON), 30.0);
+        assert_abs_diff_eq!(undulation(&grid, 0.0, -45.0), 25.0);
+        assert_abs_diff_eq!(undulation(&grid, 45.0, 45.0), 7.5);
+    }
+
+    #[quickcheck]
+    fn to_orthometric_height_is_the_inverse_of_to_ellipsoidal_height(
+        geo: Geographic,
+        height: u16,
+    ) -> bool {
+        let grid = GeoidGrid::from_grd(WORLD.as_bytes()).unwrap();
+        let geo = geo.flatten().raise(f64::from(height));
+
+        let result = geo
+            .to_ellipsoidal_height(&grid)
+            .to_orthometric_height(&grid);
+
+        abs_diff_eq!(result, geo, epsilon = 1e-6)
+    }
+}
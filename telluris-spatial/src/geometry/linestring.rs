@@ -0,0 +1,199 @@
+use crate::ellipsoid::Ellipsoid;
+use crate::geobounds::GeoBounds;
+use crate::geometry::multi::GeoMultiLineString;
+use crate::geometry::*;
+
+/// A sequence of geographic coordinates joined by edges.
+/// See the module documentation for the interpretation of edges.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeoLineString {
+    points: Vec<Geographic>,
+}
+
+impl GeoLineString {
+    /// Creates a line string going through the points, in order.
+    pub fn new(points: Vec<Geographic>) -> Self {
+        GeoLineString { points }
+    }
+
+    /// Returns the points of this line string.
+    pub fn points(&self) -> &[Geographic] {
+        &self.points
+    }
+
+    /// Returns the number of points of this line string.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns true if this line string has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the length of this line string in meters, along geodesics of the ellipsoid.
+    pub fn length(&self, ellipsoid: &Ellipsoid) -> f64 {
+        length(&self.points, ellipsoid, false)
+    }
+
+    /// Returns the bounds of this line string, or `None` if it is empty.
+    /// The bounds include the great-circle arcs between the points.
+    pub fn bounds(&self) -> Option<GeoBounds> {
+        bounds(&self.points, false)
+    }
+
+    /// Returns a line string with additional points along the great circles between
+    /// the points, so that no edge is longer than `max_length`, in meters.
+    pub fn densify(&self, max_length: f64) -> Self {
+        GeoLineString::new(densify(&self.points, max_length, false))
+    }
+
+    /// Returns the parts of this line string that are inside the bounds.
+    /// Elevations are ignored.
+    pub fn clip(&self, bounds: &GeoBounds) -> GeoMultiLineString {
+        let plane = unwrap(&self.points);
+        let (min, max) = x_range(&plane);
+        let mut parts = Vec::new();
+
+        for (lower, upper, shift) in clip_windows(bounds, min, max) {
+            let shifted: Vec<DVec3> = plane
+                .iter()
+                .map(|p| p + DVec3::new(shift, 0.0, 0.0))
+                .collect();
+            for part in clip_line(&shifted, lower, upper) {
+                parts.push(GeoLineString::new(
+                    part.into_iter().map(to_geographic).collect(),
+                ));
+            }
+        }
+
+        GeoMultiLineString::new(parts)
+    }
+}
+
+impl From<Vec<Geographic>> for GeoLineString {
+    fn from(points: Vec<Geographic>) -> Self {
+        GeoLineString::new(points)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ellipsoid::Ellipsoid;
+    use crate::geobounds::GeoBounds;
+    use crate::geographic::*;
+    use crate::geometry::linestring::*;
+
+    fn line(points: &[(f64, f64)]) -> GeoLineString {
+        GeoLineString::new(
+            points
+                .iter()
+                .map(|(lat, lon)| Geographic::new(*lat, *lon, 0.0))
+                .collect(),
+        )
+    }
+
+    fn bounds(south: f64, west: f64, north: f64, east: f64) -> GeoBounds {
+        GeoBounds::new(
+            Geographic::new(south, west, 0.0),
+            Geographic::new(north, east, 0.0),
+        )
+    }
+
+    #[test]
+    fn length_matches_reference_values() {
+        // Reference values computed with GeographicLib.
+        let jfk_sin_lhr = line(&[(40.64, -73.78), (1.36, 103.99), (51.4775, -0.4614)]);
+
+        assert_abs_diff_eq!(line(&[]).length(&Ellipsoid::wgs84()), 0.0);
+        assert_abs_diff_eq!(line(&[(40.64, -73.78)]).length(&Ellipsoid::wgs84()), 0.0);
+        assert_abs_diff_eq!(
+            jfk_sin_lhr.length(&Ellipsoid::wgs84()),
+            26_234_102.9,
+            epsilon = 1.0
+        );
+    }
+
+    #[test]
+    fn bounds_across_the_antimeridian_returns_correct_values() {
+        let result = line(&[(-10.0, 170.0), (10.0, -170.0), (0.0, -175.0)])
+            .bounds()
+            .unwrap();
+
+        assert!(result.crosses_antimeridian());
+        assert_abs_diff_eq!(result.west(), 170.0);
+        assert_abs_diff_eq!(result.east(), -170.0);
+        assert_abs_diff_eq!(result.south(), -10.0);
+        assert_abs_diff_eq!(result.north(), 10.0);
+        assert_eq!(line(&[]).bounds(), None);
+    }
+
+    #[test]
+    fn clip_splits_the_line_into_parts() {
+        let zigzag = line(&[
+            (0.0, -20.0),
+            (0.0, 0.0),
+            (20.0, 0.0),
+            (20.0, -20.0),
+            (5.0, -5.0),
+        ]);
+        let result = zigzag.clip(&bounds(-10.0, -10.0, 10.0, 10.0));
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result.line_strings()[0],
+            line(&[(0.0, -10.0), (0.0, 0.0), (10.0, 0.0)])
+        );
+        assert_eq!(
+            result.line_strings()[1],
+            line(&[(10.0, -10.0), (5.0, -5.0)])
+        );
+    }
+
+    #[test]
+    fn clip_across_the_antimeridian_returns_correct_values() {
+        let result = line(&[(0.0, 170.0), (0.0, -170.0)]).clip(&bounds(-10.0, 175.0, 10.0, -175.0));
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result.line_strings()[0],
+            line(&[(0.0, 175.0), (0.0, 180.0)])
+        );
+        assert_eq!(
+            result.line_strings()[1],
+            line(&[(0.0, -180.0), (0.0, -175.0)])
+        );
+    }
+
+    #[quickcheck]
+    fn densify_preserves_the_length(a: Geographic, b: Geographic, c: Geographic) -> bool {
+        let original = GeoLineString::new(vec![a.flatten(), b.flatten(), c.flatten()]);
+        let dense = original.densify(500_000.0);
+        let sphere = Ellipsoid::sphere(MEAN_EARTH_RADIUS);
+        let edges_are_short = dense
+            .points()
+            .windows(2)
+            .all(|w| w[0].great_circle_distance(&w[1]) <= 500_000.0 + 1e-6);
+
+        edges_are_short
+            && dense.points().first() == original.points().first()
+            && dense.points().last() == original.points().last()
+            && abs_diff_eq!(
+                dense.length(&sphere),
+                original.length(&sphere),
+                epsilon = 1e-2
+            )
+    }
+
+    #[quickcheck]
+    fn bounds_contain_the_densified_line(a: Geographic, b: Geographic) -> bool {
+        let original = GeoLineString::new(vec![a, b]);
+        let bounds = original.bounds().unwrap().grow(1e-9, 1e-9);
+
+        original
+            .densify(100_000.0)
+            .points()
+            .iter()
+            .all(|p| bounds.contains(*p))
+    }
+}
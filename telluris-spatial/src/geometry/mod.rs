@@ -0,0 +1,655 @@
+//! Geometries made of geographic coordinates: line strings, polygons with holes,
+//! and their multi variants.
+//!
+//! Consecutive points are joined by the shortest path, which crosses the antimeridian
+//! when their longitudes are more than 180° apart. The interpretation of edges depends
+//! on the operation:
+//! - lengths are measured along geodesics of the ellipsoid,
+//! - areas are measured on the authalic sphere, where edges are great circles,
+//! - densification follows great circles, and bounds include their poleward bulge,
+//! - containment and clipping treat edges as straight lines in longitude and latitude,
+//!   so long edges should be densified first.
+//!
+//! Rings are implicitly closed: the last point is joined to the first one.
+//! A ring that winds around a pole encloses the pole on its left: the north pole
+//! if it goes eastward, the south pole if it goes westward.
+//! Elevations are ignored, except that they are interpolated along edges.
+
+use crate::angle::{Degrees, Radians};
+use crate::ellipsoid::Ellipsoid;
+use crate::geobounds::GeoBounds;
+use crate::geodesic::Geodesic;
+use crate::geographic::*;
+use glm::{DVec2, DVec3};
+use std::f64::consts::PI;
+
+pub mod linestring;
+pub mod multi;
+pub mod polygon;
+
+use self::linestring::GeoLineString;
+use self::multi::{GeoMultiLineString, GeoMultiPoint, GeoMultiPolygon};
+use self::polygon::GeoPolygon;
+
+/// Any of the geometry types, as exchanged with other formats.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Geographic),
+    LineString(GeoLineString),
+    Polygon(GeoPolygon),
+    MultiPoint(GeoMultiPoint),
+    MultiLineString(GeoMultiLineString),
+    MultiPolygon(GeoMultiPolygon),
+    GeometryCollection(Vec<Geometry>),
+}
+
+impl Geometry {
+    /// Returns the bounds of this geometry, or `None` if it is empty.
+    pub fn bounds(&self) -> Option<GeoBounds> {
+        match self {
+            Geometry::Point(p) => Some(GeoBounds::new(*p, *p)),
+            Geometry::LineString(l) => l.bounds(),
+            Geometry::Polygon(p) => p.bounds(),
+            Geometry::MultiPoint(m) => m.bounds(),
+            Geometry::MultiLineString(m) => m.bounds(),
+            Geometry::MultiPolygon(m) => m.bounds(),
+            Geometry::GeometryCollection(c) => union(c.iter().filter_map(Geometry::bounds)),
+        }
+    }
+
+    /// Returns all the points of this geometry, in order.
+    pub fn points(&self) -> Box<dyn Iterator<Item = &Geographic> + '_> {
+        match self {
+            Geometry::Point(p) => Box::new(std::iter::once(p)),
+            Geometry::LineString(l) => Box::new(l.points().iter()),
+            Geometry::Polygon(p) => Box::new(p.rings().flatten()),
+            Geometry::MultiPoint(m) => Box::new(m.points().iter()),
+            Geometry::MultiLineString(m) => {
+                Box::new(m.line_strings().iter().flat_map(|l| l.points()))
+            }
+            Geometry::MultiPolygon(m) => {
+                Box::new(m.polygons().iter().flat_map(|p| p.rings().flatten()))
+            }
+            Geometry::GeometryCollection(c) => Box::new(c.iter().flat_map(Geometry::points)),
+        }
+    }
+}
+
+macro_rules! impl_from_geometry {
+    ($($variant:ident($t:ty)),*) => {
+        $(
+            impl From<$t> for Geometry {
+                fn from(g: $t) -> Self {
+                    Geometry::$variant(g)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_geometry!(
+    Point(Geographic),
+    LineString(GeoLineString),
+    Polygon(GeoPolygon),
+    MultiPoint(GeoMultiPoint),
+    MultiLineString(GeoMultiLineString),
+    MultiPolygon(GeoMultiPolygon)
+);
+
+impl From<GeoBounds> for Geometry {
+    fn from(bounds: GeoBounds) -> Self {
+        Geometry::Polygon(GeoPolygon::from(bounds))
+    }
+}
+
+/// Returns the difference of longitude from `from` to `to`, in the [-180, 180] range.
+pub(crate) fn lon_delta(from: &Geographic, to: &Geographic) -> f64 {
+    let delta = to.lon() - from.lon();
+
+    if delta > 180.0 {
+        delta - 360.0
+    } else if delta < -180.0 {
+        delta + 360.0
+    } else {
+        delta
+    }
+}
+
+/// Wraps a longitude into the [-180, 180] range.
+pub(crate) fn wrap_lon(lon: f64) -> f64 {
+    if (MIN_LON..=MAX_LON).contains(&lon) {
+        lon
+    } else {
+        (lon + 180.0).rem_euclid(360.0) - 180.0
+    }
+}
+
+/// Returns the edges of the points, as pairs of indices.
+fn edges(len: usize, closed: bool) -> impl Iterator<Item = (usize, usize)> {
+    let count = match len {
+        0 | 1 => 0,
+        _ if closed => len,
+        _ => len - 1,
+    };
+
+    (0..count).map(move |i| (i, (i + 1) % len))
+}
+
+/// Returns the number of times the closed ring winds around the poles:
+/// 1 if it goes once eastward, -1 if it goes once westward, 0 otherwise.
+fn winding(ring: &[Geographic]) -> i32 {
+    let total: f64 = edges(ring.len(), true)
+        .map(|(i, j)| lon_delta(&ring[i], &ring[j]))
+        .sum();
+
+    (total / 360.0).round() as i32
+}
+
+/// Returns the points as (longitude, latitude, elevation) vectors, with longitudes
+/// unwrapped so that consecutive points are never more than 180° apart.
+fn unwrap(points: &[Geographic]) -> Vec<DVec3> {
+    let mut result: Vec<DVec3> = Vec::with_capacity(points.len());
+
+    for (i, p) in points.iter().enumerate() {
+        let lon = match i {
+            0 => p.lon(),
+            _ => result[i - 1].x + lon_delta(&points[i - 1], p),
+        };
+        result.push(DVec3::new(lon, p.lat(), p.elevation()));
+    }
+
+    result
+}
+
+/// Returns the closed ring as a planar polygon in unwrapped (longitude, latitude)
+/// space. Rings that wind around a pole are closed along the pole.
+fn planar_ring(ring: &[Geographic]) -> Vec<DVec3> {
+    let mut plane = unwrap(ring);
+
+    let turns = winding(ring);
+    if turns != 0 {
+        let first = plane[0];
+        let pole = if turns > 0 { MAX_LAT } else { MIN_LAT };
+        let end = first.x + 360.0 * f64::from(turns);
+
+        plane.push(DVec3::new(end, first.y, first.z));
+        plane.push(DVec3::new(end, pole, first.z));
+        plane.push(DVec3::new(first.x, pole, first.z));
+    }
+
+    plane
+}
+
+/// Returns the range of the x coordinates of the points.
+fn x_range(points: &[DVec3]) -> (f64, f64) {
+    points
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| {
+            (min.min(p.x), max.max(p.x))
+        })
+}
+
+/// Converts a point from the unwrapped space into a geographic coordinate.
+fn to_geographic(p: DVec3) -> Geographic {
+    Geographic::new(p.y, wrap_lon(p.x), p.z)
+}
+
+/// Returns true if the closed planar ring contains the point, using the even-odd rule.
+fn planar_contains(ring: &[DVec3], x: f64, y: f64) -> bool {
+    let mut inside = false;
+
+    for (i, j) in edges(ring.len(), true) {
+        let (a, b) = (ring[i], ring[j]);
+        if (a.y > y) != (b.y > y) && x < a.x + (b.x - a.x) * (y - a.y) / (b.y - a.y) {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+/// Returns true if the ring, interpreted as a planar polygon, contains the coordinate.
+fn ring_contains(ring: &[Geographic], geo: Geographic) -> bool {
+    if ring.len() < 3 {
+        return false;
+    }
+
+    let plane = planar_ring(ring);
+    let (min, max) = x_range(&plane);
+
+    [0.0, -360.0, 360.0].iter().any(|shift| {
+        let x = geo.lon() + shift;
+        (min..=max).contains(&x) && planar_contains(&plane, x, geo.lat())
+    })
+}
+
+/// Returns the parts of the bounds, in the unwrapped space, along with the
+/// shifts of longitude that bring the points of the specified range over them.
+fn clip_windows(bounds: &GeoBounds, min: f64, max: f64) -> Vec<(DVec2, DVec2, f64)> {
+    let (western, eastern) = bounds.split_at_antimeridian();
+    let mut windows = Vec::new();
+
+    for part in [Some(western), eastern].iter().flatten() {
+        for shift in [-360.0, 0.0, 360.0].iter() {
+            if min + shift <= part.east() && max + shift >= part.west() {
+                windows.push((
+                    DVec2::new(part.west(), part.south()),
+                    DVec2::new(part.east(), part.north()),
+                    *shift,
+                ));
+            }
+        }
+    }
+
+    windows
+}
+
+/// Returns the point at the parameter `t` of the segment.
+fn lerp(a: DVec3, b: DVec3, t: f64) -> DVec3 {
+    a + (b - a) * t
+}
+
+/// Clips the closed planar ring to the rectangle, with the Sutherland-Hodgman
+/// algorithm. Concave rings may produce degenerate edges along the rectangle.
+fn clip_ring(ring: &[DVec3], min: DVec2, max: DVec2) -> Vec<DVec3> {
+    // Each clipping edge is an axis, a bound, and whether points below the bound are kept.
+    let planes = [
+        (0, min.x, false),
+        (0, max.x, true),
+        (1, min.y, false),
+        (1, max.y, true),
+    ];
+    let mut result = ring.to_vec();
+
+    for &(axis, bound, below) in planes.iter() {
+        let input = std::mem::take(&mut result);
+        let inside = |p: &DVec3| (p[axis] <= bound) == below || p[axis] == bound;
+
+        for (i, j) in edges(input.len(), true) {
+            let (a, b) = (input[i], input[j]);
+            match (inside(&a), inside(&b)) {
+                (true, true) => result.push(b),
+                (true, false) => result.push(lerp(a, b, (bound - a[axis]) / (b[axis] - a[axis]))),
+                (false, true) => {
+                    result.push(lerp(a, b, (bound - a[axis]) / (b[axis] - a[axis])));
+                    result.push(b);
+                }
+                (false, false) => (),
+            }
+        }
+    }
+
+    result
+}
+
+/// Clips the open planar line to the rectangle, with the Liang-Barsky algorithm.
+/// Returns the parts of the line inside the rectangle.
+fn clip_line(line: &[DVec3], min: DVec2, max: DVec2) -> Vec<Vec<DVec3>> {
+    let mut parts: Vec<Vec<DVec3>> = Vec::new();
+    let mut connected = false;
+
+    for (i, j) in edges(line.len(), false) {
+        let (a, b) = (line[i], line[j]);
+        let d = b - a;
+        let (mut t0, mut t1) = (0.0, 1.0);
+
+        let constraints = [
+            (-d.x, a.x - min.x),
+            (d.x, max.x - a.x),
+            (-d.y, a.y - min.y),
+            (d.y, max.y - a.y),
+        ];
+        let visible = constraints.iter().all(|&(p, q)| {
+            if p == 0.0 {
+                return q >= 0.0;
+            }
+            let t = q / p;
+            if p < 0.0 {
+                t0 = f64::max(t0, t);
+            } else {
+                t1 = f64::min(t1, t);
+            }
+            t0 <= t1
+        });
+
+        if !visible {
+            connected = false;
+            continue;
+        }
+
+        match parts.last_mut() {
+            Some(part) if connected && t0 == 0.0 => part.push(lerp(a, b, t1)),
+            _ => parts.push(vec![lerp(a, b, t0), lerp(a, b, t1)]),
+        }
+        connected = t1 == 1.0;
+    }
+
+    parts
+}
+
+/// Returns the unit vector of the coordinate on the unit sphere.
+fn to_unit(geo: &Geographic) -> DVec3 {
+    let (sin_lat, cos_lat) = geo.lat_rad().sin_cos();
+    let (sin_lon, cos_lon) = geo.lon_rad().sin_cos();
+
+    DVec3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat)
+}
+
+/// Returns the coordinate of the unit vector on the unit sphere.
+fn from_unit(v: DVec3, elevation: f64) -> Geographic {
+    let lat = Radians::asin(num::clamp(v.z, -1.0, 1.0));
+    let lon = Radians::atan2(v.y, v.x);
+
+    Geographic::from_radians(lat, lon, elevation)
+}
+
+/// Returns the point at the fraction `t` of the great-circle arc from `a` to `b`.
+/// Elevations are interpolated linearly. Antipodal points are joined through
+/// the north of `a`, along its meridian.
+pub(crate) fn interpolate(a: &Geographic, b: &Geographic, t: f64) -> Geographic {
+    let (u, v) = (to_unit(a), to_unit(b));
+    let angle = num::clamp(u.dot(&v), -1.0, 1.0).acos();
+    let elevation = a.elevation() + (b.elevation() - a.elevation()) * t;
+
+    if angle < 1e-12 {
+        return Geographic::new(a.lat(), a.lon(), elevation);
+    }
+
+    // The direction of the arc at `a`, whose norm is the sine of the angle.
+    let tangent = v - u * u.dot(&v);
+    let tangent = if tangent.norm() > 1e-9 {
+        tangent.normalize()
+    } else {
+        let (sin_lat, cos_lat) = a.lat_rad().sin_cos();
+        let (sin_lon, cos_lon) = a.lon_rad().sin_cos();
+        DVec3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat)
+    };
+    let (sin, cos) = (t * angle).sin_cos();
+
+    from_unit(u * cos + tangent * sin, elevation)
+}
+
+/// Returns the points with additional points inserted along great circles, so that
+/// no edge is longer than `max_length` meters on a sphere of radius `MEAN_EARTH_RADIUS`.
+fn densify(points: &[Geographic], max_length: f64, closed: bool) -> Vec<Geographic> {
+    assert!(max_length > 0.0);
+
+    let mut result = Vec::with_capacity(points.len());
+
+    for (i, j) in edges(points.len(), closed) {
+        let (a, b) = (&points[i], &points[j]);
+        let count = (a.great_circle_distance(b) / max_length).ceil().max(1.0) as usize;
+
+        result.push(*a);
+        result.extend((1..count).map(|k| interpolate(a, b, k as f64 / count as f64)));
+    }
+
+    if !closed || points.len() < 2 {
+        result.extend(points.last());
+    }
+
+    result
+}
+
+/// Returns the range of latitudes reached by the great-circle arc between the points,
+/// which may exceed the latitudes of the points.
+fn arc_lat_range(a: &Geographic, b: &Geographic) -> (f64, f64) {
+    let (mut south, mut north) = (a.lat().min(b.lat()), a.lat().max(b.lat()));
+    let (u, v) = (to_unit(a), to_unit(b));
+    let normal = u.cross(&v);
+    let length = normal.norm();
+    if length < 1e-12 {
+        return (south, north);
+    }
+
+    // The highest point of the great circle is the projection of the pole on its plane.
+    let normal = normal / length;
+    let highest = DVec3::new(0.0, 0.0, 1.0) - normal * normal.z;
+    if highest.norm() < 1e-12 {
+        return (south, north);
+    }
+
+    let highest = highest.normalize();
+    for extremum in [highest, -highest].iter() {
+        if u.cross(extremum).dot(&normal) >= 0.0 && extremum.cross(&v).dot(&normal) >= 0.0 {
+            let lat = Radians::asin(extremum.z).to_degrees().value();
+            south = south.min(lat);
+            north = north.max(lat);
+        }
+    }
+
+    (south, north)
+}
+
+/// Returns the bounds of the points and of the great-circle arcs between them.
+fn bounds(points: &[Geographic], closed: bool) -> Option<GeoBounds> {
+    let first = points.first()?;
+
+    let mut south = first.lat();
+    let mut north = first.lat();
+    let mut floor = first.elevation();
+    let mut top = first.elevation();
+    for p in points {
+        floor = floor.min(p.elevation());
+        top = top.max(p.elevation());
+    }
+    for (i, j) in edges(points.len(), closed) {
+        let (s, n) = arc_lat_range(&points[i], &points[j]);
+        south = south.min(s);
+        north = north.max(n);
+    }
+
+    let turns = if closed { winding(points) } else { 0 };
+    if turns > 0 {
+        north = MAX_LAT;
+    } else if turns < 0 {
+        south = MIN_LAT;
+    }
+
+    let (min, max) = x_range(&unwrap(points));
+    let (west, east) = if turns != 0 || max - min >= 360.0 {
+        (MIN_LON, MAX_LON)
+    } else {
+        (wrap_lon(min), wrap_lon(max))
+    };
+
+    Some(GeoBounds::new(
+        Geographic::new(south, west, floor),
+        Geographic::new(north, east, top),
+    ))
+}
+
+/// Returns the smallest bounds that contain all the bounds, or `None` if there are none.
+/// Unlike `GeoBounds::expand`, the longitude range is the smallest arc that covers
+/// all the bounds, even if none of them crosses the antimeridian.
+fn union(bounds: impl Iterator<Item = GeoBounds>) -> Option<GeoBounds> {
+    let mut arcs = Vec::new();
+    let (mut south, mut north) = (MAX_LAT, MIN_LAT);
+    let (mut floor, mut top) = (MAX_ALT, MIN_ALT);
+    for b in bounds {
+        south = south.min(b.south());
+        north = north.max(b.north());
+        floor = floor.min(b.floor());
+        top = top.max(b.top());
+        arcs.push((b.west(), b.west() + b.span_lon()));
+    }
+    if arcs.is_empty() {
+        return None;
+    }
+
+    // The smallest arc is the complement of the largest gap between the arcs.
+    // The first lap accumulates the arcs that wrap around, so that the gaps found
+    // in the second lap are not covered by any arc.
+    arcs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut end = arcs[0].1;
+    let mut largest = 0.0;
+    let mut gap = None;
+    for (lap, (start, stop)) in [0.0, 360.0]
+        .iter()
+        .flat_map(|lap| arcs.iter().map(move |(a, b)| (*lap, (a + lap, b + lap))))
+    {
+        if lap > 0.0 && start - end > largest {
+            largest = start - end;
+            gap = Some((end, start));
+        }
+        end = end.max(stop);
+    }
+
+    let (west, east) = match gap {
+        Some((east, west)) => (wrap_lon(west), wrap_lon(east)),
+        None => (MIN_LON, MAX_LON),
+    };
+
+    Some(GeoBounds::new(
+        Geographic::new(south, west, floor),
+        Geographic::new(north, east, top),
+    ))
+}
+
+/// Returns the length of the line in meters, along geodesics of the ellipsoid.
+fn length(points: &[Geographic], ellipsoid: &Ellipsoid, closed: bool) -> f64 {
+    let solver = Geodesic::new(*ellipsoid);
+
+    edges(points.len(), closed)
+        .map(|(i, j)| geodesic_length(&solver, &points[i], &points[j]))
+        .sum()
+}
+
+/// Returns the length of the geodesic between the points. The inverse problem
+/// does not converge for nearly antipodal points: the edge is then split in halves.
+fn geodesic_length(solver: &Geodesic, a: &Geographic, b: &Geographic) -> f64 {
+    match solver.inverse(*a, *b) {
+        Some(solution) => solution.distance,
+        None => {
+            let middle = interpolate(a, b, 0.5);
+            geodesic_length(solver, a, &middle) + geodesic_length(solver, &middle, b)
+        }
+    }
+}
+
+/// Returns the area of the closed ring in square meters, on the authalic sphere
+/// of the ellipsoid.
+fn ring_area(ring: &[Geographic], ellipsoid: &Ellipsoid) -> f64 {
+    if ring.len() < 3 {
+        return 0.0;
+    }
+
+    // Each edge contributes the signed area between itself and the equator,
+    // see "Algorithms for geodesics", by C. F. F. Karney.
+    let half_tan =
+        |geo: &Geographic| (ellipsoid.authalic_latitude(geo.lat_rad()).value() / 2.0).tan();
+    let excess: f64 = edges(ring.len(), true)
+        .map(|(i, j)| {
+            let (a, b) = (&ring[i], &ring[j]);
+            let (t1, t2) = (half_tan(a), half_tan(b));
+            let d_lon = Degrees(lon_delta(a, b)).to_radians().value();
+
+            2.0 * ((d_lon / 2.0).tan() * (t1 + t2)).atan2(1.0 + t1 * t2)
+        })
+        .sum();
+
+    // Rings around a pole enclose the cap on their left.
+    let excess = if winding(ring) == 0 {
+        excess.abs()
+    } else {
+        2.0 * PI - excess
+    };
+
+    excess * ellipsoid.authalic_radius().powi(2)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry::*;
+
+    #[test]
+    fn arc_lat_range_includes_the_bulge_of_great_circles() {
+        let (south, north) = arc_lat_range(
+            &Geographic::new(45.0, -90.0, 0.0),
+            &Geographic::new(45.0, 90.0, 0.0),
+        );
+
+        assert_abs_diff_eq!(south, 45.0);
+        assert_abs_diff_eq!(north, 90.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn union_returns_the_smallest_arc() {
+        let bounds = |west: f64, east: f64| {
+            GeoBounds::new(
+                Geographic::new(0.0, west, 0.0),
+                Geographic::new(10.0, east, 0.0),
+            )
+        };
+        let lons = |b: Option<GeoBounds>| b.map(|b| (b.west(), b.east()));
+
+        let all = vec![
+            bounds(160.0, 170.0),
+            bounds(-170.0, -165.0),
+            bounds(-175.0, -172.0),
+        ];
+        assert_eq!(lons(union(all.into_iter())), Some((160.0, -165.0)));
+
+        let all = vec![
+            bounds(-10.0, 10.0),
+            bounds(100.0, -100.0),
+            bounds(-120.0, 120.0),
+        ];
+        assert_eq!(lons(union(all.into_iter())), Some((MIN_LON, MAX_LON)));
+
+        let all = vec![
+            bounds(170.0, -150.0),
+            bounds(-160.0, -155.0),
+            bounds(0.0, 10.0),
+        ];
+        assert_eq!(lons(union(all.into_iter())), Some((170.0, 10.0)));
+
+        assert_eq!(union(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn winding_returns_correct_values() {
+        let cap = |lat: f64, step: f64| {
+            (0..4)
+                .map(|i| Geographic::new(lat, wrap_lon(i as f64 * step), 0.0))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(winding(&cap(80.0, 90.0)), 1);
+        assert_eq!(winding(&cap(-80.0, -90.0)), -1);
+        assert_eq!(winding(&cap(0.0, 10.0)), 0);
+    }
+
+    #[test]
+    fn interpolate_joins_antipodal_points_through_the_north() {
+        let a = Geographic::new(30.0, 20.0, 0.0);
+        let b = Geographic::new(-30.0, -160.0, 100.0);
+
+        let p = interpolate(&a, &b, 1.0 / 6.0);
+        assert_abs_diff_eq!(p.lat(), 60.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(p.lon(), 20.0, epsilon = 1e-9);
+
+        let middle = interpolate(&a, &b, 0.5);
+        assert_abs_diff_eq!(middle.lat(), 60.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(middle.lon(), -160.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(middle.elevation(), 50.0);
+
+        let end = interpolate(&a, &b, 1.0);
+        assert_abs_diff_eq!(end.lat(), b.lat(), epsilon = 1e-9);
+        assert_abs_diff_eq!(end.lon(), b.lon(), epsilon = 1e-9);
+    }
+
+    #[quickcheck]
+    fn interpolate_stays_on_the_great_circle(a: Geographic, b: Geographic, t: u8) -> bool {
+        let (a, b) = (a.flatten(), b.flatten());
+        let t = f64::from(t) / 255.0;
+        let p = interpolate(&a, &b, t);
+        let distance = |a: &Geographic, b: &Geographic| a.great_circle_distance(b);
+        let total = distance(&a, &b);
+
+        // Nearly antipodal points have no well defined great circle.
+        total > 20_000_000.0
+            || (abs_diff_eq!(distance(&a, &p), t * total, epsilon = 1e-3)
+                && abs_diff_eq!(distance(&p, &b), (1.0 - t) * total, epsilon = 1e-3))
+    }
+}
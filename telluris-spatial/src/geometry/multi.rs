@@ -0,0 +1,239 @@
+use crate::ellipsoid::Ellipsoid;
+use crate::geobounds::GeoBounds;
+use crate::geographic::*;
+use crate::geometry::linestring::GeoLineString;
+use crate::geometry::polygon::GeoPolygon;
+use crate::geometry::union;
+
+/// A collection of geographic coordinates.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeoMultiPoint {
+    points: Vec<Geographic>,
+}
+
+impl GeoMultiPoint {
+    /// Creates a collection of points.
+    pub fn new(points: Vec<Geographic>) -> Self {
+        GeoMultiPoint { points }
+    }
+
+    /// Returns the points of this collection.
+    pub fn points(&self) -> &[Geographic] {
+        &self.points
+    }
+
+    /// Returns the number of points of this collection.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns true if this collection has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the smallest bounds that contain the points, or `None` if the
+    /// collection is empty.
+    pub fn bounds(&self) -> Option<GeoBounds> {
+        union(self.points.iter().map(|p| GeoBounds::new(*p, *p)))
+    }
+
+    /// Returns the points that are inside the bounds.
+    pub fn clip(&self, bounds: &GeoBounds) -> Self {
+        let contains = |p: &&Geographic| bounds.flatten().contains(p.flatten());
+
+        GeoMultiPoint::new(self.points.iter().filter(contains).cloned().collect())
+    }
+}
+
+/// A collection of line strings.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeoMultiLineString {
+    line_strings: Vec<GeoLineString>,
+}
+
+impl GeoMultiLineString {
+    /// Creates a collection of line strings.
+    pub fn new(line_strings: Vec<GeoLineString>) -> Self {
+        GeoMultiLineString { line_strings }
+    }
+
+    /// Returns the line strings of this collection.
+    pub fn line_strings(&self) -> &[GeoLineString] {
+        &self.line_strings
+    }
+
+    /// Returns the number of line strings of this collection.
+    pub fn len(&self) -> usize {
+        self.line_strings.len()
+    }
+
+    /// Returns true if this collection has no line strings.
+    pub fn is_empty(&self) -> bool {
+        self.line_strings.is_empty()
+    }
+
+    /// Returns the total length of the line strings in meters, along geodesics
+    /// of the ellipsoid.
+    pub fn length(&self, ellipsoid: &Ellipsoid) -> f64 {
+        self.line_strings.iter().map(|l| l.length(ellipsoid)).sum()
+    }
+
+    /// Returns the union of the bounds of the line strings, or `None` if they are all empty.
+    pub fn bounds(&self) -> Option<GeoBounds> {
+        union(self.line_strings.iter().filter_map(GeoLineString::bounds))
+    }
+
+    /// Returns the line strings densified with `GeoLineString::densify`.
+    pub fn densify(&self, max_length: f64) -> Self {
+        GeoMultiLineString::new(
+            self.line_strings
+                .iter()
+                .map(|l| l.densify(max_length))
+                .collect(),
+        )
+    }
+
+    /// Returns the parts of the line strings that are inside the bounds.
+    pub fn clip(&self, bounds: &GeoBounds) -> Self {
+        GeoMultiLineString::new(
+            self.line_strings
+                .iter()
+                .flat_map(|l| l.clip(bounds).line_strings)
+                .collect(),
+        )
+    }
+}
+
+/// A collection of polygons.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeoMultiPolygon {
+    polygons: Vec<GeoPolygon>,
+}
+
+impl GeoMultiPolygon {
+    /// Creates a collection of polygons. The polygons should not overlap.
+    pub fn new(polygons: Vec<GeoPolygon>) -> Self {
+        GeoMultiPolygon { polygons }
+    }
+
+    /// Returns the polygons of this collection.
+    pub fn polygons(&self) -> &[GeoPolygon] {
+        &self.polygons
+    }
+
+    /// Returns the number of polygons of this collection.
+    pub fn len(&self) -> usize {
+        self.polygons.len()
+    }
+
+    /// Returns true if this collection has no polygons.
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    /// Returns the total area of the polygons in square meters, on the authalic
+    /// sphere of the ellipsoid.
+    pub fn area(&self, ellipsoid: &Ellipsoid) -> f64 {
+        self.polygons.iter().map(|p| p.area(ellipsoid)).sum()
+    }
+
+    /// Returns true if any polygon contains the coordinate.
+    pub fn contains(&self, geo: Geographic) -> bool {
+        self.polygons.iter().any(|p| p.contains(geo))
+    }
+
+    /// Returns the union of the bounds of the polygons, or `None` if they are all empty.
+    pub fn bounds(&self) -> Option<GeoBounds> {
+        union(self.polygons.iter().filter_map(GeoPolygon::bounds))
+    }
+
+    /// Returns the polygons densified with `GeoPolygon::densify`.
+    pub fn densify(&self, max_length: f64) -> Self {
+        GeoMultiPolygon::new(
+            self.polygons
+                .iter()
+                .map(|p| p.densify(max_length))
+                .collect(),
+        )
+    }
+
+    /// Returns the parts of the polygons that are inside the bounds.
+    pub fn clip(&self, bounds: &GeoBounds) -> Self {
+        GeoMultiPolygon::new(
+            self.polygons
+                .iter()
+                .flat_map(|p| p.clip(bounds).polygons)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ellipsoid::Ellipsoid;
+    use crate::geobounds::GeoBounds;
+    use crate::geometry::multi::*;
+
+    fn square(lat: f64, lon: f64, size: f64) -> GeoPolygon {
+        let corners = [(0.0, 0.0), (0.0, size), (size, size), (size, 0.0)];
+
+        GeoPolygon::new(
+            corners
+                .iter()
+                .map(|(y, x)| {
+                    let lon = lon + x;
+                    let lon = if lon > MAX_LON { lon - 360.0 } else { lon };
+                    Geographic::new(lat + y, lon, 0.0)
+                })
+                .collect(),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn multi_point_bounds_are_the_smallest_bounds() {
+        let points = GeoMultiPoint::new(vec![
+            Geographic::new(10.0, 170.0, 0.0),
+            Geographic::new(-5.0, -175.0, 100.0),
+        ]);
+        let bounds = points.bounds().unwrap();
+
+        assert!(bounds.crosses_antimeridian());
+        assert_abs_diff_eq!(bounds.span_lon(), 15.0);
+        assert_abs_diff_eq!(bounds.height(), 100.0);
+        assert_eq!(GeoMultiPoint::default().bounds(), None);
+    }
+
+    #[test]
+    fn multi_polygon_area_is_the_sum_of_areas() {
+        let sphere = Ellipsoid::unit_sphere();
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(10.0, 170.0, 20.0);
+        let multi = GeoMultiPolygon::new(vec![a.clone(), b.clone()]);
+
+        assert_abs_diff_eq!(multi.area(&sphere), a.area(&sphere) + b.area(&sphere));
+        assert!(multi.contains(Geographic::new(20.0, -175.0, 0.0)));
+        assert!(!multi.contains(Geographic::new(5.0, 5.0, 0.0)));
+    }
+
+    #[test]
+    fn multi_polygon_bounds_are_the_union_of_bounds() {
+        let multi = GeoMultiPolygon::new(vec![square(0.0, 160.0, 10.0), square(0.0, -170.0, 5.0)]);
+        let bounds = multi.bounds().unwrap();
+
+        assert_abs_diff_eq!(bounds.west(), 160.0);
+        assert_abs_diff_eq!(bounds.east(), -165.0);
+    }
+
+    #[quickcheck]
+    fn clipped_points_are_contained_in_the_bounds(points: Vec<Geographic>, b: GeoBounds) -> bool {
+        let clipped = GeoMultiPoint::new(points.clone()).clip(&b);
+
+        clipped.len()
+            == points
+                .iter()
+                .filter(|p| b.contains(p.flatten().raise(b.floor())))
+                .count()
+    }
+}
@@ -0,0 +1,393 @@
+use crate::ellipsoid::Ellipsoid;
+use crate::geobounds::GeoBounds;
+use crate::geometry::multi::GeoMultiPolygon;
+use crate::geometry::*;
+
+/// A surface delimited by an exterior ring, minus the surfaces of its holes.
+/// Rings are implicitly closed, see the module documentation.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeoPolygon {
+    exterior: Vec<Geographic>,
+    holes: Vec<Vec<Geographic>>,
+}
+
+/// Removes the last point of the ring if it repeats the first one.
+fn open_ring(mut ring: Vec<Geographic>) -> Vec<Geographic> {
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+
+    ring
+}
+
+impl GeoPolygon {
+    /// Creates a polygon with the specified exterior ring and holes.
+    /// Rings may repeat their first point at the end, in which case it is removed.
+    pub fn new(exterior: Vec<Geographic>, holes: Vec<Vec<Geographic>>) -> Self {
+        GeoPolygon {
+            exterior: open_ring(exterior),
+            holes: holes.into_iter().map(open_ring).collect(),
+        }
+    }
+
+    /// Returns the exterior ring of this polygon.
+    pub fn exterior(&self) -> &[Geographic] {
+        &self.exterior
+    }
+
+    /// Returns the holes of this polygon.
+    pub fn holes(&self) -> &[Vec<Geographic>] {
+        &self.holes
+    }
+
+    /// Returns the exterior ring, followed by the holes.
+    pub fn rings(&self) -> impl Iterator<Item = &[Geographic]> {
+        std::iter::once(self.exterior.as_slice()).chain(self.holes.iter().map(Vec::as_slice))
+    }
+
+    /// Returns true if the exterior ring has less than three points.
+    pub fn is_empty(&self) -> bool {
+        self.exterior.len() < 3
+    }
+
+    /// Returns the area of this polygon in square meters, on the authalic
+    /// sphere of the ellipsoid.
+    pub fn area(&self, ellipsoid: &Ellipsoid) -> f64 {
+        let holes: f64 = self.holes.iter().map(|h| ring_area(h, ellipsoid)).sum();
+
+        (ring_area(&self.exterior, ellipsoid) - holes).max(0.0)
+    }
+
+    /// Returns the length of the rings of this polygon in meters, along geodesics
+    /// of the ellipsoid.
+    pub fn perimeter(&self, ellipsoid: &Ellipsoid) -> f64 {
+        self.rings().map(|ring| length(ring, ellipsoid, true)).sum()
+    }
+
+    /// Returns true if the coordinate is inside the exterior ring and outside
+    /// of the holes. The result is unspecified for points on the rings.
+    /// Elevations are ignored.
+    pub fn contains(&self, geo: Geographic) -> bool {
+        ring_contains(&self.exterior, geo) && !self.holes.iter().any(|h| ring_contains(h, geo))
+    }
+
+    /// Returns the bounds of the exterior ring, or `None` if it is empty.
+    /// The bounds include the great-circle arcs between the points.
+    pub fn bounds(&self) -> Option<GeoBounds> {
+        bounds(&self.exterior, true)
+    }
+
+    /// Returns a polygon with additional points along the great circles between
+    /// the points, so that no edge is longer than `max_length`, in meters.
+    pub fn densify(&self, max_length: f64) -> Self {
+        GeoPolygon {
+            exterior: densify(&self.exterior, max_length, true),
+            holes: self
+                .holes
+                .iter()
+                .map(|h| densify(h, max_length, true))
+                .collect(),
+        }
+    }
+
+    /// Returns the parts of this polygon that are inside the bounds. Concave polygons
+    /// may produce parts joined by degenerate edges along the bounds.
+    /// Elevations are ignored.
+    pub fn clip(&self, bounds: &GeoBounds) -> GeoMultiPolygon {
+        if self.is_empty() {
+            return GeoMultiPolygon::default();
+        }
+
+        let exterior = planar_ring(&self.exterior);
+        let holes: Vec<Vec<DVec3>> = self.holes.iter().map(|h| planar_ring(h)).collect();
+        let (min, max) = x_range(&exterior);
+        let mut parts = Vec::new();
+
+        for (lower, upper, shift) in clip_windows(bounds, min, max) {
+            let clip = |ring: &[DVec3]| {
+                let offset = DVec3::new(shift, 0.0, 0.0);
+                let shifted: Vec<DVec3> = ring.iter().map(|p| p + offset).collect();
+                let clipped = clip_ring(&shifted, lower, upper);
+                clipped.into_iter().map(to_geographic).collect::<Vec<_>>()
+            };
+
+            let exterior = clip(&exterior);
+            if exterior.len() < 3 {
+                continue;
+            }
+            let holes = holes
+                .iter()
+                .map(|h| clip(h))
+                .filter(|h| h.len() >= 3)
+                .collect();
+
+            parts.push(GeoPolygon::new(exterior, holes));
+        }
+
+        GeoMultiPolygon::new(parts)
+    }
+}
+
+impl From<GeoBounds> for GeoPolygon {
+    /// Returns the rectangle of the bounds, at the elevation of their floor. The edges
+    /// along parallels are split so that they never span 180° or more of longitude.
+    fn from(bounds: GeoBounds) -> Self {
+        let count = (bounds.span_lon() / 90.0).ceil().max(1.0) as usize;
+        let step = bounds.span_lon() / count as f64;
+        let parallel = |lat: f64| {
+            (0..=count).map(move |i| {
+                let lon = match bounds.west() + step * i as f64 {
+                    _ if i == count => bounds.east(),
+                    lon if lon > MAX_LON => lon - 360.0,
+                    lon => lon,
+                };
+                Geographic::new(lat, lon, bounds.floor())
+            })
+        };
+
+        let mut exterior: Vec<Geographic> = parallel(bounds.south()).collect();
+        let mut north: Vec<Geographic> = parallel(bounds.north()).collect();
+        north.reverse();
+        exterior.extend(north);
+
+        GeoPolygon::new(exterior, vec![])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ellipsoid::Ellipsoid;
+    use crate::geobounds::GeoBounds;
+    use crate::geographic::*;
+    use crate::geometry::polygon::*;
+
+    fn ring(points: &[(f64, f64)]) -> Vec<Geographic> {
+        points
+            .iter()
+            .map(|(lat, lon)| Geographic::new(*lat, *lon, 0.0))
+            .collect()
+    }
+
+    fn polygon(points: &[(f64, f64)]) -> GeoPolygon {
+        GeoPolygon::new(ring(points), vec![])
+    }
+
+    fn bounds(south: f64, west: f64, north: f64, east: f64) -> GeoBounds {
+        GeoBounds::new(
+            Geographic::new(south, west, 0.0),
+            Geographic::new(north, east, 0.0),
+        )
+    }
+
+    fn geo(lat: f64, lon: f64) -> Geographic {
+        Geographic::new(lat, lon, 0.0)
+    }
+
+    #[test]
+    fn new_removes_the_closing_point() {
+        let closed = polygon(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.0, 0.0)]);
+
+        assert_eq!(closed.exterior().len(), 3);
+        assert!(polygon(&[(0.0, 0.0), (0.0, 1.0), (0.0, 0.0)]).is_empty());
+    }
+
+    #[test]
+    fn area_matches_reference_values() {
+        // Reference values computed with GeographicLib, along geodesics of the ellipsoid.
+        let wgs84 = Ellipsoid::wgs84();
+        let area = |p: &[(f64, f64)]| polygon(p).area(&wgs84);
+
+        let square = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)];
+        let colorado = [
+            (41.0, -109.05),
+            (41.0, -102.05),
+            (37.0, -102.05),
+            (37.0, -109.05),
+        ];
+        let antimeridian = [
+            (-10.0, 170.0),
+            (-10.0, -170.0),
+            (10.0, -170.0),
+            (10.0, 170.0),
+        ];
+        let arctic = [(80.0, 0.0), (80.0, 90.0), (80.0, 180.0), (80.0, -90.0)];
+        let southern = [(-60.0, 0.0), (-60.0, -90.0), (-60.0, 180.0), (-60.0, 90.0)];
+
+        assert_relative_eq!(area(&square), 12_308_778_361.5, max_relative = 1e-3);
+        assert_relative_eq!(area(&colorado), 269_154_549_884.0, max_relative = 1e-3);
+        assert_relative_eq!(
+            area(&antimeridian),
+            4_948_480_469_169.5,
+            max_relative = 1e-3
+        );
+        assert_relative_eq!(area(&arctic), 2_507_270_031_169.9, max_relative = 1e-3);
+        assert_relative_eq!(area(&southern), 23_441_600_180_227.0, max_relative = 1e-3);
+    }
+
+    #[test]
+    fn area_excludes_holes() {
+        let sphere = Ellipsoid::unit_sphere();
+        let octant = ring(&[(0.0, 0.0), (0.0, 90.0), (90.0, 0.0)]);
+        let hole = ring(&[(10.0, 10.0), (10.0, 20.0), (20.0, 10.0)]);
+
+        let full = GeoPolygon::new(octant.clone(), vec![]).area(&sphere);
+        let holed = GeoPolygon::new(octant, vec![hole.clone()]).area(&sphere);
+
+        assert_relative_eq!(full, std::f64::consts::PI / 2.0, max_relative = 1e-12);
+        assert_relative_eq!(
+            holed,
+            full - GeoPolygon::new(hole, vec![]).area(&sphere),
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn contains_returns_correct_values() {
+        let square = ring(&[(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]);
+        let hole = ring(&[(4.0, 4.0), (4.0, 6.0), (6.0, 6.0), (6.0, 4.0)]);
+        let holed = GeoPolygon::new(square, vec![hole]);
+
+        assert!(holed.contains(geo(2.0, 2.0)));
+        assert!(!holed.contains(geo(5.0, 5.0)));
+        assert!(!holed.contains(geo(12.0, 5.0)));
+        assert!(!holed.contains(geo(5.0, -175.0)));
+    }
+
+    #[test]
+    fn contains_across_the_antimeridian_returns_correct_values() {
+        let p = polygon(&[
+            (-10.0, 170.0),
+            (-10.0, -170.0),
+            (10.0, -170.0),
+            (10.0, 170.0),
+        ]);
+
+        assert!(p.contains(geo(0.0, 180.0)));
+        assert!(p.contains(geo(0.0, -180.0)));
+        assert!(p.contains(geo(5.0, 175.0)));
+        assert!(p.contains(geo(5.0, -175.0)));
+        assert!(!p.contains(geo(5.0, 0.0)));
+        assert!(!p.contains(geo(5.0, -165.0)));
+    }
+
+    #[quickcheck]
+    fn polygons_of_bounds_contain_the_same_points(b: GeoBounds, x: u8, y: u8) -> bool {
+        let b = b.flatten();
+        let inner = b.sample(
+            f64::from(x) / 256.0 + 1e-3,
+            f64::from(y) / 256.0 + 1e-3,
+            0.0,
+        );
+        let p = GeoPolygon::from(b);
+
+        let above = b.north() + 1.0;
+        let outside = above > MAX_LAT || !p.contains(geo(above, b.center().lon()));
+
+        p.contains(inner) && outside
+    }
+
+    #[test]
+    fn rings_around_a_pole_contain_the_pole_on_their_left() {
+        let eastward = polygon(&[(80.0, 0.0), (80.0, 90.0), (80.0, 180.0), (80.0, -90.0)]);
+        let westward = polygon(&[(80.0, 0.0), (80.0, -90.0), (80.0, 180.0), (80.0, 90.0)]);
+
+        assert!(eastward.contains(geo(85.0, 45.0)));
+        assert!(eastward.contains(geo(89.9, 0.0)));
+        assert!(!eastward.contains(geo(0.0, 45.0)));
+        assert!(westward.contains(geo(0.0, 45.0)));
+        assert!(westward.contains(geo(-89.9, 0.0)));
+        assert!(!westward.contains(geo(85.0, 45.0)));
+
+        let bounds = eastward.bounds().unwrap();
+        assert_abs_diff_eq!(bounds.north(), MAX_LAT);
+        assert_abs_diff_eq!(bounds.span_lon(), 360.0);
+    }
+
+    #[test]
+    fn clip_returns_correct_values() {
+        let square = ring(&[(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]);
+        let hole = ring(&[(4.0, 4.0), (4.0, 6.0), (6.0, 6.0), (6.0, 4.0)]);
+        let holed = GeoPolygon::new(square, vec![hole]);
+
+        let result = holed.clip(&bounds(5.0, 5.0, 20.0, 20.0));
+        assert_eq!(result.len(), 1);
+        let part = &result.polygons()[0];
+        assert_eq!(part.holes().len(), 1);
+        // Bounds include the bulge of the great circle along the northern edge.
+        assert_abs_diff_eq!(
+            part.bounds().unwrap(),
+            bounds(5.0, 5.0, 10.0, 10.0),
+            epsilon = 1e-2
+        );
+        assert!(part.contains(geo(8.0, 8.0)));
+        assert!(!part.contains(geo(5.5, 5.5)));
+
+        assert!(holed.clip(&bounds(20.0, 20.0, 30.0, 30.0)).is_empty());
+    }
+
+    #[test]
+    fn clip_across_the_antimeridian_returns_correct_values() {
+        let p = polygon(&[
+            (-10.0, 170.0),
+            (-10.0, -170.0),
+            (10.0, -170.0),
+            (10.0, 170.0),
+        ]);
+
+        let result = p.clip(&bounds(0.0, 175.0, 20.0, -175.0));
+        assert_eq!(result.len(), 2);
+        assert_abs_diff_eq!(
+            result.polygons()[0].bounds().unwrap(),
+            bounds(0.0, 175.0, 10.0, 180.0),
+            epsilon = 1e-2
+        );
+        assert_abs_diff_eq!(
+            result.polygons()[1].bounds().unwrap(),
+            bounds(0.0, -180.0, 10.0, -175.0),
+            epsilon = 1e-2
+        );
+
+        let result = p.clip(&bounds(0.0, -175.0, 20.0, 0.0));
+        assert_eq!(result.len(), 1);
+        assert_abs_diff_eq!(
+            result.polygons()[0].bounds().unwrap(),
+            bounds(0.0, -175.0, 10.0, -170.0),
+            epsilon = 1e-2
+        );
+    }
+
+    #[quickcheck]
+    fn clipped_parts_contain_the_same_points(b: GeoBounds, a: Geographic) -> bool {
+        let p = polygon(&[
+            (-40.0, 100.0),
+            (-30.0, -150.0),
+            (50.0, -160.0),
+            (20.0, 170.0),
+        ]);
+        let a = a.flatten();
+        let b = b.flatten();
+        let clipped = p.clip(&b);
+
+        // Points on the edges of the bounds may be on the rings of the parts.
+        let on_edge = [b.south(), b.north()]
+            .iter()
+            .any(|lat| (a.lat() - lat).abs() < 1e-9)
+            || [b.west(), b.east()]
+                .iter()
+                .any(|lon| (a.lon() - lon).abs() < 1e-9);
+
+        on_edge || clipped.contains(a) == (p.contains(a) && b.contains(a))
+    }
+
+    #[quickcheck]
+    fn densify_preserves_the_area(a: Geographic, b: Geographic, c: Geographic) -> bool {
+        let original = GeoPolygon::new(vec![a.flatten(), b.flatten(), c.flatten()], vec![]);
+        let sphere = Ellipsoid::unit_sphere();
+
+        abs_diff_eq!(
+            original.densify(100_000.0).area(&sphere),
+            original.area(&sphere),
+            epsilon = 1e-9
+        )
+    }
+}
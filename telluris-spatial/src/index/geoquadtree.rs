@@ -1,3 +1,556 @@
-pub struct GeoQuadtree {
-    
-}
\ No newline at end of file
+use crate::angle::{Degrees, Radians};
+use crate::geobounds::GeoBounds;
+use crate::geographic::*;
+use crate::index::quadtreenode::{QuadtreeNode, MAX_DEPTH};
+use crate::index::{GeoIndex, Quadrant};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// The default number of items a node can hold before being split.
+pub const DEFAULT_NODE_CAPACITY: usize = 16;
+
+/// A spatial index over items located by a geographic coordinate.
+/// Each node stores up to a fixed number of items, and is split into four
+/// children when this capacity is exceeded. Nodes at `MAX_DEPTH` are never split.
+/// Distances are great-circle distances, in meters. Elevations are ignored.
+#[derive(Debug, Clone)]
+pub struct GeoQuadtree<T: GeoIndex> {
+    root: Node<T>,
+    capacity: usize,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<T: GeoIndex> {
+    address: QuadtreeNode,
+    bounds: GeoBounds,
+    items: Vec<T>,
+    children: Option<Box<[Node<T>; 4]>>,
+}
+
+impl<T: GeoIndex> GeoQuadtree<T> {
+    /// Creates an empty quadtree with the default node capacity.
+    pub fn new() -> Self {
+        Self::with_node_capacity(DEFAULT_NODE_CAPACITY)
+    }
+
+    /// Creates an empty quadtree whose nodes hold up to `capacity` items.
+    pub fn with_node_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        GeoQuadtree {
+            root: Node::new(QuadtreeNode::root()),
+            capacity,
+            len: 0,
+        }
+    }
+
+    /// Creates a quadtree holding the provided items. The tree is built top-down,
+    /// which is faster than inserting the items one after the other.
+    pub fn bulk_load(items: Vec<T>, capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        let len = items.len();
+        let mut root = Node::new(QuadtreeNode::root());
+        root.load(items, capacity);
+
+        GeoQuadtree {
+            root,
+            capacity,
+            len,
+        }
+    }
+
+    /// Returns the number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the tree contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximal number of items a node holds before being split.
+    pub fn node_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Inserts an item in the tree.
+    pub fn insert(&mut self, item: T) {
+        self.root.insert(item, self.capacity);
+        self.len += 1;
+    }
+
+    /// Removes the first item equal to the provided one, and returns it.
+    /// Nodes whose children hold no more than the node capacity are merged back.
+    pub fn remove(&mut self, item: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let removed = self.root.remove(item, self.capacity);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Returns all items located inside the bounds, borders included.
+    pub fn query(&self, bounds: &GeoBounds) -> Vec<&T> {
+        let mut result = Vec::new();
+        self.root.query(bounds, &mut result);
+        result
+    }
+
+    /// Returns all items located at most `radius` meters from the center.
+    pub fn query_radius(&self, center: Geographic, radius: f64) -> Vec<&T> {
+        let mut result = Vec::new();
+        self.root.query_radius(&center, radius, &mut result);
+        result
+    }
+
+    /// Returns the `k` items nearest to the provided coordinate,
+    /// ordered from the nearest to the farthest.
+    pub fn nearest(&self, point: Geographic, k: usize) -> Vec<&T> {
+        let mut result = Vec::with_capacity(k);
+        let mut heap = BinaryHeap::new();
+        heap.push(Candidate {
+            distance: 0.0,
+            entry: Entry::Node(&self.root),
+        });
+
+        while let Some(Candidate { entry, .. }) = heap.pop() {
+            if result.len() == k {
+                break;
+            }
+            match entry {
+                Entry::Item(item) => result.push(item),
+                Entry::Node(node) => {
+                    for item in &node.items {
+                        heap.push(Candidate {
+                            distance: point.great_circle_distance(&item.geo_index()),
+                            entry: Entry::Item(item),
+                        });
+                    }
+                    if let Some(children) = &node.children {
+                        for child in children.iter() {
+                            heap.push(Candidate {
+                                distance: min_distance(&point, &child.bounds),
+                                entry: Entry::Node(child),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<T: GeoIndex> Default for GeoQuadtree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: GeoIndex> Extend<T> for GeoQuadtree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+impl<T: GeoIndex> std::iter::FromIterator<T> for GeoQuadtree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::bulk_load(iter.into_iter().collect(), DEFAULT_NODE_CAPACITY)
+    }
+}
+
+impl<T: GeoIndex> Node<T> {
+    fn new(address: QuadtreeNode) -> Self {
+        Node {
+            address,
+            bounds: address.bounds(),
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn can_split(&self) -> bool {
+        self.address.depth() < MAX_DEPTH
+    }
+
+    /// Returns the index of the child that should hold the coordinate.
+    fn child_index(&self, geo: &Geographic) -> usize {
+        let center = self.bounds.center();
+        let quadrant = match (geo.lat() >= center.lat(), geo.lon() >= center.lon()) {
+            (true, false) => Quadrant::NorthWest,
+            (true, true) => Quadrant::NorthEast,
+            (false, false) => Quadrant::SouthWest,
+            (false, true) => Quadrant::SouthEast,
+        };
+        quadrant as usize
+    }
+
+    fn make_children(&self) -> Box<[Node<T>; 4]> {
+        let c = self.address.children().unwrap();
+
+        Box::new([
+            Node::new(c[0]),
+            Node::new(c[1]),
+            Node::new(c[2]),
+            Node::new(c[3]),
+        ])
+    }
+
+    fn insert(&mut self, item: T, capacity: usize) {
+        let i = self.child_index(&item.geo_index());
+        if let Some(children) = &mut self.children {
+            children[i].insert(item, capacity);
+            return;
+        }
+
+        self.items.push(item);
+
+        if self.items.len() > capacity && self.can_split() {
+            let items = std::mem::take(&mut self.items);
+            self.children = Some(self.make_children());
+            for item in items {
+                self.insert(item, capacity);
+            }
+        }
+    }
+
+    fn load(&mut self, items: Vec<T>, capacity: usize) {
+        if items.len() <= capacity || !self.can_split() {
+            self.items = items;
+            return;
+        }
+
+        let mut parts: [Vec<T>; 4] = Default::default();
+        for item in items {
+            parts[self.child_index(&item.geo_index())].push(item);
+        }
+
+        let mut children = self.make_children();
+        for (child, part) in children.iter_mut().zip(parts.iter_mut()) {
+            child.load(std::mem::take(part), capacity);
+        }
+        self.children = Some(children);
+    }
+
+    fn remove(&mut self, item: &T, capacity: usize) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let i = self.child_index(&item.geo_index());
+
+        let removed = match &mut self.children {
+            None => {
+                let position = self.items.iter().position(|x| x == item)?;
+                return Some(self.items.swap_remove(position));
+            }
+            Some(children) => children[i].remove(item, capacity)?,
+        };
+
+        self.try_merge(capacity);
+
+        Some(removed)
+    }
+
+    /// Merges the children back into this node if they are all leaves
+    /// and hold no more than `capacity` items.
+    fn try_merge(&mut self, capacity: usize) {
+        let mergeable = match &self.children {
+            None => false,
+            Some(children) => {
+                children.iter().all(|c| c.children.is_none())
+                    && children.iter().map(|c| c.items.len()).sum::<usize>() <= capacity
+            }
+        };
+
+        if mergeable {
+            let children = self.children.take().unwrap();
+            for mut child in Vec::from(children as Box<[Node<T>]>) {
+                self.items.append(&mut child.items);
+            }
+        }
+    }
+
+    fn query<'a>(&'a self, bounds: &GeoBounds, result: &mut Vec<&'a T>) {
+        if !overlaps(&self.bounds, bounds) {
+            return;
+        }
+
+        result.extend(self.items.iter().filter(|x| bounds.contains(x.geo_index())));
+
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                child.query(bounds, result);
+            }
+        }
+    }
+
+    fn query_radius<'a>(&'a self, center: &Geographic, radius: f64, result: &mut Vec<&'a T>) {
+        if min_distance(center, &self.bounds) > radius {
+            return;
+        }
+
+        result.extend(
+            self.items
+                .iter()
+                .filter(|x| center.great_circle_distance(&x.geo_index()) <= radius),
+        );
+
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                child.query_radius(center, radius, result);
+            }
+        }
+    }
+}
+
+/// Returns true if the node bounds overlap the query bounds horizontally, borders included.
+/// Node bounds never cross the antimeridian, but query bounds may.
+fn overlaps(node: &GeoBounds, query: &GeoBounds) -> bool {
+    let overlaps = |b: &GeoBounds| {
+        node.west() <= b.east()
+            && node.east() >= b.west()
+            && node.south() <= b.north()
+            && node.north() >= b.south()
+    };
+
+    match query.split_at_antimeridian() {
+        (western, Some(eastern)) => overlaps(&western) || overlaps(&eastern),
+        (bounds, None) => overlaps(&bounds),
+    }
+}
+
+/// Returns the smallest great-circle distance between the point and the bounds.
+fn min_distance(p: &Geographic, bounds: &GeoBounds) -> f64 {
+    let lat = p.lat();
+    let lon = p.lon();
+
+    if lon >= bounds.west() && lon <= bounds.east() {
+        let d_lat = (bounds.south() - lat).max(lat - bounds.north()).max(0.0);
+        return Degrees(d_lat).to_radians().value() * MEAN_EARTH_RADIUS;
+    }
+
+    // The point lies beside the bounds: the closest point is on the nearest meridian edge.
+    let delta = |edge: f64| Degrees(((lon - edge + 540.0) % 360.0 - 180.0).abs());
+    let edge = if delta(bounds.west()) < delta(bounds.east()) {
+        bounds.west()
+    } else {
+        bounds.east()
+    };
+    let d_lon = delta(edge).to_radians();
+
+    let south = Geographic::new(bounds.south(), edge, 0.0);
+    let north = Geographic::new(bounds.north(), edge, 0.0);
+    let corners = p.great_circle_distance(&south).min(p.great_circle_distance(&north));
+
+    if d_lon.value() < std::f64::consts::FRAC_PI_2 {
+        // The foot of the perpendicular from the point to the edge meridian.
+        let foot = Radians::atan(p.lat_rad().tan() / d_lon.cos()).to_degrees();
+        if foot.value() > bounds.south() && foot.value() < bounds.north() {
+            let cross_track = Radians::asin(p.lat_rad().cos() * d_lon.sin());
+            return (cross_track.value() * MEAN_EARTH_RADIUS).min(corners);
+        }
+    }
+
+    corners
+}
+
+enum Entry<'a, T: GeoIndex> {
+    Node(&'a Node<T>),
+    Item(&'a T),
+}
+
+/// An entry in the nearest neighbour search, ordered by increasing distance.
+struct Candidate<'a, T: GeoIndex> {
+    distance: f64,
+    entry: Entry<'a, T>,
+}
+
+impl<'a, T: GeoIndex> PartialEq for Candidate<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+impl<'a, T: GeoIndex> Eq for Candidate<'a, T> {}
+
+impl<'a, T: GeoIndex> PartialOrd for Candidate<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T: GeoIndex> Ord for Candidate<'a, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, so that the max-heap pops the nearest entry first.
+        other
+            .distance
+            .partial_cmp(&self.distance)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geobounds::GeoBounds;
+    use crate::geographic::*;
+    use crate::index::geoquadtree::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Marker(Geographic);
+
+    impl GeoIndex for Marker {
+        fn geo_index(&self) -> Geographic {
+            self.0
+        }
+    }
+
+    impl quickcheck::Arbitrary for Marker {
+        fn arbitrary<G: quickcheck::Gen>(g: &mut G) -> Self {
+            Marker(Geographic::arbitrary(g).flatten())
+        }
+    }
+
+    fn build(markers: &[Marker]) -> GeoQuadtree<Marker> {
+        let mut tree = GeoQuadtree::with_node_capacity(2);
+        tree.extend(markers.iter().cloned());
+        tree
+    }
+
+    #[test]
+    fn insert_splits_nodes_beyond_capacity() {
+        let mut tree = GeoQuadtree::with_node_capacity(1);
+        tree.insert(Marker(Geographic::new(10.0, 10.0, 0.0)));
+        assert!(tree.root.children.is_none());
+
+        tree.insert(Marker(Geographic::new(-10.0, -10.0, 0.0)));
+        assert!(tree.root.children.is_some());
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn remove_merges_nodes_back() {
+        let a = Marker(Geographic::new(10.0, 10.0, 0.0));
+        let b = Marker(Geographic::new(-10.0, -10.0, 0.0));
+        let mut tree = GeoQuadtree::with_node_capacity(1);
+        tree.insert(a.clone());
+        tree.insert(b.clone());
+
+        assert_eq!(tree.remove(&b), Some(b));
+        assert!(tree.root.children.is_none());
+        assert_eq!(tree.query(&GeoBounds::world()), vec![&a]);
+    }
+
+    #[test]
+    fn nearest_returns_items_by_increasing_distance() {
+        let markers = vec![
+            Marker(Geographic::new(0.0, 30.0, 0.0)),
+            Marker(Geographic::new(0.0, 10.0, 0.0)),
+            Marker(Geographic::new(0.0, -170.0, 0.0)),
+            Marker(Geographic::new(0.0, 20.0, 0.0)),
+        ];
+        let tree = GeoQuadtree::bulk_load(markers.clone(), 1);
+
+        let result = tree.nearest(Geographic::new(0.0, 179.0, 0.0), 2);
+
+        assert_eq!(result, vec![&markers[2], &markers[0]]);
+    }
+
+    #[quickcheck]
+    fn query_returns_the_same_items_as_a_linear_scan(markers: Vec<Marker>, bounds: GeoBounds) -> bool {
+        let bounds = GeoBounds::new(
+            Geographic::new(bounds.south(), bounds.west(), MIN_ALT),
+            Geographic::new(bounds.north(), bounds.east(), MAX_ALT),
+        );
+        let tree = build(&markers);
+
+        let expected = markers.iter().filter(|m| bounds.contains(m.0)).count();
+
+        tree.query(&bounds).len() == expected
+    }
+
+    #[quickcheck]
+    fn query_across_the_antimeridian_returns_the_same_items_as_a_linear_scan(
+        markers: Vec<Marker>,
+        bounds: GeoBounds,
+    ) -> bool {
+        // Swap the edges, so that the bounds cross the antimeridian.
+        let bounds = GeoBounds::new(
+            Geographic::new(bounds.south(), bounds.east(), MIN_ALT),
+            Geographic::new(bounds.north(), bounds.west(), MAX_ALT),
+        );
+        let tree = build(&markers);
+
+        let expected = markers.iter().filter(|m| bounds.contains(m.0)).count();
+
+        tree.query(&bounds).len() == expected
+    }
+
+    #[quickcheck]
+    fn query_radius_returns_the_same_items_as_a_linear_scan(
+        markers: Vec<Marker>,
+        center: Marker,
+        radius: u16,
+    ) -> bool {
+        let radius = f64::from(radius) * 1000.0;
+        let tree = build(&markers);
+
+        let expected = markers
+            .iter()
+            .filter(|m| center.0.great_circle_distance(&m.0) <= radius)
+            .count();
+
+        tree.query_radius(center.0, radius).len() == expected
+    }
+
+    #[quickcheck]
+    fn nearest_returns_the_k_closest_items(markers: Vec<Marker>, center: Marker, k: u8) -> bool {
+        let k = k as usize % 8;
+        let tree = GeoQuadtree::bulk_load(markers.clone(), 2);
+
+        let mut distances: Vec<f64> = markers
+            .iter()
+            .map(|m| center.0.great_circle_distance(&m.0))
+            .collect();
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let result: Vec<f64> = tree
+            .nearest(center.0, k)
+            .iter()
+            .map(|m| center.0.great_circle_distance(&m.0))
+            .collect();
+
+        result.len() == k.min(markers.len())
+            && result
+                .iter()
+                .zip(distances.iter())
+                .all(|(a, b)| abs_diff_eq!(a, b, epsilon = 0.001))
+    }
+
+    #[quickcheck]
+    fn removing_all_items_empties_the_tree(markers: Vec<Marker>) -> bool {
+        let mut tree = build(&markers);
+
+        let all_removed = markers.iter().all(|m| tree.remove(m).is_some());
+
+        all_removed && tree.is_empty() && tree.root.children.is_none()
+    }
+
+    #[quickcheck]
+    fn bulk_load_and_insert_find_the_same_items(markers: Vec<Marker>, bounds: GeoBounds) -> bool {
+        let bounds = bounds.grow(1.0, 1.0);
+        let loaded = GeoQuadtree::bulk_load(markers.clone(), 2);
+        let inserted = build(&markers);
+
+        loaded.len() == inserted.len()
+            && loaded.query(&bounds).len() == inserted.query(&bounds).len()
+    }
+}
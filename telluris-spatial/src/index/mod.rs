@@ -1,9 +1,11 @@
 //! Spatial indexes.
 
 pub mod geoquadtree;
+pub mod quadtreenode;
 use crate::geographic::Geographic;
 
 // A division of a planar space
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Quadrant {
     NorthWest = 0,
     NorthEast = 1,
@@ -11,6 +13,25 @@ pub enum Quadrant {
     SouthEast = 3,
 }
 
+impl Quadrant {
+    /// All quadrants, ordered by their discriminant.
+    pub const ALL: [Quadrant; 4] = [
+        Quadrant::NorthWest,
+        Quadrant::NorthEast,
+        Quadrant::SouthWest,
+        Quadrant::SouthEast,
+    ];
+}
+
+/// A cardinal direction on the surface.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
 pub trait GeoIndex {
     fn geo_index(&self) -> Geographic;
-}
\ No newline at end of file
+}
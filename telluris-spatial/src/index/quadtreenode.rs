@@ -1,6 +1,23 @@
+use crate::geobounds::GeoBounds;
+use crate::geographic::*;
+use crate::index::{Direction, Quadrant};
+use std::collections::VecDeque;
+
+use quickcheck::{Arbitrary, Gen};
+use rand::Rng;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// The deepest level supported by the quadtree.
 pub const MAX_DEPTH : u32 = 23;
 
-#[derive(Debug)]
+/// The address of a node in a geographic quadtree.
+/// The root node (at depth zero) covers the whole world. At depth `d`, the
+/// world is divided into `2^d` rows and `2^d` columns of equal angular size.
+/// Rows are numbered from north to south, and columns from west to east.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct QuadtreeNode {
     row: u32,
     column: u32,
@@ -8,9 +25,411 @@ pub struct QuadtreeNode {
 }
 
 impl QuadtreeNode {
-    pub fn new(row: u32, column: u32, depth: u32) {
+    /// Creates the node at the given row, column and depth.
+    pub fn new(row: u32, column: u32, depth: u32) -> Self {
         assert!(depth <= MAX_DEPTH);
+        assert!(row < Self::dimension(depth), "row is out of range");
+        assert!(column < Self::dimension(depth), "column is out of range");
+
+        QuadtreeNode { row, column, depth }
+    }
+
+    /// Returns the root node, encompassing the whole world.
+    pub fn root() -> Self {
+        QuadtreeNode {
+            row: 0,
+            column: 0,
+            depth: 0,
+        }
+    }
+
+    /// Returns the number of rows (and columns) at the given depth.
+    pub fn dimension(depth: u32) -> u32 {
+        assert!(depth <= MAX_DEPTH);
+
+        1 << depth
+    }
+
+    /// Returns the row of this node, starting from the north.
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    /// Returns the column of this node, starting from the west.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
 
-        unimplemented!();
+    /// Returns the depth of this node. The root node has a depth of zero.
+    pub fn depth(&self) -> u32 {
+        self.depth
     }
-}
\ No newline at end of file
+
+    /// Returns true if this node is the root node.
+    pub fn is_root(&self) -> bool {
+        self.depth == 0
+    }
+
+    /// Returns the parent of this node, or `None` for the root node.
+    pub fn parent(&self) -> Option<Self> {
+        if self.is_root() {
+            return None;
+        }
+
+        Some(QuadtreeNode {
+            row: self.row / 2,
+            column: self.column / 2,
+            depth: self.depth - 1,
+        })
+    }
+
+    /// Returns the quadrant this node occupies in its parent,
+    /// or `None` for the root node.
+    pub fn quadrant(&self) -> Option<Quadrant> {
+        if self.is_root() {
+            return None;
+        }
+
+        let quadrant = match (self.row % 2, self.column % 2) {
+            (0, 0) => Quadrant::NorthWest,
+            (0, _) => Quadrant::NorthEast,
+            (_, 0) => Quadrant::SouthWest,
+            _ => Quadrant::SouthEast,
+        };
+
+        Some(quadrant)
+    }
+
+    /// Returns the child of this node in the given quadrant,
+    /// or `None` if this node is at `MAX_DEPTH`.
+    pub fn child(&self, quadrant: Quadrant) -> Option<Self> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+
+        let (dy, dx) = match quadrant {
+            Quadrant::NorthWest => (0, 0),
+            Quadrant::NorthEast => (0, 1),
+            Quadrant::SouthWest => (1, 0),
+            Quadrant::SouthEast => (1, 1),
+        };
+
+        Some(QuadtreeNode {
+            row: self.row * 2 + dy,
+            column: self.column * 2 + dx,
+            depth: self.depth + 1,
+        })
+    }
+
+    /// Returns the four children of this node, ordered as in `Quadrant::ALL`,
+    /// or `None` if this node is at `MAX_DEPTH`.
+    pub fn children(&self) -> Option<[Self; 4]> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+
+        let child = |q| self.child(q).unwrap();
+
+        Some([
+            child(Quadrant::NorthWest),
+            child(Quadrant::NorthEast),
+            child(Quadrant::SouthWest),
+            child(Quadrant::SouthEast),
+        ])
+    }
+
+    /// Returns the adjacent node at the same depth in the given direction.
+    /// Eastern and western neighbors wrap around the antimeridian.
+    /// Returns `None` when moving north of the northernmost row,
+    /// or south of the southernmost row.
+    pub fn neighbor(&self, direction: Direction) -> Option<Self> {
+        let dim = Self::dimension(self.depth);
+
+        let (row, column) = match direction {
+            Direction::North if self.row == 0 => return None,
+            Direction::North => (self.row - 1, self.column),
+            Direction::South if self.row + 1 == dim => return None,
+            Direction::South => (self.row + 1, self.column),
+            Direction::East => (self.row, (self.column + 1) % dim),
+            Direction::West => (self.row, (self.column + dim - 1) % dim),
+        };
+
+        Some(QuadtreeNode {
+            row,
+            column,
+            depth: self.depth,
+        })
+    }
+
+    /// Returns the geographic bounds of this node. The bounds span
+    /// the whole supported altitude range, as in `GeoBounds::world`.
+    pub fn bounds(&self) -> GeoBounds {
+        let dim = f64::from(Self::dimension(self.depth));
+        let span_lon = (MAX_LON - MIN_LON) / dim;
+        let span_lat = (MAX_LAT - MIN_LAT) / dim;
+
+        let west = MIN_LON + f64::from(self.column) * span_lon;
+        let north = MAX_LAT - f64::from(self.row) * span_lat;
+        let east = MIN_LON + f64::from(self.column + 1) * span_lon;
+        let south = MAX_LAT - f64::from(self.row + 1) * span_lat;
+
+        GeoBounds::new(
+            Geographic::new(south, west, MIN_ALT),
+            Geographic::new(north, east, MAX_ALT),
+        )
+    }
+
+    /// Returns an iterator over the ancestors of this node, starting
+    /// with its parent and ending with the root node.
+    pub fn ancestors(&self) -> Ancestors {
+        Ancestors {
+            current: self.parent(),
+        }
+    }
+
+    /// Returns an iterator over the descendants of this node, down to `depth`
+    /// levels below it, in breadth-first order. The node itself is not included.
+    pub fn descendants(&self, depth: u32) -> Descendants {
+        assert!(
+            self.depth + depth <= MAX_DEPTH,
+            "descendants cannot be deeper than MAX_DEPTH"
+        );
+
+        let mut queue = VecDeque::new();
+        if depth > 0 {
+            queue.extend(self.children().unwrap().iter());
+        }
+
+        Descendants {
+            queue,
+            max_depth: self.depth + depth,
+        }
+    }
+}
+
+/// Iterator over the ancestors of a `QuadtreeNode`.
+#[derive(Debug, Clone)]
+pub struct Ancestors {
+    current: Option<QuadtreeNode>,
+}
+
+impl Iterator for Ancestors {
+    type Item = QuadtreeNode;
+
+    fn next(&mut self) -> Option<QuadtreeNode> {
+        let result = self.current;
+        self.current = result.and_then(|n| n.parent());
+        result
+    }
+}
+
+/// Breadth-first iterator over the descendants of a `QuadtreeNode`.
+#[derive(Debug, Clone)]
+pub struct Descendants {
+    queue: VecDeque<QuadtreeNode>,
+    max_depth: u32,
+}
+
+impl Iterator for Descendants {
+    type Item = QuadtreeNode;
+
+    fn next(&mut self) -> Option<QuadtreeNode> {
+        let node = self.queue.pop_front()?;
+        if node.depth() < self.max_depth {
+            self.queue.extend(node.children().unwrap().iter());
+        }
+        Some(node)
+    }
+}
+
+/// Deserializes a node, whose depth must not exceed `MAX_DEPTH`, and whose row and
+/// column must exist at this depth.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for QuadtreeNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "QuadtreeNode", deny_unknown_fields)]
+        struct Fields {
+            row: u32,
+            column: u32,
+            depth: u32,
+        }
+
+        let f = Fields::deserialize(deserializer)?;
+        if f.depth > MAX_DEPTH {
+            return Err(de::Error::custom(format_args!(
+                "depth {} is greater than {}",
+                f.depth, MAX_DEPTH
+            )));
+        }
+        if f.row >= Self::dimension(f.depth) || f.column >= Self::dimension(f.depth) {
+            return Err(de::Error::custom(format_args!(
+                "node ({}, {}) does not exist at depth {}",
+                f.row, f.column, f.depth
+            )));
+        }
+
+        Ok(QuadtreeNode::new(f.row, f.column, f.depth))
+    }
+}
+
+impl Arbitrary for QuadtreeNode {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let depth = g.gen_range(0, MAX_DEPTH + 1);
+        let dim = Self::dimension(depth);
+        let row = g.gen_range(0, dim);
+        let column = g.gen_range(0, dim);
+
+        Self::new(row, column, depth)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geographic::*;
+    use crate::index::quadtreenode::*;
+
+    #[test]
+    fn root_bounds_cover_the_world() {
+        let b = QuadtreeNode::root().bounds();
+
+        assert_abs_diff_eq!(b, GeoBounds::world(), epsilon = 0.000_001);
+    }
+
+    #[test]
+    fn children_of_root_have_correct_bounds() {
+        let children = QuadtreeNode::root().children().unwrap();
+
+        let nw = children[Quadrant::NorthWest as usize].bounds();
+        assert_abs_diff_eq!(nw.west(), MIN_LON);
+        assert_abs_diff_eq!(nw.east(), 0.0);
+        assert_abs_diff_eq!(nw.south(), 0.0);
+        assert_abs_diff_eq!(nw.north(), MAX_LAT);
+
+        let se = children[Quadrant::SouthEast as usize].bounds();
+        assert_abs_diff_eq!(se.west(), 0.0);
+        assert_abs_diff_eq!(se.east(), MAX_LON);
+        assert_abs_diff_eq!(se.south(), MIN_LAT);
+        assert_abs_diff_eq!(se.north(), 0.0);
+    }
+
+    #[test]
+    fn node_at_max_depth_has_no_children() {
+        let n = QuadtreeNode::new(0, 0, MAX_DEPTH);
+
+        assert!(n.children().is_none());
+        assert!(n.child(Quadrant::NorthWest).is_none());
+    }
+
+    #[test]
+    fn west_neighbor_wraps_across_the_antimeridian() {
+        let n = QuadtreeNode::new(1, 0, 2);
+
+        assert_eq!(n.neighbor(Direction::West), Some(QuadtreeNode::new(1, 3, 2)));
+        assert_eq!(
+            QuadtreeNode::new(1, 3, 2).neighbor(Direction::East),
+            Some(n)
+        );
+    }
+
+    #[test]
+    fn polar_nodes_have_no_neighbor_beyond_the_pole() {
+        assert!(QuadtreeNode::new(0, 2, 2).neighbor(Direction::North).is_none());
+        assert!(QuadtreeNode::new(3, 2, 2).neighbor(Direction::South).is_none());
+    }
+
+    #[quickcheck]
+    fn children_have_self_as_parent(n: QuadtreeNode) -> bool {
+        match n.children() {
+            None => n.depth() == MAX_DEPTH,
+            Some(children) => children.iter().all(|c| c.parent() == Some(n)),
+        }
+    }
+
+    #[quickcheck]
+    fn child_quadrant_matches_requested_quadrant(n: QuadtreeNode) -> bool {
+        if n.depth() == MAX_DEPTH {
+            return true;
+        }
+
+        Quadrant::ALL
+            .iter()
+            .all(|q| n.child(*q).unwrap().quadrant() == Some(*q))
+    }
+
+    #[quickcheck]
+    fn children_are_contained_in_parent_bounds(n: QuadtreeNode) -> bool {
+        if n.depth() == MAX_DEPTH {
+            return true;
+        }
+
+        let parent = n.bounds().grow(0.000_001, 0.000_001);
+
+        n.children().unwrap().iter().all(|c| {
+            let b = c.bounds();
+            parent.contains(Geographic::new(b.south(), b.west(), b.floor()))
+                && parent.contains(Geographic::new(b.north(), b.east(), b.top()))
+        })
+    }
+
+    #[quickcheck]
+    fn bounds_contain_their_center(n: QuadtreeNode) -> bool {
+        let b = n.bounds();
+        b.contains(b.center())
+    }
+
+    #[quickcheck]
+    fn opposite_neighbors_cancel_out(n: QuadtreeNode) -> bool {
+        let east_west = n
+            .neighbor(Direction::East)
+            .and_then(|e| e.neighbor(Direction::West));
+
+        let north_south = n
+            .neighbor(Direction::North)
+            .and_then(|e| e.neighbor(Direction::South));
+
+        east_west == Some(n) && (n.row() == 0 || north_south == Some(n))
+    }
+
+    #[quickcheck]
+    fn ancestors_end_at_root(n: QuadtreeNode) -> bool {
+        let ancestors: Vec<QuadtreeNode> = n.ancestors().collect();
+
+        ancestors.len() == n.depth() as usize
+            && (n.is_root() || *ancestors.last().unwrap() == QuadtreeNode::root())
+    }
+
+    #[quickcheck]
+    fn descendants_have_self_as_ancestor(n: QuadtreeNode, depth: u8) -> bool {
+        let depth = u32::from(depth % 3).min(MAX_DEPTH - n.depth());
+        let expected: usize = (1..=depth).map(|d| 4usize.pow(d)).sum();
+
+        let descendants: Vec<QuadtreeNode> = n.descendants(depth).collect();
+
+        descendants.len() == expected
+            && descendants
+                .iter()
+                .all(|d| d.ancestors().any(|a| a == n))
+    }
+
+    #[cfg(feature = "serde")]
+    #[quickcheck]
+    fn deserialize_is_the_inverse_of_serialize(node: QuadtreeNode) -> bool {
+        let json = serde_json::to_string(&node).unwrap();
+
+        serde_json::from_str::<QuadtreeNode>(&json).unwrap() == node
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_validates_the_address() {
+        let parse = |s| serde_json::from_str::<QuadtreeNode>(s);
+
+        assert_eq!(
+            parse(r#"{"row": 1, "column": 3, "depth": 2}"#).unwrap(),
+            QuadtreeNode::new(1, 3, 2)
+        );
+        assert!(parse(r#"{"row": 4, "column": 0, "depth": 2}"#).is_err());
+        assert!(parse(r#"{"row": 0, "column": 0, "depth": 24}"#).is_err());
+    }
+}
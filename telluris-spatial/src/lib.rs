@@ -5,11 +5,29 @@
 //! Some problems are more easily solved using geographic types, for example
 //! modeling the earth's surface. Other problems are more naturally expressed
 //! in a cartesian frame, such as horizon culling.
+//!
+//! Angles are stored and exchanged in degrees, as plain `f64` values.
+//! Whenever an angle is fed to a trigonometric function, it goes through the
+//! `Degrees` and `Radians` types of the `angle` module, so that every unit
+//! conversion is explicit. For example, `Geographic::lat` returns degrees,
+//! and `Geographic::lat_rad` returns the same latitude as `Radians`.
 
+pub mod angle;
+pub mod culling;
+pub mod datum;
+pub mod ellipsoid;
+pub mod formats;
+pub mod geodesic;
 pub mod geographic;
 pub mod geobounds;
+pub mod geoid;
+pub mod geometry;
 pub mod index;
+pub mod notation;
+pub mod ray;
+pub mod route;
 pub mod transformations;
+pub mod volumes;
 
 #[cfg(test)]
 #[macro_use]
@@ -0,0 +1,373 @@
+//! Coordinates written as angles: decimal degrees and degrees-minutes-seconds.
+//! The parser accepts both notations, with either signs or hemisphere letters,
+//! such as `48.8582, 2.2945`, `N 48.8582 E 2.2945`, `48°51.493'N 2°17.67'E`,
+//! `48°51'29.6"N 2°17'40.2"E` or `48 51 29.6 2 17 40.2`.
+
+use crate::geographic::*;
+use crate::notation::ParseError;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Token {
+    Number(f64, bool),
+    Sign(bool),
+    Unit(usize),
+    Hemisphere(char),
+    Separator,
+}
+
+/// The parts of an angle, before they are assigned to an axis.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Angle {
+    degrees: f64,
+    negative: bool,
+    hemisphere: Option<char>,
+}
+
+impl Angle {
+    fn is_latitude(&self) -> Option<bool> {
+        match self.hemisphere {
+            Some('N') | Some('S') => Some(true),
+            Some(_) => Some(false),
+            None => None,
+        }
+    }
+
+    fn value(&self) -> f64 {
+        match self.hemisphere {
+            Some('S') | Some('W') => -self.degrees,
+            _ if self.negative => -self.degrees,
+            _ => self.degrees,
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&d) = chars.peek() {
+                if d.is_ascii_digit() || d == '.' {
+                    number.push(d);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let value = number.parse().map_err(|_| ParseError::InvalidSyntax)?;
+            tokens.push(Token::Number(value, number.contains('.')));
+            continue;
+        }
+
+        chars.next();
+        let token = match c {
+            '+' => Token::Sign(false),
+            '-' | '−' => Token::Sign(true),
+            '°' | 'º' | '˚' => Token::Unit(0),
+            '′' | '’' => Token::Unit(1),
+            '\'' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                Token::Unit(2)
+            }
+            '\'' => Token::Unit(1),
+            '"' | '″' | '”' => Token::Unit(2),
+            'N' | 'S' | 'E' | 'W' | 'n' | 's' | 'e' | 'w' => {
+                Token::Hemisphere(c.to_ascii_uppercase())
+            }
+            ',' | ';' => Token::Separator,
+            c if c.is_whitespace() => continue,
+            _ => return Err(ParseError::InvalidSyntax),
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Reads an angle starting at the specified token. Returns the angle and the next token.
+fn angle(tokens: &[Token], start: usize, max_parts: usize) -> Result<(Angle, usize), ParseError> {
+    let mut i = start;
+    let token = |i: usize| tokens.get(i).copied();
+
+    let mut hemisphere = None;
+    if let Some(Token::Hemisphere(h)) = token(i) {
+        hemisphere = Some(h);
+        i += 1;
+    }
+
+    let mut negative = false;
+    if let Some(Token::Sign(s)) = token(i) {
+        if s && hemisphere.is_some() {
+            return Err(ParseError::InvalidSyntax);
+        }
+        negative = s;
+        i += 1;
+    }
+
+    // Degrees, minutes and seconds. Only the last part may have decimals.
+    let mut parts: Vec<f64> = Vec::with_capacity(3);
+    let mut fractional = false;
+    while parts.len() < max_parts && !fractional {
+        let (value, has_decimals) = match token(i) {
+            Some(Token::Number(v, d)) => (v, d),
+            _ => break,
+        };
+
+        let unit = match token(i + 1) {
+            // An explicit degree starts the next angle.
+            Some(Token::Unit(0)) if !parts.is_empty() => break,
+            Some(Token::Unit(u)) if u != parts.len() => return Err(ParseError::InvalidSyntax),
+            Some(Token::Unit(_)) => 2,
+            _ => 1,
+        };
+
+        parts.push(value);
+        fractional = has_decimals;
+        i += unit;
+    }
+
+    if parts.is_empty() {
+        return Err(ParseError::InvalidSyntax);
+    }
+
+    if parts.iter().skip(1).any(|p| *p >= 60.0) {
+        return Err(ParseError::OutOfRange);
+    }
+
+    if hemisphere.is_none() {
+        if let Some(Token::Hemisphere(h)) = token(i) {
+            if negative {
+                return Err(ParseError::InvalidSyntax);
+            }
+            hemisphere = Some(h);
+            i += 1;
+        }
+    }
+
+    let degrees = parts
+        .iter()
+        .zip(&[1.0, 60.0, 3600.0])
+        .map(|(p, d)| p / d)
+        .sum::<f64>();
+
+    Ok((
+        Angle {
+            degrees,
+            negative,
+            hemisphere,
+        },
+        i,
+    ))
+}
+
+/// Parses coordinates written in decimal degrees, or in degrees, minutes and seconds.
+/// The latitude comes first, unless hemisphere letters specify otherwise.
+pub fn parse(s: &str) -> Result<Geographic, ParseError> {
+    let tokens = tokenize(s)?;
+
+    // Without units, the number of parts is deduced from the number of values.
+    let has_units = tokens.iter().any(|t| matches!(t, Token::Unit(_)));
+    let numbers = tokens
+        .iter()
+        .filter(|t| matches!(t, Token::Number(_, _)))
+        .count();
+    let max_parts = if has_units {
+        3
+    } else if numbers % 2 == 0 && numbers <= 6 {
+        numbers / 2
+    } else {
+        return Err(ParseError::InvalidSyntax);
+    };
+
+    let mut angles = Vec::with_capacity(2);
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i] == Token::Separator {
+            if angles.len() != 1 {
+                return Err(ParseError::InvalidSyntax);
+            }
+            i += 1;
+        }
+
+        let (a, next) = angle(&tokens, i, max_parts)?;
+        angles.push(a);
+        i = next;
+    }
+
+    let (lat, lon) = match angles.as_slice() {
+        [a, b] => match (a.is_latitude(), b.is_latitude()) {
+            (Some(x), Some(y)) if x == y => return Err(ParseError::InvalidSyntax),
+            (Some(false), _) | (_, Some(true)) => (b, a),
+            _ => (a, b),
+        },
+        _ => return Err(ParseError::InvalidSyntax),
+    };
+
+    let (lat, lon) = (lat.value(), lon.value());
+    if !(MIN_LAT..=MAX_LAT).contains(&lat) || !(MIN_LON..=MAX_LON).contains(&lon) {
+        return Err(ParseError::OutOfRange);
+    }
+
+    Ok(Geographic::new(lat, lon, 0.0))
+}
+
+pub(crate) fn format_decimal(
+    f: &mut fmt::Formatter,
+    geo: Geographic,
+    precision: usize,
+) -> fmt::Result {
+    write!(
+        f,
+        "{:.*}, {:.*}",
+        precision,
+        geo.lat(),
+        precision,
+        geo.lon()
+    )
+}
+
+pub(crate) fn format_decimal_hemisphere(
+    f: &mut fmt::Formatter,
+    geo: Geographic,
+    precision: usize,
+) -> fmt::Result {
+    let (ns, ew) = hemispheres(geo);
+
+    write!(
+        f,
+        "{:.*}°{} {:.*}°{}",
+        precision,
+        geo.lat().abs(),
+        ns,
+        precision,
+        geo.lon().abs(),
+        ew
+    )
+}
+
+pub(crate) fn format_dms(f: &mut fmt::Formatter, geo: Geographic, precision: usize) -> fmt::Result {
+    let (ns, ew) = hemispheres(geo);
+
+    write_dms(f, geo.lat().abs(), precision)?;
+    write!(f, "{} ", ns)?;
+    write_dms(f, geo.lon().abs(), precision)?;
+    write!(f, "{}", ew)
+}
+
+fn hemispheres(geo: Geographic) -> (char, char) {
+    let ns = if geo.lat() < 0.0 { 'S' } else { 'N' };
+    let ew = if geo.lon() < 0.0 { 'W' } else { 'E' };
+
+    (ns, ew)
+}
+
+fn write_dms(f: &mut fmt::Formatter, degrees: f64, precision: usize) -> fmt::Result {
+    // Beyond nanoseconds of arc, the number of units would not fit in 64 bits.
+    let precision = precision.min(9);
+
+    // Round before splitting, so that 59.96" are written as 1' rather than 60.0".
+    let scale = 10f64.powi(precision as i32);
+    let total = (degrees * 3600.0 * scale).round() as u64;
+    let per_degree = (3600.0 * scale) as u64;
+    let per_minute = (60.0 * scale) as u64;
+
+    let d = total / per_degree;
+    let m = (total % per_degree) / per_minute;
+    let s = (total % per_minute) as f64 / scale;
+
+    write!(f, "{}°{}'{:.*}\"", d, m, precision, s)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::notation::dms::*;
+    use crate::notation::Notation;
+
+    fn parsed(s: &str) -> (f64, f64) {
+        let geo = parse(s).unwrap();
+        (geo.lat(), geo.lon())
+    }
+
+    #[test]
+    fn parse_reads_decimal_degrees() {
+        assert_eq!(parsed("48.8582, 2.2945"), (48.8582, 2.2945));
+        assert_eq!(parsed("48.8582 2.2945"), (48.8582, 2.2945));
+        assert_eq!(parsed("-33.8568;151.2153"), (-33.8568, 151.2153));
+        assert_eq!(parsed("48.8582°N 2.2945°W"), (48.8582, -2.2945));
+        assert_eq!(parsed("N 48.8582 W 2.2945"), (48.8582, -2.2945));
+        assert_eq!(parsed("33.8568 s, 151.2153 e"), (-33.8568, 151.2153));
+        assert_eq!(parsed("151.2153E 33.8568S"), (-33.8568, 151.2153));
+        assert_eq!(parsed("45 -120"), (45.0, -120.0));
+    }
+
+    #[test]
+    fn parse_reads_degrees_minutes_seconds() {
+        let eq = |s, lat, lon| {
+            let (a, b) = parsed(s);
+            abs_diff_eq!(a, lat, epsilon = 1e-9) && abs_diff_eq!(b, lon, epsilon = 1e-9)
+        };
+        let (lat, lon) = (
+            48.0 + 51.0 / 60.0 + 29.6 / 3600.0,
+            2.0 + 17.0 / 60.0 + 40.2 / 3600.0,
+        );
+
+        assert!(eq("48°51'29.6\"N 2°17'40.2\"E", lat, lon));
+        assert!(eq("48°51′29.6″N, 2°17′40.2″E", lat, lon));
+        assert!(eq("48°51'29.6''N 2°17'40.2''E", lat, lon));
+        assert!(eq("2°17'40.2\"E 48°51'29.6\"N", lat, lon));
+        assert!(eq("48 51 29.6 N 2 17 40.2 E", lat, lon));
+        assert!(eq("48 51 29.6, -2 17 40.2", lat, -lon));
+        assert!(eq("48°51.5'S 2°17.5'W", -48.858_333_333, -2.291_666_667));
+        assert!(eq("48 51.5 2 17.5", 48.858_333_333, 2.291_666_667));
+        assert!(eq("48° 2°", 48.0, 2.0));
+    }
+
+    #[test]
+    fn parse_returns_errors() {
+        let syntax = Err(ParseError::InvalidSyntax);
+        let range = Err(ParseError::OutOfRange);
+
+        assert_eq!(parse(""), syntax);
+        assert_eq!(parse("48.8582"), syntax);
+        assert_eq!(parse("1 2 3"), syntax);
+        assert_eq!(parse("48.8582N 2.2945S"), syntax);
+        assert_eq!(parse("-48.8582N 2.2945E"), syntax);
+        assert_eq!(parse("48\"51' 2°"), syntax);
+        assert_eq!(parse("48.5 51 2 17"), syntax);
+        assert_eq!(parse("48.8582, 2.2945, 3.0"), syntax);
+        assert_eq!(parse("91.0, 0.0"), range);
+        assert_eq!(parse("0.0, -180.5"), range);
+        assert_eq!(parse("48°60'N 2°E"), range);
+    }
+
+    #[test]
+    fn dms_rounds_seconds_up_to_the_next_minute() {
+        let geo = Geographic::new(-0.999_99, -179.999_999, 0.0);
+
+        assert_eq!(
+            format!("{}", geo.display(Notation::Dms)),
+            "1°0'0.0\"S 180°0'0.0\"W"
+        );
+    }
+
+    #[quickcheck]
+    fn parse_is_the_inverse_of_format(geo: Geographic, precision: u8) -> bool {
+        let precision = usize::from(precision % 10);
+        let epsilon = 10f64.powi(-(precision as i32));
+
+        [
+            Notation::Decimal,
+            Notation::DecimalHemisphere,
+            Notation::Dms,
+        ]
+        .iter()
+        .all(|n| {
+            let result = parse(&format!("{:.*}", precision, geo.display(*n))).unwrap();
+            abs_diff_eq!(result.lat(), geo.lat(), epsilon = epsilon)
+                && abs_diff_eq!(result.lon(), geo.lon(), epsilon = epsilon)
+        })
+    }
+}
@@ -0,0 +1,160 @@
+//! Geohashes, which encode a cell of a hierarchical grid as a string of base 32
+//! characters. Each character subdivides the cell of the previous characters into
+//! 32 cells, by interleaving bits of longitude and latitude, starting with the longitude.
+
+use crate::geobounds::GeoBounds;
+use crate::geographic::*;
+use crate::notation::ParseError;
+
+/// The characters of the geohash alphabet, which omits `a`, `i`, `l` and `o`.
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// The maximal length of geohashes, beyond which cells are smaller than the
+/// precision of `f64` coordinates.
+pub const MAX_GEOHASH_LENGTH: usize = 22;
+
+/// Returns the geohash of the specified length of the cell that contains the coordinate.
+/// Lengths are clamped to `MAX_GEOHASH_LENGTH`.
+pub fn encode(geo: Geographic, length: usize) -> String {
+    let length = length.min(MAX_GEOHASH_LENGTH);
+    let mut hash = String::with_capacity(length);
+
+    let (mut south, mut north) = (MIN_LAT, MAX_LAT);
+    let (mut west, mut east) = (MIN_LON, MAX_LON);
+    let mut even = true;
+
+    for _ in 0..length {
+        let mut index = 0;
+        for _ in 0..5 {
+            let (value, min, max) = if even {
+                (geo.lon(), &mut west, &mut east)
+            } else {
+                (geo.lat(), &mut south, &mut north)
+            };
+
+            let mid = (*min + *max) / 2.0;
+            index <<= 1;
+            if value >= mid {
+                index |= 1;
+                *min = mid;
+            } else {
+                *max = mid;
+            }
+            even = !even;
+        }
+        hash.push(char::from(ALPHABET[index]));
+    }
+
+    hash
+}
+
+/// Returns the cell of the geohash, at zero elevation. Geohashes are case insensitive.
+pub fn decode(hash: &str) -> Result<GeoBounds, ParseError> {
+    let hash = hash.trim();
+    if hash.is_empty() || hash.len() > MAX_GEOHASH_LENGTH {
+        return Err(ParseError::InvalidSyntax);
+    }
+
+    let (mut south, mut north) = (MIN_LAT, MAX_LAT);
+    let (mut west, mut east) = (MIN_LON, MAX_LON);
+    let mut even = true;
+
+    for c in hash.chars() {
+        if !c.is_ascii() {
+            return Err(ParseError::InvalidSyntax);
+        }
+        let c = c.to_ascii_lowercase() as u8;
+        let index = ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or(ParseError::InvalidSyntax)?;
+
+        for bit in (0..5).rev() {
+            let (min, max) = if even {
+                (&mut west, &mut east)
+            } else {
+                (&mut south, &mut north)
+            };
+
+            let mid = (*min + *max) / 2.0;
+            if index & (1 << bit) != 0 {
+                *min = mid;
+            } else {
+                *max = mid;
+            }
+            even = !even;
+        }
+    }
+
+    Ok(GeoBounds::new(
+        Geographic::new(south, west, 0.0),
+        Geographic::new(north, east, 0.0),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geographic::*;
+    use crate::notation::geohash::*;
+
+    #[test]
+    fn encode_returns_correct_values() {
+        assert_eq!(
+            encode(Geographic::new(57.64911, 10.40744, 0.0), 11),
+            "u4pruydqqvj"
+        );
+        assert_eq!(
+            encode(Geographic::new(48.858222, 2.2945, 0.0), 9),
+            "u09tunqgc"
+        );
+        assert_eq!(
+            encode(Geographic::new(-33.8568, 151.2153, 0.0), 6),
+            "r3gx2u"
+        );
+        assert_eq!(encode(Geographic::new(MIN_LAT, MIN_LON, 0.0), 3), "000");
+        assert_eq!(encode(Geographic::new(MAX_LAT, MAX_LON, 0.0), 3), "zzz");
+        assert_eq!(encode(Geographic::new(0.0, 0.0, 0.0), 0), "");
+    }
+
+    #[test]
+    fn decode_returns_the_cell() {
+        let cell = decode("ezs42").unwrap();
+
+        assert_abs_diff_eq!(cell.south(), 42.583_007_812_5);
+        assert_abs_diff_eq!(cell.north(), 42.626_953_125);
+        assert_abs_diff_eq!(cell.west(), -5.625);
+        assert_abs_diff_eq!(cell.east(), -5.581_054_687_5);
+        assert_eq!(decode("EZS42"), Ok(cell));
+    }
+
+    #[test]
+    fn decode_returns_errors_for_invalid_geohashes() {
+        assert_eq!(decode(""), Err(ParseError::InvalidSyntax));
+        assert_eq!(decode("ezs42a"), Err(ParseError::InvalidSyntax));
+        assert_eq!(decode("u09 tu"), Err(ParseError::InvalidSyntax));
+        assert_eq!(decode(&"0".repeat(23)), Err(ParseError::InvalidSyntax));
+        assert_eq!(decode("u09İ"), Err(ParseError::InvalidSyntax));
+        assert_eq!(decode("ı"), Err(ParseError::InvalidSyntax));
+    }
+
+    #[quickcheck]
+    fn decode_contains_the_encoded_coordinate(geo: Geographic, length: u8) -> bool {
+        let length = 1 + usize::from(length) % 12;
+        let hash = encode(geo, length);
+        let cell = decode(&hash).unwrap();
+
+        hash.len() == length
+            && cell.contains(geo.flatten())
+            && encode(cell.center(), length) == hash
+    }
+
+    #[quickcheck]
+    fn longer_geohashes_are_nested_in_shorter_ones(geo: Geographic, length: u8) -> bool {
+        let length = 1 + usize::from(length) % 12;
+        let parent = decode(&encode(geo, length)).unwrap();
+        let child = decode(&encode(geo, length + 1)).unwrap();
+
+        parent.contains(Geographic::new(child.south(), child.west(), 0.0))
+            && parent.contains(Geographic::new(child.north(), child.east(), 0.0))
+    }
+}
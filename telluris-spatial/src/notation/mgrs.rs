@@ -0,0 +1,317 @@
+//! The Military Grid Reference System (MGRS), and its civilian equivalent, the
+//! US National Grid (USNG). A grid reference is made of a UTM zone and latitude band
+//! (or a UPS band), the letters of a 100 km square, then an equal number of digits
+//! for the easting and northing in the square, such as `31U DQ 48251 11935`.
+//! References designate squares: coordinates are truncated when formatting,
+//! and the center of the square is returned when parsing.
+
+use crate::geographic::*;
+use crate::notation::ParseError;
+use crate::transformations::utm::*;
+use crate::transformations::Projection;
+use std::fmt;
+
+/// The latitude bands of UTM zones, 8° high from 80°S, except the 12° high band `X`.
+const BANDS: &[u8; 20] = b"CDEFGHJKLMNPQRSTUVWX";
+
+/// The column letters of UTM squares, for zone numbers modulo 3.
+const COLUMNS: [&[u8; 8]; 3] = [b"STUVWXYZ", b"ABCDEFGH", b"JKLMNPQR"];
+
+/// The row letters of UTM squares, which repeat every 2000 km.
+const ROWS: &[u8; 20] = b"ABCDEFGHJKLMNPQRSTUV";
+
+/// The column letters of UPS squares, for bands `A`, `B`, `Y` and `Z`.
+const UPS_COLUMNS: [&[u8]; 4] = [b"JKLPQRSTUXYZ", b"ABCFGHJKLPQR", b"RSTUXYZ", b"ABCFGHJ"];
+
+/// The row letters of UPS squares, in the southern and northern zones.
+const UPS_ROWS: [&[u8]; 2] = [b"ABCDEFGHJKLMNPQRSTUVWXYZ", b"ABCDEFGHJKLMNP"];
+
+/// The index of the first column and row of UPS squares, in the southern and northern zones.
+const UPS_FIRST_SQUARE: [usize; 2] = [8, 13];
+
+/// The index of the column of UPS squares that starts at the pole.
+const UPS_POLE_SQUARE: usize = 20;
+
+/// The size of a square, in meters.
+const SQUARE_SIZE: f64 = 100_000.0;
+
+/// The distance after which the row letters of UTM squares repeat, in meters.
+const ROW_CYCLE: f64 = 2_000_000.0;
+
+/// The maximal number of digits of the easting and northing.
+pub const MAX_MGRS_PRECISION: usize = 5;
+
+/// Returns the letter of the latitude band of UTM zones, or `None` for latitudes
+/// covered by UPS.
+pub fn latitude_band(lat: f64) -> Option<char> {
+    if !(UTM_MIN_LAT..UTM_MAX_LAT).contains(&lat) {
+        return None;
+    }
+
+    let index = (((lat - UTM_MIN_LAT) / 8.0).floor() as usize).min(BANDS.len() - 1);
+
+    Some(char::from(BANDS[index]))
+}
+
+fn ups_band(hemisphere: Hemisphere, easting: f64) -> usize {
+    let east = easting >= UPS_FALSE_ORIGIN;
+
+    match hemisphere {
+        Hemisphere::South => east as usize,
+        Hemisphere::North => 2 + east as usize,
+    }
+}
+
+fn ups_hemisphere(band: usize) -> usize {
+    band / 2
+}
+
+/// Writes the grid reference of the coordinate, with the specified number of digits
+/// (at most 5) for the easting and northing.
+pub(crate) fn format(
+    f: &mut fmt::Formatter,
+    geo: Geographic,
+    precision: usize,
+    spaced: bool,
+) -> fmt::Result {
+    let precision = precision.min(MAX_MGRS_PRECISION);
+    let utm = UtmCoordinate::from_geographic(geo);
+    // Rounding to the micrometer before truncating ensures that parsed references
+    // are formatted back into the same reference.
+    let round = |v: f64| (v * 1e6).round() / 1e6;
+    let (easting, northing) = (round(utm.easting()), round(utm.northing()));
+    let column = (easting / SQUARE_SIZE).floor() as usize;
+    let row = (northing / SQUARE_SIZE).floor() as usize;
+
+    let (zone, square) = match utm.zone() {
+        Zone::Utm(number, _) => {
+            let band = latitude_band(geo.lat()).unwrap_or('X');
+            let columns = COLUMNS[usize::from(number) % 3];
+            let offset = if number % 2 == 1 { 0 } else { 5 };
+
+            (
+                format!("{}{}", number, band),
+                [columns[(column + 7) % 8], ROWS[(row + offset) % 20]],
+            )
+        }
+        Zone::Ups(hemisphere) => {
+            let band = ups_band(hemisphere, easting);
+            let first = UPS_FIRST_SQUARE[ups_hemisphere(band)];
+            let first_column = if band % 2 == 1 {
+                UPS_POLE_SQUARE
+            } else {
+                first
+            };
+            let columns = UPS_COLUMNS[band];
+            let rows = UPS_ROWS[ups_hemisphere(band)];
+            let letter = |letters: &[u8], i: usize| letters[i.min(letters.len() - 1)];
+
+            (
+                char::from(b"ABYZ"[band]).to_string(),
+                [
+                    letter(columns, column.saturating_sub(first_column)),
+                    letter(rows, row.saturating_sub(first)),
+                ],
+            )
+        }
+    };
+
+    let square = String::from_utf8_lossy(&square);
+    let separator = if spaced { " " } else { "" };
+    write!(f, "{}{}{}", zone, separator, square)?;
+
+    if precision > 0 {
+        let divisor = 10f64.powi((MAX_MGRS_PRECISION - precision) as i32);
+        let digits = |v: f64| ((v % SQUARE_SIZE) / divisor).floor() as u32;
+
+        write!(
+            f,
+            "{}{:0w$}{}{:0w$}",
+            separator,
+            digits(easting),
+            separator,
+            digits(northing),
+            w = precision
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Parses a MGRS or USNG grid reference, and returns the center of its square.
+/// References are case insensitive, and may contain spaces.
+pub fn parse(s: &str) -> Result<Geographic, ParseError> {
+    let s: Vec<u8> = s
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    let zone_digits = s.iter().take_while(|c| c.is_ascii_digit()).count();
+    let letters = &s[zone_digits.min(s.len())..];
+    if zone_digits > 2 || letters.len() < 3 || !letters[..3].iter().all(u8::is_ascii_alphabetic) {
+        return Err(ParseError::InvalidSyntax);
+    }
+
+    let digits = &letters[3..];
+    if digits.len() % 2 == 1
+        || digits.len() > 2 * MAX_MGRS_PRECISION
+        || !digits.iter().all(u8::is_ascii_digit)
+    {
+        return Err(ParseError::InvalidSyntax);
+    }
+
+    // The position of the center of the square designated by the digits.
+    let precision = digits.len() / 2;
+    let scale = 10f64.powi((MAX_MGRS_PRECISION - precision) as i32);
+    let value =
+        |d: &[u8]| d.iter().fold(0.0, |v, c| v * 10.0 + f64::from(c - b'0')) * scale + scale / 2.0;
+    let easting = value(&digits[..precision]);
+    let northing = value(&digits[precision..]);
+
+    let position = |letters: &[u8], c: u8| {
+        letters
+            .iter()
+            .position(|l| *l == c)
+            .ok_or(ParseError::InvalidGridReference)
+    };
+
+    let utm = if zone_digits == 0 {
+        let band = position(b"ABYZ", letters[0]).map_err(|_| ParseError::InvalidSyntax)?;
+        let hemisphere = ups_hemisphere(band);
+        let first_column = if band % 2 == 1 {
+            UPS_POLE_SQUARE
+        } else {
+            UPS_FIRST_SQUARE[hemisphere]
+        };
+        let column = first_column + position(UPS_COLUMNS[band], letters[1])?;
+        let row = UPS_FIRST_SQUARE[hemisphere] + position(UPS_ROWS[hemisphere], letters[2])?;
+        let hemisphere = if hemisphere == 0 {
+            Hemisphere::South
+        } else {
+            Hemisphere::North
+        };
+
+        UtmCoordinate::new(
+            Zone::Ups(hemisphere),
+            column as f64 * SQUARE_SIZE + easting,
+            row as f64 * SQUARE_SIZE + northing,
+        )
+    } else {
+        let number: u8 = std::str::from_utf8(&s[..zone_digits])
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .ok_or(ParseError::InvalidSyntax)?;
+        if !(1..=UTM_ZONE_COUNT).contains(&number) {
+            return Err(ParseError::InvalidGridReference);
+        }
+
+        let band = position(BANDS, letters[0]).map_err(|_| ParseError::InvalidSyntax)?;
+        let hemisphere = if band < BANDS.len() / 2 {
+            Hemisphere::South
+        } else {
+            Hemisphere::North
+        };
+        let zone = Zone::utm(number, hemisphere);
+
+        let column = 1 + position(COLUMNS[usize::from(number) % 3], letters[1])?;
+        let offset = if number % 2 == 1 { 0 } else { 5 };
+        let row = (position(ROWS, letters[2])? + 20 - offset) % 20;
+
+        // Row letters repeat every 2000 km: find the repetition that lies in the band.
+        // The southern edge of the band is lowest on the central meridian, except
+        // in the southern hemisphere, hence a margin.
+        let south = UTM_MIN_LAT + 8.0 * band as f64;
+        let central_meridian = zone.central_meridian().unwrap_or(0.0);
+        let min_northing = zone
+            .project(Geographic::new(south, central_meridian, 0.0))
+            .y
+            - SQUARE_SIZE;
+
+        let mut northing = row as f64 * SQUARE_SIZE + northing;
+        while northing < min_northing {
+            northing += ROW_CYCLE;
+        }
+
+        UtmCoordinate::new(zone, column as f64 * SQUARE_SIZE + easting, northing)
+    };
+
+    Ok(utm.to_geographic())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geodesic::Geodesic;
+    use crate::geographic::*;
+    use crate::notation::mgrs::*;
+    use crate::notation::Notation;
+
+    fn mgrs(lat: f64, lon: f64) -> String {
+        format!("{}", Geographic::new(lat, lon, 0.0).display(Notation::Mgrs))
+    }
+
+    #[test]
+    fn latitude_band_returns_correct_values() {
+        assert_eq!(latitude_band(-80.0), Some('C'));
+        assert_eq!(latitude_band(-0.1), Some('M'));
+        assert_eq!(latitude_band(0.0), Some('N'));
+        assert_eq!(latitude_band(48.8), Some('U'));
+        assert_eq!(latitude_band(83.9), Some('X'));
+        assert_eq!(latitude_band(84.0), None);
+        assert_eq!(latitude_band(-80.1), None);
+    }
+
+    #[test]
+    fn format_returns_correct_values_for_reference_points() {
+        assert_eq!(mgrs(48.858222, 2.2945), "31UDQ4825111935");
+        assert_eq!(mgrs(33.3, 44.4), "38SMB4414084706");
+        assert_eq!(mgrs(-33.8568, 151.2153), "56HLH3490052288");
+        assert_eq!(mgrs(0.0, 0.0), "31NAA6602100000");
+        assert_eq!(mgrs(78.0, 16.0), "33XWG2320858567");
+        assert_eq!(mgrs(MAX_LAT, 0.0), "ZAH0000000000");
+        assert_eq!(mgrs(MIN_LAT, 0.0), "BAN0000000000");
+        assert_eq!(mgrs(85.0, 45.0), "ZFD9276707232");
+        assert_eq!(mgrs(-87.0, -120.0), "AXL1148833427");
+    }
+
+    #[test]
+    fn parse_returns_the_center_of_the_square() {
+        let geo = parse("31U DQ 48251 11935").unwrap();
+        let square = parse("31udq").unwrap();
+
+        assert_abs_diff_eq!(geo.lat(), 48.858_22, epsilon = 1e-5);
+        assert_abs_diff_eq!(geo.lon(), 2.294_49, epsilon = 1e-5);
+        assert_eq!(format!("{:.0}", square.display(Notation::Mgrs)), "31UDQ");
+        assert_eq!(format!("{:.1}", square.display(Notation::Mgrs)), "31UDQ55");
+    }
+
+    #[test]
+    fn parse_returns_errors() {
+        assert_eq!(parse(""), Err(ParseError::InvalidSyntax));
+        assert_eq!(parse("31UDQ482511193"), Err(ParseError::InvalidSyntax));
+        assert_eq!(parse("31UDQ48251119350"), Err(ParseError::InvalidSyntax));
+        assert_eq!(parse("131UDQ"), Err(ParseError::InvalidSyntax));
+        assert_eq!(parse("31IDQ"), Err(ParseError::InvalidSyntax));
+        assert_eq!(parse("61UDQ"), Err(ParseError::InvalidGridReference));
+        assert_eq!(parse("31UJQ"), Err(ParseError::InvalidGridReference));
+        assert_eq!(parse("31UDW"), Err(ParseError::InvalidGridReference));
+        assert_eq!(parse("ZZZ"), Err(ParseError::InvalidGridReference));
+    }
+
+    #[quickcheck]
+    fn parse_is_the_inverse_of_format(geo: Geographic, spaced: bool) -> bool {
+        let notation = if spaced {
+            Notation::Usng
+        } else {
+            Notation::Mgrs
+        };
+        let geo = geo.flatten();
+        let result = parse(&format!("{}", geo.display(notation))).unwrap();
+
+        // The center of a 1 m square is less than a meter away.
+        Geodesic::default()
+            .inverse(geo, result)
+            .map(|s| s.distance < 1.0)
+            .unwrap_or(true)
+    }
+}
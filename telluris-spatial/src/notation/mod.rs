@@ -0,0 +1,237 @@
+//! Textual notations of geographic coordinates: decimal degrees, degrees-minutes-seconds,
+//! MGRS/USNG grid references and geohashes.
+//! `Geographic` implements `FromStr`, which recognizes all notations, and `Display`,
+//! which uses decimal degrees with hemisphere letters. Other notations are available
+//! through `Geographic::display`. In all notations, the precision of the formatter
+//! controls the precision of the output, and elevations are ignored: parsed
+//! coordinates are at zero elevation.
+
+use crate::geographic::Geographic;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+pub mod dms;
+pub mod geohash;
+pub mod mgrs;
+
+/// A textual notation of geographic coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// Signed decimal degrees, latitude first, such as `48.858222, 2.294500`.
+    /// The precision is the number of decimals, 6 by default.
+    Decimal,
+    /// Decimal degrees with hemisphere letters, such as `48.858222°N 2.294500°E`.
+    /// The precision is the number of decimals, 6 by default.
+    DecimalHemisphere,
+    /// Degrees, minutes and seconds, such as `48°51'29.6"N 2°17'40.2"E`.
+    /// The precision is the number of decimals of the seconds, 1 by default.
+    Dms,
+    /// A MGRS grid reference, such as `31UDQ4825111935`. The precision is the
+    /// number of digits of the easting and northing, from 0 to 5 (the default).
+    Mgrs,
+    /// A USNG grid reference, which is a MGRS grid reference with spaces between
+    /// its parts, such as `31U DQ 48251 11935`.
+    Usng,
+    /// A geohash, such as `u09tunqgc`. The precision is the number of characters,
+    /// 9 by default.
+    Geohash,
+}
+
+/// The error returned when parsing geographic coordinates fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The string is not written in any of the supported notations.
+    InvalidSyntax,
+    /// An angle is outside of its valid range, such as a latitude beyond 90°,
+    /// or 60 minutes of arc.
+    OutOfRange,
+    /// The grid reference does not exist, such as a MGRS square outside of its zone.
+    InvalidGridReference,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            ParseError::InvalidSyntax => "unrecognized coordinate notation",
+            ParseError::OutOfRange => "angle out of range",
+            ParseError::InvalidGridReference => "invalid grid reference",
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Formats a `Geographic` in a specific notation. See `Geographic::display`.
+#[derive(Debug, Copy, Clone)]
+pub struct GeographicDisplay {
+    geo: Geographic,
+    notation: Notation,
+}
+
+impl GeographicDisplay {
+    pub(crate) fn new(geo: Geographic, notation: Notation) -> Self {
+        GeographicDisplay { geo, notation }
+    }
+}
+
+impl fmt::Display for GeographicDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let geo = self.geo;
+        let precision = f.precision();
+
+        match self.notation {
+            Notation::Decimal => dms::format_decimal(f, geo, precision.unwrap_or(6)),
+            Notation::DecimalHemisphere => {
+                dms::format_decimal_hemisphere(f, geo, precision.unwrap_or(6))
+            }
+            Notation::Dms => dms::format_dms(f, geo, precision.unwrap_or(1)),
+            Notation::Mgrs => mgrs::format(f, geo, precision.unwrap_or(5), false),
+            Notation::Usng => mgrs::format(f, geo, precision.unwrap_or(5), true),
+            Notation::Geohash => f.write_str(&geohash::encode(geo, precision.unwrap_or(9))),
+        }
+    }
+}
+
+impl fmt::Display for Geographic {
+    /// Formats the coordinate in decimal degrees with hemisphere letters.
+    /// See `Notation::DecimalHemisphere`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.display(Notation::DecimalHemisphere), f)
+    }
+}
+
+impl FromStr for Geographic {
+    type Err = ParseError;
+
+    /// Parses coordinates written in any of the supported notations.
+    /// Strings that are both valid MGRS references and valid geohashes are
+    /// read as MGRS references: use `geohash::decode` for such geohashes.
+    /// Valid geohashes made only of letters and digits, such as `s1e2`, are read
+    /// as geohashes rather than as degrees with hemisphere letters: separate the
+    /// angles with whitespace, units or a comma to read them as degrees.
+    /// Strings that are neither return the error of the MGRS parser.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let compact = s.trim().chars().all(|c| c.is_ascii_alphanumeric());
+        if !compact || geohash::decode(s).is_err() {
+            match dms::parse(s) {
+                Err(ParseError::InvalidSyntax) => (),
+                result => return result,
+            }
+        }
+
+        let error = match mgrs::parse(s) {
+            Err(e) => e,
+            result => return result,
+        };
+
+        geohash::decode(s)
+            .map(|cell| cell.center().flatten())
+            .map_err(|_| error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geographic::*;
+    use crate::notation::*;
+
+    #[test]
+    fn from_str_recognizes_all_notations() {
+        let expected = Geographic::new(48.858222, 2.2945, 0.0);
+        let parse = |s: &str| s.parse::<Geographic>().unwrap();
+
+        assert_abs_diff_eq!(parse("48.858222, 2.2945"), expected, epsilon = 1e-9);
+        assert_abs_diff_eq!(parse("48.858222°N 2.2945°E"), expected, epsilon = 1e-9);
+        assert_abs_diff_eq!(
+            parse("48°51'29.6\"N 2°17'40.2\"E"),
+            expected,
+            epsilon = 1e-4
+        );
+        assert_abs_diff_eq!(parse("31UDQ4825111935"), expected, epsilon = 1e-4);
+        assert_abs_diff_eq!(parse("31U DQ 48251 11935"), expected, epsilon = 1e-4);
+        assert_abs_diff_eq!(parse("u09tunqgc"), expected, epsilon = 1e-4);
+        assert_eq!(parse("8N 21W"), Geographic::new(8.0, -21.0, 0.0));
+    }
+
+    #[test]
+    fn from_str_reads_geohashes_that_start_like_grid_references() {
+        for hash in &["9qqj", "zzz", "dr5r", "w21n8", "s1e2", "e0n0"] {
+            let cell = geohash::decode(hash).unwrap();
+
+            assert_eq!(hash.parse::<Geographic>(), Ok(cell.center().flatten()));
+        }
+    }
+
+    #[test]
+    fn from_str_returns_errors() {
+        assert_eq!("".parse::<Geographic>(), Err(ParseError::InvalidSyntax));
+        assert_eq!(
+            "hello world".parse::<Geographic>(),
+            Err(ParseError::InvalidSyntax)
+        );
+        assert_eq!(
+            "91.0, 0.0".parse::<Geographic>(),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            "31UDI4825111935".parse::<Geographic>(),
+            Err(ParseError::InvalidGridReference)
+        );
+    }
+
+    #[test]
+    fn display_uses_the_precision_of_the_formatter() {
+        let geo = Geographic::new(48.858222, 2.2945, 0.0);
+
+        assert_eq!(format!("{}", geo), "48.858222°N 2.294500°E");
+        assert_eq!(format!("{:.2}", geo), "48.86°N 2.29°E");
+        assert_eq!(
+            format!("{:.3}", geo.display(Notation::Decimal)),
+            "48.858, 2.295"
+        );
+        assert_eq!(
+            format!("{}", geo.display(Notation::Dms)),
+            "48°51'29.6\"N 2°17'40.2\"E"
+        );
+        assert_eq!(
+            format!("{}", geo.display(Notation::Mgrs)),
+            "31UDQ4825111935"
+        );
+        assert_eq!(
+            format!("{:.2}", geo.display(Notation::Usng)),
+            "31U DQ 48 11"
+        );
+        assert_eq!(format!("{:.5}", geo.display(Notation::Geohash)), "u09tu");
+    }
+
+    #[quickcheck]
+    fn from_str_is_the_inverse_of_display(geo: Geographic) -> bool {
+        let geo = geo.flatten();
+        let parse = |n| {
+            format!("{:.9}", geo.display(n))
+                .parse::<Geographic>()
+                .unwrap()
+        };
+
+        // Longitudes are meaningless at the poles, and both signs of the antimeridian are equal.
+        let eq = |a: Geographic, epsilon: f64| {
+            let lon_error = (a.lon() - geo.lon()).abs();
+            abs_diff_eq!(a.lat(), geo.lat(), epsilon = epsilon)
+                && (lon_error <= epsilon
+                    || 360.0 - lon_error <= epsilon
+                    || geo.lat().abs() > 90.0 - epsilon)
+        };
+
+        // Some geohashes are also MGRS references, such as `32snf2122`.
+        let hash = format!("{}", geo.display(Notation::Geohash));
+        let cell = geohash::decode(&hash).unwrap();
+
+        eq(parse(Notation::Decimal), 1e-9)
+            && eq(parse(Notation::DecimalHemisphere), 1e-9)
+            && eq(parse(Notation::Dms), 1e-9)
+            && eq(cell.center(), 1e-4)
+    }
+}
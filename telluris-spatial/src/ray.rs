@@ -0,0 +1,312 @@
+//! Rays and their intersections with the ellipsoid, used to pick positions on the
+//! surface from screen coordinates, and to prevent the camera from going underground.
+
+use crate::ellipsoid::Ellipsoid;
+use crate::geographic::*;
+use crate::transformations::ecef::ECEF;
+use crate::transformations::SpatialReference;
+use glm::{DMat4, DVec2, DVec3};
+
+/// The number of refinement steps of intersections with a raised ellipsoid.
+const ALTITUDE_ITERATIONS: usize = 4;
+
+/// A half-line starting at an origin, in a cartesian frame.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ray {
+    origin: DVec3,
+    direction: DVec3,
+}
+
+impl Ray {
+    /// Creates a ray with the specified origin and direction. The direction is normalized,
+    /// so that distances along the ray are expressed in the units of the frame.
+    pub fn new(origin: DVec3, direction: DVec3) -> Self {
+        Ray {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    /// Creates the ray going through the specified pixel of the viewport.
+    /// Pixel coordinates start at the top left corner of the viewport, with `Y` pointing down.
+    /// The ray starts on the near plane of the view-projection matrix, which follows
+    /// the OpenGL convention used by `glm::perspective`.
+    /// Returns `None` if the matrix cannot be inverted.
+    pub fn from_screen(screen: DVec2, viewport: DVec2, view_projection: &DMat4) -> Option<Self> {
+        let inverse = view_projection.try_inverse()?;
+
+        let x = 2.0 * screen.x / viewport.x - 1.0;
+        let y = 1.0 - 2.0 * screen.y / viewport.y;
+
+        let unproject = |z: f64| {
+            let p = inverse * glm::vec4(x, y, z, 1.0);
+            p.xyz() / p.w
+        };
+
+        let near = unproject(-1.0);
+        let far = unproject(1.0);
+
+        Some(Ray::new(near, far - near))
+    }
+
+    /// Returns the origin of the ray.
+    pub fn origin(&self) -> DVec3 {
+        self.origin
+    }
+
+    /// Returns the unit direction of the ray.
+    pub fn direction(&self) -> DVec3 {
+        self.direction
+    }
+
+    /// Returns the point at the given distance along the ray.
+    pub fn at(&self, distance: f64) -> DVec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Returns the distances along the ray where it enters and leaves the ellipsoid,
+    /// or `None` if the ray misses it. If the origin is inside the ellipsoid, the entry
+    /// distance is zero. Both distances are equal if the ray is tangent to the ellipsoid.
+    pub fn intersect_ellipsoid(&self, ellipsoid: &Ellipsoid) -> Option<(f64, f64)> {
+        // Implementation taken from the book "3D Engine design
+        // for virtual globes", by Patrick Cozzi and Kevin Ring.
+
+        let q = ellipsoid.to_scaled_space(self.origin);
+        let w = ellipsoid.to_scaled_space(self.direction);
+
+        let q2 = q.norm_squared();
+        let qw = q.dot(&w);
+        let w2 = w.norm_squared();
+        let difference = q2 - 1.0;
+
+        if difference > 0.0 {
+            // The origin is outside the ellipsoid.
+            if qw >= 0.0 {
+                return None;
+            }
+
+            let qw2 = qw * qw;
+            let product = w2 * difference;
+
+            if qw2 < product {
+                None
+            } else if qw2 > product {
+                let temp = -qw + (qw2 - product).sqrt();
+                let a = temp / w2;
+                let b = difference / temp;
+                Some((a.min(b), a.max(b)))
+            } else {
+                let root = (difference / w2).sqrt();
+                Some((root, root))
+            }
+        } else if difference < 0.0 {
+            // The origin is inside the ellipsoid.
+            let temp = -qw + (qw * qw - w2 * difference).sqrt();
+            Some((0.0, temp / w2))
+        } else if qw < 0.0 {
+            // The origin is on the surface, and the ray goes inside.
+            Some((0.0, -2.0 * qw / w2))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the nearest point where the ray hits the ellipsoid.
+    /// If the origin is inside the ellipsoid, returns the point where the ray leaves it.
+    pub fn pick_ellipsoid(&self, ellipsoid: &Ellipsoid) -> Option<DVec3> {
+        match self.intersect_ellipsoid(ellipsoid)? {
+            (start, _) if start > 0.0 => Some(self.at(start)),
+            (_, stop) => Some(self.at(stop)),
+        }
+    }
+
+    /// Returns the geographic coordinates of the nearest point where the ray
+    /// hits the surface of the ellipsoid of the frame.
+    pub fn pick(&self, ecef: &ECEF) -> Option<Geographic> {
+        self.pick_ellipsoid(ecef.ellipsoid())
+            .map(|p| ecef.inverse_f64(p).flatten())
+    }
+
+    /// Returns the nearest point where the ray hits the surface located at the given
+    /// altitude above the ellipsoid. As with `Geographic::raise`, the altitude is clamped
+    /// in the domain specified by `MIN_ALT` and `MAX_ALT`. Returns `None` for altitudes
+    /// below the center of the ellipsoid, where the surface does not exist.
+    pub fn pick_ellipsoid_at_altitude(
+        &self,
+        ellipsoid: &Ellipsoid,
+        altitude: f64,
+    ) -> Option<DVec3> {
+        let altitude = num::clamp(altitude, MIN_ALT, MAX_ALT);
+        if altitude <= -ellipsoid.semi_minor_axis() {
+            return None;
+        }
+
+        // The ellipsoid whose radii are raised by the altitude is a close approximation
+        // of the raised surface. The intersection is then refined along the ray.
+        let raised = Ellipsoid::new(
+            ellipsoid.semi_major_axis() + altitude,
+            ellipsoid.semi_minor_axis() + altitude,
+        );
+        let (start, stop) = self.intersect_ellipsoid(&raised)?;
+        let mut distance = if start > 0.0 { start } else { stop };
+
+        for _ in 0..ALTITUDE_ITERATIONS {
+            let p = self.at(distance);
+            let surface = match ellipsoid.scale_to_geodetic_surface(p) {
+                Some(s) => s,
+                None => break,
+            };
+            let normal = ellipsoid.geodetic_surface_normal_at(surface);
+            let height = (p - surface).dot(&normal);
+
+            // Grazing rays do not converge: keep the approximation.
+            let slope = self.direction.dot(&normal);
+            if slope.abs() < 1e-3 {
+                break;
+            }
+
+            distance -= (height - altitude) / slope;
+        }
+
+        Some(self.at(distance))
+    }
+
+    /// Returns the geographic coordinates of the nearest point where the ray hits
+    /// the surface located at the given altitude above the ellipsoid of the frame.
+    pub fn pick_at_altitude(&self, ecef: &ECEF, altitude: f64) -> Option<Geographic> {
+        self.pick_ellipsoid_at_altitude(ecef.ellipsoid(), altitude)
+            .map(|p| ecef.inverse_f64(p))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ellipsoid::*;
+    use crate::geographic::*;
+    use crate::ray::*;
+    use rand::Rng;
+
+    /// Returns a ray starting above the coordinate, looking down along the vertical.
+    fn vertical_ray(geo: Geographic, height: f64) -> Ray {
+        let ecef = ECEF::default();
+        let origin = ecef.convert_f64(geo.flatten().raise(height));
+
+        Ray::new(origin, -ecef.normal_f64(geo))
+    }
+
+    #[test]
+    fn intersect_ellipsoid_returns_both_hits() {
+        let a = WGS84_SEMI_MAJOR_AXIS;
+        let ray = Ray::new(DVec3::new(2.0 * a, 0.0, 0.0), DVec3::new(-1.0, 0.0, 0.0));
+
+        let (start, stop) = ray.intersect_ellipsoid(&Ellipsoid::wgs84()).unwrap();
+
+        assert_abs_diff_eq!(start, a, epsilon = 1e-6);
+        assert_abs_diff_eq!(stop, 3.0 * a, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn intersect_ellipsoid_returns_none_for_misses() {
+        let a = WGS84_SEMI_MAJOR_AXIS;
+        let e = Ellipsoid::wgs84();
+
+        let away = Ray::new(DVec3::new(2.0 * a, 0.0, 0.0), DVec3::new(1.0, 0.0, 0.0));
+        let beside = Ray::new(DVec3::new(2.0 * a, 0.0, a), DVec3::new(-1.0, 0.0, 0.0));
+
+        assert_eq!(away.intersect_ellipsoid(&e), None);
+        assert_eq!(beside.intersect_ellipsoid(&e), None);
+    }
+
+    #[test]
+    fn intersect_ellipsoid_from_inside_starts_at_the_origin() {
+        let ray = Ray::new(DVec3::zeros(), DVec3::new(0.0, 0.0, 1.0));
+
+        let (start, stop) = ray.intersect_ellipsoid(&Ellipsoid::wgs84()).unwrap();
+
+        assert_abs_diff_eq!(start, 0.0);
+        assert_abs_diff_eq!(stop, WGS84_SEMI_MINOR_AXIS, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn pick_ellipsoid_at_altitude_returns_none_below_the_center() {
+        let ray = Ray::new(DVec3::new(0.0, 0.0, 5.0), DVec3::new(0.0, 0.0, -1.0));
+        let sphere = Ellipsoid::unit_sphere();
+
+        assert_eq!(ray.pick_ellipsoid_at_altitude(&sphere, -1.0), None);
+        assert_eq!(ray.pick_ellipsoid_at_altitude(&sphere, -2.0), None);
+
+        let p = ray.pick_ellipsoid_at_altitude(&sphere, -0.5).unwrap();
+        assert_abs_diff_eq!(p, DVec3::new(0.0, 0.0, 0.5), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn from_screen_at_the_center_of_the_viewport_looks_forward() {
+        let eye = DVec3::new(10_000_000.0, 0.0, 0.0);
+        let view = glm::look_at(&eye, &DVec3::zeros(), &DVec3::new(0.0, 0.0, 1.0));
+        let projection = glm::perspective(1.5, 1.0, 1.0, 1e8);
+        let viewport = DVec2::new(800.0, 600.0);
+
+        let center = Ray::from_screen(viewport / 2.0, viewport, &(projection * view)).unwrap();
+        let top = Ray::from_screen(DVec2::new(400.0, 0.0), viewport, &(projection * view)).unwrap();
+
+        assert_abs_diff_eq!(
+            center.direction(),
+            DVec3::new(-1.0, 0.0, 0.0),
+            epsilon = 1e-9
+        );
+        assert!(top.direction().z > 0.0);
+
+        let picked = center.pick(&ECEF::default()).unwrap();
+        assert_abs_diff_eq!(picked, Geographic::new(0.0, 0.0, 0.0), epsilon = 1e-6);
+    }
+
+    #[quickcheck]
+    fn pick_returns_the_coordinate_below_the_origin(geo: Geographic) -> bool {
+        let ray = vertical_ray(geo, 1_000_000.0);
+        let picked = ray.pick(&ECEF::default()).unwrap();
+
+        // Longitudes are meaningless at the poles.
+        abs_diff_eq!(picked.lat(), geo.lat(), epsilon = 1e-7)
+            && (geo.lat().abs() > 89.999 || abs_diff_eq!(picked.lon(), geo.lon(), epsilon = 1e-7))
+    }
+
+    #[quickcheck]
+    fn pick_at_altitude_returns_the_raised_coordinate(geo: Geographic, altitude: u16) -> bool {
+        let altitude = f64::from(altitude);
+        let ecef = ECEF::default();
+        let ray = vertical_ray(geo, 1_000_000.0);
+
+        let picked = ray.pick_at_altitude(&ecef, altitude).unwrap();
+
+        abs_diff_eq!(picked.elevation(), altitude, epsilon = 1e-3)
+            && abs_diff_eq!(picked.lat(), geo.lat(), epsilon = 1e-7)
+    }
+
+    #[quickcheck]
+    fn pick_at_altitude_of_oblique_rays_lies_at_the_altitude(
+        geo: Geographic,
+        target: Geographic,
+    ) -> bool {
+        let ecef = ECEF::default();
+        let mut rng = rand::thread_rng();
+        let altitude = rng.gen_range(MIN_ALT, 100_000.0);
+
+        // A ray from the camera towards a point under the raised surface.
+        let origin = ecef.convert_f64(geo.flatten().raise(altitude + 10_000_000.0));
+        let ray = Ray::new(
+            origin,
+            ecef.convert_f64(target.flatten().raise(MIN_ALT)) - origin,
+        );
+
+        match ray.pick_ellipsoid_at_altitude(ecef.ellipsoid(), altitude) {
+            Some(p) => {
+                let slope = ray
+                    .direction()
+                    .dot(&ecef.ellipsoid().geodetic_surface_normal_at(p));
+                slope.abs() < 1e-3
+                    || abs_diff_eq!(ecef.inverse_f64(p).elevation(), altitude, epsilon = 1e-3)
+            }
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! Routes between geographic coordinates, to draw flight paths and animate cameras.
+//! A route either follows the great circle, which is the shortest path on the sphere,
+//! or the rhumb line, which keeps a constant azimuth and is a straight line on a
+//! Mercator map. Both take the shortest way around the globe, across the antimeridian
+//! when the longitudes are more than 180° apart.
+//! Distances are measured on a sphere of radius `MEAN_EARTH_RADIUS`.
+//! Elevations are interpolated linearly, and an `AltitudeProfile` may raise them
+//! along the way.
+
+use crate::angle::Degrees;
+use crate::geographic::*;
+use crate::geometry::{self, lon_delta, wrap_lon};
+use std::f64::consts::FRAC_PI_4;
+
+/// The latitude in degrees at which rhumb lines stop, since the Mercator
+/// latitude of the poles is infinite. Rhumb lines that end at a pole
+/// reach it in a straight line from this latitude.
+const MAX_RHUMB_LAT: f64 = 89.999_999;
+
+/// The path followed between two coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Route {
+    /// The shortest path on the sphere.
+    GreatCircle,
+    /// The path of constant azimuth.
+    Rhumb,
+}
+
+impl Route {
+    /// Returns the point at the fraction `t` (in the [0, 1] range) of the route
+    /// from `from` to `to`. Elevations are interpolated linearly.
+    pub fn interpolate(self, from: &Geographic, to: &Geographic, t: f64) -> Geographic {
+        if t == 0.0 {
+            return *from;
+        }
+        if t == 1.0 {
+            return *to;
+        }
+
+        match self {
+            Route::GreatCircle => geometry::interpolate(from, to, t),
+            Route::Rhumb => rhumb(from, to, t),
+        }
+    }
+
+    /// Returns the length in meters of the route from `from` to `to`.
+    /// Elevations are ignored.
+    pub fn distance(self, from: &Geographic, to: &Geographic) -> f64 {
+        match self {
+            Route::GreatCircle => from.great_circle_distance(to),
+            Route::Rhumb => rhumb_distance(from, to),
+        }
+    }
+}
+
+/// The way elevations vary along a route, on top of the linear interpolation
+/// between the elevations of its ends.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum AltitudeProfile {
+    /// Elevations are interpolated linearly.
+    #[default]
+    Linear,
+    /// Elevations follow a parabolic arc, raised by the specified height in meters
+    /// in the middle of the route.
+    Arc(f64),
+}
+
+impl AltitudeProfile {
+    /// Returns the height in meters added to the linear elevation at the fraction `t`
+    /// of the route, to be applied with `Geographic::raise`.
+    pub fn offset(self, t: f64) -> f64 {
+        match self {
+            AltitudeProfile::Linear => 0.0,
+            AltitudeProfile::Arc(height) => 4.0 * height * t * (1.0 - t),
+        }
+    }
+}
+
+/// An iterator over evenly spaced points along a route, both ends included.
+/// See `Geographic::densify`.
+#[derive(Debug, Clone)]
+pub struct Densify {
+    from: Geographic,
+    to: Geographic,
+    route: Route,
+    profile: AltitudeProfile,
+    count: usize,
+    index: usize,
+}
+
+impl Densify {
+    /// Returns an iterator over the points from `from` to `to`, spaced by at most `step`
+    /// meters along the route.
+    pub fn new(from: Geographic, to: Geographic, step: f64, route: Route) -> Self {
+        assert!(step > 0.0);
+
+        Densify {
+            from,
+            to,
+            route,
+            profile: AltitudeProfile::Linear,
+            count: (route.distance(&from, &to) / step).ceil().max(1.0) as usize,
+            index: 0,
+        }
+    }
+
+    /// Returns this iterator with elevations following the specified profile.
+    pub fn profile(self, profile: AltitudeProfile) -> Self {
+        Densify { profile, ..self }
+    }
+}
+
+impl Iterator for Densify {
+    type Item = Geographic;
+
+    fn next(&mut self) -> Option<Geographic> {
+        if self.index > self.count {
+            return None;
+        }
+
+        let t = self.index as f64 / self.count as f64;
+        self.index += 1;
+
+        let point = self.route.interpolate(&self.from, &self.to, t);
+        Some(point.raise(self.profile.offset(t)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count + 1 - self.index.min(self.count + 1);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Densify {}
+
+/// Returns the Mercator (isometric) latitude of the latitude in degrees.
+fn mercator_latitude(lat: f64) -> f64 {
+    let lat = Degrees(num::clamp(lat, -MAX_RHUMB_LAT, MAX_RHUMB_LAT)).to_radians();
+
+    (FRAC_PI_4 + lat.value() / 2.0).tan().ln()
+}
+
+/// Returns the point at the fraction `t` of the rhumb line from `a` to `b`.
+/// The latitude varies linearly with the distance along the rhumb line, and the
+/// longitude linearly with the Mercator latitude.
+fn rhumb(a: &Geographic, b: &Geographic, t: f64) -> Geographic {
+    let lat = num::clamp(a.lat() + (b.lat() - a.lat()) * t, MIN_LAT, MAX_LAT);
+    let elevation = a.elevation() + (b.elevation() - a.elevation()) * t;
+
+    let start = mercator_latitude(a.lat());
+    let d_psi = mercator_latitude(b.lat()) - start;
+    let fraction = if d_psi.abs() > 1e-12 {
+        (mercator_latitude(lat) - start) / d_psi
+    } else {
+        t
+    };
+
+    Geographic::new(
+        lat,
+        wrap_lon(a.lon() + lon_delta(a, b) * fraction),
+        elevation,
+    )
+}
+
+/// Returns the length in meters of the rhumb line from `a` to `b`.
+fn rhumb_distance(a: &Geographic, b: &Geographic) -> f64 {
+    let d_lat = (b.lat_rad() - a.lat_rad()).value();
+    let d_lon = Degrees(lon_delta(a, b)).to_radians().value();
+    let d_psi = mercator_latitude(b.lat()) - mercator_latitude(a.lat());
+
+    // The ratio of the latitude difference to the Mercator latitude difference,
+    // which tends to the cosine of the latitude along parallels.
+    let q = if d_psi.abs() > 1e-12 {
+        d_lat / d_psi
+    } else {
+        a.lat_rad().cos()
+    };
+
+    MEAN_EARTH_RADIUS * (d_lat * d_lat + q * q * d_lon * d_lon).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::route::*;
+    use quickcheck::{Arbitrary, Gen};
+    use rand::Rng;
+
+    #[derive(Debug, Copy, Clone)]
+    struct Fraction(f64);
+
+    impl Arbitrary for Fraction {
+        fn arbitrary<G: Gen>(g: &mut G) -> Fraction {
+            Fraction(g.gen_range(0.0, 1.0))
+        }
+    }
+
+    fn assert_geo_eq(actual: Geographic, lat: f64, lon: f64, elevation: f64) {
+        assert_abs_diff_eq!(actual.lat(), lat, epsilon = 1e-9);
+        assert_abs_diff_eq!(actual.lon(), lon, epsilon = 1e-9);
+        assert_abs_diff_eq!(actual.elevation(), elevation, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn interpolate_great_circle_returns_correct_values() {
+        let a = Geographic::new(0.0, 0.0, 0.0);
+        let b = Geographic::new(0.0, 90.0, 1000.0);
+
+        assert_geo_eq(a.interpolate_great_circle(&b, 0.5), 0.0, 45.0, 500.0);
+        assert_eq!(a.interpolate_great_circle(&b, 0.0), a);
+        assert_eq!(a.interpolate_great_circle(&b, 1.0), b);
+    }
+
+    #[test]
+    fn interpolate_great_circle_crosses_the_antimeridian() {
+        let a = Geographic::new(0.0, 170.0, 0.0);
+        let b = Geographic::new(0.0, -160.0, 0.0);
+
+        let middle = a.interpolate_great_circle(&b, 1.0 / 3.0);
+        assert_abs_diff_eq!(middle.lon().abs(), 180.0, epsilon = 1e-9);
+        assert_geo_eq(a.interpolate_great_circle(&b, 2.0 / 3.0), 0.0, -170.0, 0.0);
+    }
+
+    #[test]
+    fn interpolate_great_circle_crosses_the_poles() {
+        let a = Geographic::new(80.0, 0.0, 0.0);
+        let b = Geographic::new(80.0, 180.0, 0.0);
+        let antipode = Geographic::new(0.0, 180.0, 0.0);
+
+        assert_abs_diff_eq!(
+            a.interpolate_great_circle(&b, 0.5).lat(),
+            90.0,
+            epsilon = 1e-9
+        );
+        assert_geo_eq(a.interpolate_great_circle(&b, 0.25), 85.0, 0.0, 0.0);
+
+        // Antipodal points are joined through the north of the start point.
+        let origin = Geographic::new(0.0, 0.0, 0.0);
+        assert_abs_diff_eq!(
+            origin.interpolate_great_circle(&antipode, 0.5).lat(),
+            90.0,
+            epsilon = 1e-9
+        );
+        assert_geo_eq(
+            origin.interpolate_great_circle(&antipode, 0.25),
+            45.0,
+            0.0,
+            0.0,
+        );
+    }
+
+    #[test]
+    fn interpolate_rhumb_returns_correct_values() {
+        let a = Geographic::new(10.0, 20.0, 0.0);
+
+        // Along a parallel and along a meridian.
+        let east = Geographic::new(10.0, 60.0, 0.0);
+        let north = Geographic::new(50.0, 20.0, 0.0);
+        assert_geo_eq(a.interpolate_rhumb(&east, 0.25), 10.0, 30.0, 0.0);
+        assert_geo_eq(a.interpolate_rhumb(&north, 0.25), 20.0, 20.0, 0.0);
+
+        // Across the antimeridian.
+        let b = Geographic::new(0.0, 170.0, 0.0);
+        let c = Geographic::new(0.0, -170.0, 0.0);
+        assert_geo_eq(b.interpolate_rhumb(&c, 0.75), 0.0, -175.0, 0.0);
+    }
+
+    #[test]
+    fn interpolate_rhumb_reaches_the_poles() {
+        let a = Geographic::new(60.0, 10.0, 0.0);
+        let pole = Geographic::new(90.0, 0.0, 0.0);
+
+        assert_eq!(a.interpolate_rhumb(&pole, 1.0), pole);
+        let near = a.interpolate_rhumb(&pole, 0.999_999);
+        assert!(near.lat() < 90.0 && near.lat() > 89.99);
+
+        let b = Geographic::new(90.0, 0.0, 0.0);
+        let c = Geographic::new(90.0, 90.0, 0.0);
+        assert_geo_eq(b.interpolate_rhumb(&c, 0.5), 90.0, 45.0, 0.0);
+    }
+
+    #[test]
+    fn rhumb_distance_returns_correct_values() {
+        // From Plymouth to Cape Cod.
+        let a = Geographic::new(50.366389, -4.133889, 0.0);
+        let b = Geographic::new(42.351111, -71.040833, 0.0);
+        assert_abs_diff_eq!(a.rhumb_distance(&b), 5_198_000.0, epsilon = 1000.0);
+
+        let c = Geographic::new(0.0, 179.0, 0.0);
+        let d = Geographic::new(0.0, -179.0, 0.0);
+        assert_abs_diff_eq!(
+            c.rhumb_distance(&d),
+            c.great_circle_distance(&d),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn arc_profile_raises_the_middle_of_the_route() {
+        let a = Geographic::new(0.0, 0.0, 100.0);
+        let b = Geographic::new(0.0, 10.0, 300.0);
+        let points: Vec<_> = a
+            .densify(&b, 300_000.0, Route::GreatCircle)
+            .profile(AltitudeProfile::Arc(10_000.0))
+            .collect();
+
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0], a);
+        assert_eq!(points[4], b);
+        assert_geo_eq(points[2], 0.0, 5.0, 10_200.0);
+        assert_abs_diff_eq!(points[1].elevation(), 150.0 + 7500.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn densify_returns_both_ends_of_empty_routes() {
+        let a = Geographic::new(90.0, 0.0, 0.0);
+        let b = Geographic::new(90.0, 45.0, 0.0);
+
+        let points: Vec<_> = a.densify(&b, 1.0, Route::Rhumb).collect();
+        assert_eq!(points, vec![a, b]);
+    }
+
+    #[quickcheck]
+    fn interpolate_great_circle_divides_the_distance(
+        a: Geographic,
+        b: Geographic,
+        t: Fraction,
+    ) -> bool {
+        let p = a.interpolate_great_circle(&b, t.0);
+        let distance = a.great_circle_distance(&b);
+
+        // Nearly antipodal points have no unique great circle.
+        distance > 20_000_000.0
+            || (a.great_circle_distance(&p) - distance * t.0).abs() < 1e-3
+                && (p.great_circle_distance(&b) - distance * (1.0 - t.0)).abs() < 1e-3
+    }
+
+    #[quickcheck]
+    fn interpolate_rhumb_divides_the_distance(a: Geographic, b: Geographic, t: Fraction) -> bool {
+        let p = a.interpolate_rhumb(&b, t.0);
+        let distance = a.rhumb_distance(&b);
+        let polar = |g: &Geographic| g.lat().abs() > MAX_RHUMB_LAT;
+
+        polar(&a) || polar(&b) || (a.rhumb_distance(&p) - distance * t.0).abs() < 1e-3
+    }
+
+    #[quickcheck]
+    fn densify_spaces_points_by_at_most_the_step(a: Geographic, b: Geographic) -> bool {
+        let step = 100_000.0;
+        let points: Vec<_> = a.densify(&b, step, Route::GreatCircle).collect();
+        let expected = a.densify(&b, step, Route::GreatCircle).len();
+
+        points.len() == expected
+            && points.first() == Some(&a)
+            && points.last() == Some(&b)
+            && points
+                .windows(2)
+                .all(|w| w[0].great_circle_distance(&w[1]) <= step + 1e-3)
+    }
+}
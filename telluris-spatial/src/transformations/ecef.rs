@@ -1,57 +1,222 @@
-use crate::transformations::SpatialReference;
-use crate::geographic::Geographic;
-use glm::{Vec3, DVec3};
+use crate::ellipsoid::{self, Ellipsoid};
+use crate::geographic::*;
+use crate::transformations::{rtc, SpatialReference};
+use glm::{DVec3, Vec3};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// The length of the semi major axis, in meters, in the WGS 84 system.
-pub const WGS84_SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+#[deprecated(note = "use `ellipsoid::WGS84_SEMI_MAJOR_AXIS` or `Ellipsoid::wgs84()`")]
+pub const WGS84_SEMI_MAJOR_AXIS: f64 = ellipsoid::WGS84_SEMI_MAJOR_AXIS;
 
 /// The length of the semi minor axis, in meters, in the WGS 84 system.
-pub const WGS84_SEMI_MINOR_AXIS: f64 = 6_356_752.314_245;
-
-const WGS84_RADII_SQUARED: (f64, f64, f64) = (
-    WGS84_SEMI_MINOR_AXIS * WGS84_SEMI_MINOR_AXIS,
-    WGS84_SEMI_MAJOR_AXIS * WGS84_SEMI_MAJOR_AXIS,
-    WGS84_SEMI_MAJOR_AXIS * WGS84_SEMI_MAJOR_AXIS,
-);
+#[deprecated(note = "use `ellipsoid::WGS84_SEMI_MINOR_AXIS` or `Ellipsoid::wgs84()`")]
+pub const WGS84_SEMI_MINOR_AXIS: f64 = ellipsoid::WGS84_SEMI_MINOR_AXIS;
 
 /// Earth-centered, earth-fixed referential system.
 /// North is positive `Z`, the intersection between the prime meridian
 /// and the equator (0°N, 0°E) lies on the positive `X` axis,
 /// and the intersection between the 90°E meridian and the equator (0°N, 90°E)
 /// lies on the positive `Y` axis.
-#[derive(Debug, Clone, Copy)]
-pub struct ECEF {}
+/// Despite its name, the frame can be centered on any body described by an
+/// `Ellipsoid`. The default ellipsoid is WGS 84.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ECEF {
+    ellipsoid: Ellipsoid,
+}
+
+impl ECEF {
+    /// Creates a frame centered on the provided ellipsoid.
+    pub fn new(ellipsoid: Ellipsoid) -> Self {
+        ECEF { ellipsoid }
+    }
+
+    /// Returns the ellipsoid of this frame.
+    pub fn ellipsoid(&self) -> &Ellipsoid {
+        &self.ellipsoid
+    }
+}
 
 impl SpatialReference for ECEF {
-    fn convert(self: &Self, geo: Geographic) -> Vec3 {
-        // Implementation taken from the book "3D Engine design
-        // for virtual globes", by Patrick Cozzi and Kevin Ring.
-
-        let cos_lat = geo.lat().cos();
-        let n = DVec3::new(
-            cos_lat * geo.lon().cos(),
-            cos_lat * geo.lon().sin(),
-            geo.lat().sin(),
+    fn convert_f64(&self, geo: Geographic) -> DVec3 {
+        self.ellipsoid.to_cartesian(geo)
+    }
+
+    fn normal(&self, geo: Geographic) -> Vec3 {
+        rtc::to_f32(self.normal_f64(geo))
+    }
+
+    fn normal_f64(&self, geo: Geographic) -> DVec3 {
+        self.ellipsoid.geodetic_surface_normal(geo)
+    }
+
+    fn inverse_f64(&self, p: DVec3) -> Geographic {
+        self.ellipsoid.to_geographic(p)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ellipsoid::*;
+    use crate::ellipsoid::{WGS84_SEMI_MAJOR_AXIS, WGS84_SEMI_MINOR_AXIS};
+    use crate::geographic::*;
+    use crate::transformations::ecef::*;
+    use glm::DVec3;
+
+    /// Returns true if the two coordinates are within `angle` degrees and `height` meters.
+    /// Longitudes are not compared near the poles, where they are meaningless.
+    fn same_position(a: Geographic, b: Geographic, angle: f64, height: f64) -> bool {
+        let d_lon = (a.lon() - b.lon()).abs();
+        let same_lon = d_lon.min(360.0 - d_lon) < angle || a.lat().abs() > 89.999;
+
+        (a.lat() - b.lat()).abs() < angle
+            && same_lon
+            && (a.elevation() - b.elevation()).abs() < height
+    }
+
+    type Triple = (f64, f64, f64);
+
+    /// Known positions in the WGS 84 ECEF frame: (latitude, longitude, elevation) and (x, y, z).
+    const REFERENCE_POINTS: [(Triple, Triple); 6] = [
+        ((0.0, 0.0, 0.0), (6_378_137.0, 0.0, 0.0)),
+        ((-90.0, 0.0, 0.0), (0.0, 0.0, -6_356_752.314)),
+        (
+            (48.8583, 2.2945, 0.0),
+            (4_200_944.187, 168_323.437, 4_780_205.726),
+        ),
+        (
+            (-33.8568, 151.2153, 0.0),
+            (-4_646_968.637, 2_553_076.920, -3_533_267.127),
+        ),
+        (
+            (27.988_056, 86.925_278, 8848.86),
+            (302_742.711, 5_636_029.983, 2_979_489.179),
+        ),
+        (
+            (45.0, -120.0, 1000.0),
+            (-2_259_148.993, -3_912_960.837, 4_488_055.516),
+        ),
+    ];
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_axes_match_the_wgs84_ellipsoid() {
+        let wgs84 = Ellipsoid::wgs84();
+
+        assert_eq!(super::WGS84_SEMI_MAJOR_AXIS, wgs84.semi_major_axis());
+        assert_eq!(super::WGS84_SEMI_MINOR_AXIS, wgs84.semi_minor_axis());
+    }
+
+    #[test]
+    fn convert_returns_correct_values_for_reference_points() {
+        let ecef = ECEF::default();
+
+        for ((lat, lon, h), (x, y, z)) in REFERENCE_POINTS.iter() {
+            let v = ecef.convert(Geographic::new(*lat, *lon, *h));
+            let expected = Vec3::new(*x as f32, *y as f32, *z as f32);
+
+            assert_abs_diff_eq!(v, expected, epsilon = 1.0);
+        }
+    }
+
+    #[test]
+    fn inverse_f64_returns_correct_values_for_reference_points() {
+        let ecef = ECEF::default();
+
+        for ((lat, lon, h), (x, y, z)) in REFERENCE_POINTS.iter() {
+            let geo = ecef.inverse_f64(DVec3::new(*x, *y, *z));
+
+            assert!(same_position(
+                geo,
+                Geographic::new(*lat, *lon, *h),
+                1e-7,
+                0.01
+            ));
+        }
+    }
+
+    #[test]
+    fn normal_points_up_at_the_poles_and_outward_at_the_equator() {
+        let ecef = ECEF::default();
+
+        assert_abs_diff_eq!(
+            ecef.normal(Geographic::new(90.0, 0.0, 0.0)),
+            Vec3::new(0.0, 0.0, 1.0),
+            epsilon = 1e-6
+        );
+        assert_abs_diff_eq!(
+            ecef.normal(Geographic::new(0.0, 90.0, 0.0)),
+            Vec3::new(0.0, 1.0, 0.0),
+            epsilon = 1e-6
         );
-        let k = DVec3::new(
-            WGS84_RADII_SQUARED.0 * n.x,
-            WGS84_RADII_SQUARED.1 * n.y,
-            WGS84_RADII_SQUARED.2 * n.z,
+        assert_abs_diff_eq!(
+            ecef.normal(Geographic::new(0.0, 180.0, 0.0)),
+            Vec3::new(-1.0, 0.0, 0.0),
+            epsilon = 1e-6
         );
-        let gamma = (k.x * n.x + k.y * n.y + k.z * n.z).sqrt();
-        let surface = k / gamma;
-        let result = surface + (geo.elevation() * n);
+    }
+
+    #[test]
+    fn inverse_of_the_axes_returns_correct_values() {
+        let ecef = ECEF::default();
+
+        let x = ecef.inverse(Vec3::new(WGS84_SEMI_MAJOR_AXIS as f32, 0.0, 0.0));
+        assert!(same_position(x, Geographic::new(0.0, 0.0, 0.0), 1e-6, 1.0));
+
+        let y = ecef.inverse(Vec3::new(0.0, WGS84_SEMI_MAJOR_AXIS as f32, 0.0));
+        assert!(same_position(y, Geographic::new(0.0, 90.0, 0.0), 1e-6, 1.0));
+
+        let z = ecef.inverse(Vec3::new(0.0, 0.0, WGS84_SEMI_MINOR_AXIS as f32 + 1000.0));
+        assert!(same_position(
+            z,
+            Geographic::new(90.0, 0.0, 1000.0),
+            1e-6,
+            1.0
+        ));
+    }
+
+    #[test]
+    fn inverse_of_the_center_does_not_panic() {
+        let geo = ECEF::default().inverse(Vec3::new(0.0, 0.0, 0.0));
+
+        assert_eq!(geo.elevation(), MIN_ALT);
+    }
+
+    #[quickcheck]
+    fn inverse_f64_is_the_inverse_of_the_closed_form_transform(geo: Geographic) -> bool {
+        let lat = geo.lat_rad();
+        let lon = geo.lon_rad();
+
+        // The usual closed form of the forward transform, using the prime vertical radius.
+        let a = WGS84_SEMI_MAJOR_AXIS;
+        let e2 = Ellipsoid::wgs84().eccentricity_squared();
+        let n = a / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+        let p = DVec3::new(
+            (n + geo.elevation()) * lat.cos() * lon.cos(),
+            (n + geo.elevation()) * lat.cos() * lon.sin(),
+            (n * (1.0 - e2) + geo.elevation()) * lat.sin(),
+        );
+
+        same_position(ECEF::default().inverse_f64(p), geo, 1e-9, 1e-4)
+    }
+
+    #[quickcheck]
+    fn inverse_of_convert_returns_the_original_coordinate(geo: Geographic) -> bool {
+        let ecef = ECEF::default();
+        let result = ecef.inverse(ecef.convert(geo));
 
-        Vec3::new(result.x as f32, result.z as f32, result.y as f32)
+        // Single precision positions are only accurate to a few meters
+        // at the largest supported altitudes.
+        same_position(result, geo, 1e-5, 8.0)
     }
 
-    fn normal(self: &Self, geo: Geographic) -> Vec3 {
-        let cos_lat = geo.lat().cos() as f32;
+    #[test]
+    fn convert_uses_the_provided_ellipsoid() {
+        let moon = ECEF::new(Ellipsoid::moon());
+        let v = moon.convert_f64(Geographic::new(0.0, 90.0, 100.0));
 
-        Vec3::new(
-            cos_lat * geo.lon().cos() as f32,
-            cos_lat * geo.lon().sin() as f32,
-            geo.lat().sin() as f32,
-        )
+        assert_abs_diff_eq!(v, DVec3::new(0.0, 1_737_500.0, 0.0), epsilon = 1e-6);
     }
 }
@@ -0,0 +1,385 @@
+//! Earth-centered inertial (ECI) frames, and the Julian dates and sidereal times
+//! that relate them to the rotating earth-fixed frame of `ECEF`.
+
+use crate::angle::{Degrees, Radians};
+use glm::{DMat3, DVec3};
+use std::f64::consts::PI;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The Julian date of the J2000 epoch, January 1st 2000 at 12:00 TT.
+pub const J2000: f64 = 2_451_545.0;
+
+/// The Julian date of the Unix epoch, January 1st 1970 at 00:00 UTC.
+const UNIX_EPOCH_DATE: f64 = 2_440_587.5;
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// The number of days in a Julian century.
+const DAYS_PER_CENTURY: f64 = 36_525.0;
+
+/// The difference between Terrestrial Time and UTC, in seconds, since the leap second
+/// of January 1st 2017.
+const TT_MINUS_UTC: f64 = 69.184;
+
+/// An instant, as a Julian date (a number of days) in the UTC time scale.
+/// Universal Time (UT1) is taken equal to UTC, which is off by less than a second, and
+/// Terrestrial Time is UTC plus 69.184 seconds, which ignores future leap seconds.
+#[derive(Debug, Default, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JulianDate(pub f64);
+
+impl JulianDate {
+    /// Creates the date of the UTC calendar date and time, in the proleptic Gregorian calendar.
+    pub fn from_utc(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: f64) -> Self {
+        assert!((1..=12).contains(&month));
+
+        // The number of days since the Unix epoch, counting years from March so that
+        // leap days are at the end of years.
+        let year = i64::from(year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = i64::from(month);
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        let seconds = f64::from(hour * 3600 + minute * 60) + second;
+        JulianDate(UNIX_EPOCH_DATE + days as f64 + seconds / SECONDS_PER_DAY)
+    }
+
+    /// Creates the date of the number of seconds since the Unix epoch.
+    pub fn from_unix_seconds(seconds: f64) -> Self {
+        JulianDate(UNIX_EPOCH_DATE + seconds / SECONDS_PER_DAY)
+    }
+
+    /// Creates the date of the system time.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs_f64(),
+            Err(e) => -e.duration().as_secs_f64(),
+        };
+
+        Self::from_unix_seconds(seconds)
+    }
+
+    /// Returns the current date.
+    pub fn now() -> Self {
+        Self::from_system_time(SystemTime::now())
+    }
+
+    /// Returns the number of days.
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Returns the number of Julian centuries of Universal Time since J2000.
+    pub(crate) fn ut_centuries(self) -> f64 {
+        (self.0 - J2000) / DAYS_PER_CENTURY
+    }
+
+    /// Returns the number of Julian centuries of Terrestrial Time since J2000.
+    pub(crate) fn tt_centuries(self) -> f64 {
+        (self.0 + TT_MINUS_UTC / SECONDS_PER_DAY - J2000) / DAYS_PER_CENTURY
+    }
+}
+
+/// Returns the Greenwich mean sidereal time at the date, as an angle in the [0, 2π) range,
+/// with the IAU 1982 model.
+pub fn gmst(date: JulianDate) -> Radians {
+    let t = date.ut_centuries();
+    let degrees =
+        280.460_618_37 + 360.985_647_366_29 * (date.value() - J2000) + 0.000_387_933 * t * t
+            - t * t * t / 38_710_000.0;
+
+    Degrees(degrees.rem_euclid(360.0)).to_radians()
+}
+
+/// Returns the Greenwich apparent sidereal time at the date, as an angle in the [0, 2π)
+/// range: the mean sidereal time corrected by the nutation in longitude.
+pub fn gast(date: JulianDate) -> Radians {
+    let t = date.tt_centuries();
+    let (longitude, obliquity) = nutation(t);
+    let equation = longitude.value() * (mean_obliquity(t) + obliquity.value()).cos();
+
+    Radians((gmst(date).value() + equation).rem_euclid(2.0 * PI))
+}
+
+/// Returns the mean obliquity of the ecliptic, in radians, at `t` Julian centuries
+/// of Terrestrial Time since J2000.
+pub(crate) fn mean_obliquity(t: f64) -> f64 {
+    let arcseconds = 84_381.448 - 46.815_0 * t - 0.000_59 * t * t + 0.001_813 * t * t * t;
+
+    Degrees(arcseconds / 3600.0).to_radians().value()
+}
+
+/// Returns the nutation in longitude and in obliquity at `t` Julian centuries of
+/// Terrestrial Time since J2000, with the main terms of the IAU 1980 model, accurate
+/// to 0.5″ in longitude and 0.1″ in obliquity.
+fn nutation(t: f64) -> (Radians, Radians) {
+    let arg = |degrees: f64| Degrees(degrees).to_radians();
+    // The longitudes of the ascending node of the moon, of the sun and of the moon.
+    let node = arg(125.044_52 - 1_934.136_261 * t);
+    let sun = arg(280.466_5 + 36_000.769_8 * t);
+    let moon = arg(218.316_5 + 481_267.881_3 * t);
+    let double = |a: Radians| Radians(2.0 * a.value());
+
+    let longitude = -17.20 * node.sin() - 1.32 * double(sun).sin() - 0.23 * double(moon).sin()
+        + 0.21 * double(node).sin();
+    let obliquity = 9.20 * node.cos() + 0.57 * double(sun).cos() + 0.10 * double(moon).cos()
+        - 0.09 * double(node).cos();
+
+    (arg(longitude / 3600.0), arg(obliquity / 3600.0))
+}
+
+/// Returns the matrix that rotates a frame by the angle around the `X` axis.
+fn rotation_x(angle: f64) -> DMat3 {
+    let (s, c) = angle.sin_cos();
+
+    #[rustfmt::skip]
+    let m = DMat3::new(
+        1.0, 0.0, 0.0,
+        0.0, c, s,
+        0.0, -s, c,
+    );
+    m
+}
+
+/// Returns the matrix that rotates a frame by the angle around the `Y` axis.
+fn rotation_y(angle: f64) -> DMat3 {
+    let (s, c) = angle.sin_cos();
+
+    #[rustfmt::skip]
+    let m = DMat3::new(
+        c, 0.0, -s,
+        0.0, 1.0, 0.0,
+        s, 0.0, c,
+    );
+    m
+}
+
+/// Returns the matrix that rotates a frame by the angle around the `Z` axis.
+fn rotation_z(angle: f64) -> DMat3 {
+    let (s, c) = angle.sin_cos();
+
+    #[rustfmt::skip]
+    let m = DMat3::new(
+        c, s, 0.0,
+        -s, c, 0.0,
+        0.0, 0.0, 1.0,
+    );
+    m
+}
+
+/// Returns the IAU 1976 precession matrix, from the mean equator and equinox of J2000
+/// to those of the date, at `t` Julian centuries of Terrestrial Time since J2000.
+fn precession(t: f64) -> DMat3 {
+    let arg = |arcseconds: f64| Degrees(arcseconds / 3600.0).to_radians().value();
+    let zeta = arg(2_306.218_1 * t + 0.301_88 * t * t + 0.017_998 * t * t * t);
+    let z = arg(2_306.218_1 * t + 1.094_68 * t * t + 0.018_203 * t * t * t);
+    let theta = arg(2_004.310_9 * t - 0.426_65 * t * t - 0.041_833 * t * t * t);
+
+    rotation_z(-z) * rotation_y(theta) * rotation_z(-zeta)
+}
+
+/// The inertial frame of an `ECI` transformation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InertialFrame {
+    /// The mean equator and equinox of the date. Only the rotation of the earth is
+    /// applied, with the mean sidereal time. This frame drifts by about 50″ per year
+    /// relative to the stars, which is enough for lighting.
+    #[default]
+    OfDate,
+    /// The mean equator and equinox of J2000 (close to the GCRF), in which orbits
+    /// and star catalogs are usually expressed. Precession and nutation are applied,
+    /// along with the apparent sidereal time.
+    J2000,
+}
+
+/// The transformation between an Earth-Centered Inertial frame and the earth-centered,
+/// earth-fixed frame of `ECEF`, at a given date. Both frames share their origin, and
+/// the `Z` axis of the inertial frame points to the north celestial pole.
+/// The `X` axis points to the vernal equinox, whereas the `X` axis of `ECEF` follows
+/// the prime meridian as the earth rotates. Polar motion is ignored.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ECI {
+    date: JulianDate,
+    frame: InertialFrame,
+    // The rotation from the inertial frame to the earth-fixed frame.
+    rotation: DMat3,
+}
+
+impl ECI {
+    /// Creates the transformation at the date.
+    pub fn new(date: JulianDate, frame: InertialFrame) -> Self {
+        let rotation = match frame {
+            InertialFrame::OfDate => rotation_z(gmst(date).value()),
+            InertialFrame::J2000 => {
+                let t = date.tt_centuries();
+                let (longitude, obliquity) = nutation(t);
+                let mean = mean_obliquity(t);
+                let nutation = rotation_x(-(mean + obliquity.value()))
+                    * rotation_z(-longitude.value())
+                    * rotation_x(mean);
+
+                rotation_z(gast(date).value()) * nutation * precession(t)
+            }
+        };
+
+        ECI {
+            date,
+            frame,
+            rotation,
+        }
+    }
+
+    /// Returns the date of this transformation.
+    pub fn date(&self) -> JulianDate {
+        self.date
+    }
+
+    /// Returns the inertial frame of this transformation.
+    pub fn frame(&self) -> InertialFrame {
+        self.frame
+    }
+
+    /// Returns the rotation matrix from the inertial frame to the earth-fixed frame.
+    pub fn rotation(&self) -> DMat3 {
+        self.rotation
+    }
+
+    /// Converts a position or direction of the inertial frame into the earth-fixed frame.
+    pub fn to_ecef(&self, v: DVec3) -> DVec3 {
+        self.rotation * v
+    }
+
+    /// Converts a position or direction of the earth-fixed frame into the inertial frame.
+    pub fn from_ecef(&self, v: DVec3) -> DVec3 {
+        self.rotation.transpose() * v
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geographic::Geographic;
+    use crate::transformations::ecef::ECEF;
+    use crate::transformations::eci::*;
+    use crate::transformations::SpatialReference;
+
+    fn degrees(angle: Radians) -> f64 {
+        angle.to_degrees().value()
+    }
+
+    #[test]
+    fn from_utc_returns_correct_values() {
+        assert_eq!(
+            JulianDate::from_utc(2000, 1, 1, 12, 0, 0.0),
+            JulianDate(J2000)
+        );
+        assert_eq!(
+            JulianDate::from_utc(1970, 1, 1, 0, 0, 0.0),
+            JulianDate(UNIX_EPOCH_DATE)
+        );
+        assert_eq!(
+            JulianDate::from_utc(2024, 2, 29, 0, 0, 0.0),
+            JulianDate(2_460_369.5)
+        );
+        assert_abs_diff_eq!(
+            JulianDate::from_utc(1957, 10, 4, 19, 26, 24.0).value(),
+            2_436_116.31,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn from_system_time_returns_correct_values() {
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(946_728_000);
+
+        assert_eq!(JulianDate::from_system_time(time), JulianDate(J2000));
+        assert_eq!(
+            JulianDate::from_system_time(UNIX_EPOCH),
+            JulianDate(UNIX_EPOCH_DATE)
+        );
+    }
+
+    #[test]
+    fn gmst_returns_correct_values() {
+        let midnight = JulianDate::from_utc(1987, 4, 10, 0, 0, 0.0);
+        let evening = JulianDate::from_utc(1987, 4, 10, 19, 21, 0.0);
+
+        assert_abs_diff_eq!(degrees(gmst(midnight)), 197.693_195, epsilon = 1e-6);
+        assert_abs_diff_eq!(degrees(gmst(evening)), 128.737_873_4, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn nutation_returns_correct_values() {
+        let t = (JulianDate::from_utc(1987, 4, 10, 0, 0, 0.0).value() - J2000) / DAYS_PER_CENTURY;
+        let (longitude, obliquity) = nutation(t);
+
+        assert_abs_diff_eq!(degrees(longitude) * 3600.0, -3.788, epsilon = 0.5);
+        assert_abs_diff_eq!(degrees(obliquity) * 3600.0, 9.443, epsilon = 0.1);
+        assert_abs_diff_eq!(
+            mean_obliquity(t).to_degrees(),
+            23.0 + 26.0 / 60.0 + 27.407 / 3600.0,
+            epsilon = 1e-5
+        );
+    }
+
+    #[test]
+    fn gast_differs_from_gmst_by_the_equation_of_the_equinoxes() {
+        let date = JulianDate::from_utc(1987, 4, 10, 0, 0, 0.0);
+        let difference = (degrees(gast(date)) - degrees(gmst(date))) * 3600.0;
+
+        // -0.2317 seconds of time.
+        assert_abs_diff_eq!(difference, -0.2317 * 15.0, epsilon = 0.5);
+    }
+
+    #[test]
+    fn frames_differ_by_the_precession() {
+        let angle = |date: JulianDate| {
+            let of_date = ECI::new(date, InertialFrame::OfDate);
+            let j2000 = ECI::new(date, InertialFrame::J2000);
+            let pole = j2000.from_ecef(of_date.to_ecef(DVec3::z()));
+
+            pole.angle(&DVec3::z()).to_degrees()
+        };
+
+        // The pole moves by about 20″ per year, and nutation adds at most 9″.
+        assert!(angle(JulianDate(J2000)) * 3600.0 < 10.0);
+        let drift = angle(JulianDate::from_utc(2050, 1, 1, 0, 0, 0.0)) * 3600.0;
+        assert_abs_diff_eq!(drift, 50.0 * 20.04, epsilon = 10.0);
+    }
+
+    #[test]
+    fn of_date_frame_follows_the_rotation_of_the_earth() {
+        let date = JulianDate::from_utc(1987, 4, 10, 0, 0, 0.0);
+        let eci = ECI::new(date, InertialFrame::OfDate);
+
+        // The prime meridian is at the sidereal time, east of the equinox.
+        let meridian = eci.from_ecef(DVec3::x());
+        assert_abs_diff_eq!(
+            meridian.y.atan2(meridian.x).to_degrees().rem_euclid(360.0),
+            197.693_195,
+            epsilon = 1e-6
+        );
+        assert_abs_diff_eq!(eci.to_ecef(DVec3::z()), DVec3::z(), epsilon = 1e-12);
+    }
+
+    #[quickcheck]
+    fn from_ecef_is_the_inverse_of_to_ecef(geo: Geographic, seconds: i32) -> bool {
+        let v = ECEF::default().convert_f64(geo);
+        let date = JulianDate::from_unix_seconds(f64::from(seconds));
+
+        [InertialFrame::OfDate, InertialFrame::J2000]
+            .iter()
+            .all(|frame| {
+                let eci = ECI::new(date, *frame);
+                (eci.from_ecef(eci.to_ecef(v)) - v).norm() < 1e-6
+            })
+    }
+}
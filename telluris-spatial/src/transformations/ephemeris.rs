@@ -0,0 +1,172 @@
+//! Low precision positions of the sun and the moon, in the inertial frames of
+//! the `eci` module, for lighting and shadows rather than astronomy.
+
+use crate::angle::Degrees;
+use crate::transformations::eci::{mean_obliquity, InertialFrame, JulianDate, ECI};
+use glm::DVec3;
+
+/// The astronomical unit, in meters.
+pub const ASTRONOMICAL_UNIT: f64 = 149_597_870_700.0;
+
+/// The equatorial radius of the earth used by lunar parallaxes, in meters.
+const EARTH_RADIUS: f64 = 6_378_140.0;
+
+/// Returns the unit vector of the ecliptic longitude and latitude, in degrees,
+/// in the equatorial frame of the date.
+fn from_ecliptic(longitude: f64, latitude: f64, t: f64) -> DVec3 {
+    let (sin_lon, cos_lon) = Degrees(longitude).to_radians().sin_cos();
+    let (sin_lat, cos_lat) = Degrees(latitude).to_radians().sin_cos();
+    let (sin_obl, cos_obl) = mean_obliquity(t).sin_cos();
+
+    DVec3::new(
+        cos_lat * cos_lon,
+        cos_obl * cos_lat * sin_lon - sin_obl * sin_lat,
+        sin_obl * cos_lat * sin_lon + cos_obl * sin_lat,
+    )
+}
+
+/// Returns the geocentric position of the sun at the date, in meters, in the inertial
+/// frame of the mean equator and equinox of the date (`InertialFrame::OfDate`).
+/// The low precision formulae of the Astronomical Almanac are accurate to about 0.01°
+/// between 1950 and 2050.
+pub fn sun_eci(date: JulianDate) -> DVec3 {
+    let t = date.tt_centuries();
+    let days = t * 36_525.0;
+
+    let mean_longitude = 280.460 + 0.985_647_4 * days;
+    let anomaly = Degrees(357.528 + 0.985_600_3 * days).to_radians().value();
+    let longitude = mean_longitude + 1.915 * anomaly.sin() + 0.020 * (2.0 * anomaly).sin();
+    let distance = 1.000_14 - 0.016_71 * anomaly.cos() - 0.000_14 * (2.0 * anomaly).cos();
+
+    from_ecliptic(longitude, 0.0, t) * (distance * ASTRONOMICAL_UNIT)
+}
+
+/// Returns the geocentric position of the moon at the date, in meters, in the inertial
+/// frame of the mean equator and equinox of the date (`InertialFrame::OfDate`).
+/// The low precision formulae of the Astronomical Almanac are accurate to about 0.3°
+/// and 0.2% of the distance.
+pub fn moon_eci(date: JulianDate) -> DVec3 {
+    let t = date.tt_centuries();
+    // Sums the terms made of an amplitude, a phase and a rate, in degrees and degrees
+    // per century.
+    let sum = |terms: &[(f64, f64, f64)], f: fn(f64) -> f64| -> f64 {
+        terms
+            .iter()
+            .map(|(amplitude, phase, rate)| {
+                amplitude * f(Degrees(phase + rate * t).to_radians().value())
+            })
+            .sum()
+    };
+
+    let longitude = 218.32
+        + 481_267.881 * t
+        + sum(
+            &[
+                (6.29, 135.0, 477_198.87),
+                (-1.27, 259.3, -413_335.36),
+                (0.66, 235.7, 890_534.22),
+                (0.21, 269.9, 954_397.74),
+                (-0.19, 357.5, 35_999.05),
+                (-0.11, 186.5, 966_404.03),
+            ],
+            f64::sin,
+        );
+    let latitude = sum(
+        &[
+            (5.13, 93.3, 483_202.02),
+            (0.28, 228.2, 960_400.89),
+            (-0.28, 318.3, 6_003.15),
+            (-0.17, 217.6, -407_332.21),
+        ],
+        f64::sin,
+    );
+    let parallax = 0.9508
+        + sum(
+            &[
+                (0.0518, 135.0, 477_198.87),
+                (0.0095, 259.3, -413_335.36),
+                (0.0078, 235.7, 890_534.22),
+                (0.0028, 269.9, 954_397.74),
+            ],
+            f64::cos,
+        );
+
+    let distance = EARTH_RADIUS / Degrees(parallax).to_radians().sin();
+    from_ecliptic(longitude, latitude, t) * distance
+}
+
+/// Returns the unit vector from the center of the earth to the sun at the date,
+/// in the earth-fixed frame of `ECEF`. Useful to shade the day and night sides.
+pub fn sun_direction(date: JulianDate) -> DVec3 {
+    ECI::new(date, InertialFrame::OfDate).to_ecef(sun_eci(date).normalize())
+}
+
+/// Returns the unit vector from the center of the earth to the moon at the date,
+/// in the earth-fixed frame of `ECEF`.
+pub fn moon_direction(date: JulianDate) -> DVec3 {
+    ECI::new(date, InertialFrame::OfDate).to_ecef(moon_eci(date).normalize())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::transformations::ephemeris::*;
+
+    /// Returns the right ascension and declination of the vector, in degrees.
+    fn equatorial(v: DVec3) -> (f64, f64) {
+        let v = v.normalize();
+
+        (
+            v.y.atan2(v.x).to_degrees().rem_euclid(360.0),
+            v.z.asin().to_degrees(),
+        )
+    }
+
+    #[test]
+    fn sun_eci_returns_correct_values() {
+        let date = JulianDate::from_utc(1992, 10, 13, 0, 0, 0.0);
+        let sun = sun_eci(date);
+        let (ra, dec) = equatorial(sun);
+
+        // The apparent position, which includes the aberration and the nutation.
+        assert_abs_diff_eq!(ra, 198.378_178, epsilon = 0.02);
+        assert_abs_diff_eq!(dec, -7.783_872, epsilon = 0.02);
+        assert_abs_diff_eq!(sun.norm() / ASTRONOMICAL_UNIT, 0.997_61, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn moon_eci_returns_correct_values() {
+        let date = JulianDate::from_utc(1992, 4, 12, 0, 0, 0.0);
+        let moon = moon_eci(date);
+        let (ra, dec) = equatorial(moon);
+
+        assert_abs_diff_eq!(ra, 134.688_470, epsilon = 0.3);
+        assert_abs_diff_eq!(dec, 13.768_368, epsilon = 0.3);
+        assert_abs_diff_eq!(moon.norm(), 368_409_700.0, epsilon = 1_000_000.0);
+    }
+
+    #[test]
+    fn sun_direction_is_above_the_prime_meridian_at_noon() {
+        // The equation of time is at most 16 minutes, or 4°.
+        for month in 1..=12 {
+            let sun = sun_direction(JulianDate::from_utc(2021, month, 15, 12, 0, 0.0));
+
+            assert_abs_diff_eq!(sun.norm(), 1.0, epsilon = 1e-12);
+            assert!(sun.y.atan2(sun.x).to_degrees().abs() < 4.5);
+        }
+
+        // Near the june solstice, the sun is above the tropic of Cancer.
+        let solstice = sun_direction(JulianDate::from_utc(2021, 6, 21, 3, 32, 0.0));
+        assert_abs_diff_eq!(solstice.z.asin().to_degrees(), 23.44, epsilon = 0.01);
+    }
+
+    #[test]
+    fn moon_direction_is_opposite_to_the_sun_at_full_moon() {
+        let date = JulianDate::from_utc(2021, 5, 26, 11, 14, 0.0);
+        let angle = moon_direction(date)
+            .angle(&sun_direction(date))
+            .to_degrees();
+
+        // The moon was also eclipsed, so it was close to the ecliptic.
+        assert_abs_diff_eq!(angle, 180.0, epsilon = 1.0);
+    }
+}
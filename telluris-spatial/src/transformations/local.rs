@@ -0,0 +1,437 @@
+use crate::angle::{Degrees, Radians};
+use crate::geographic::*;
+use crate::transformations::SpatialReference;
+use glm::{DMat3, DMat4, DQuat, DVec3};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The axes of a local tangent frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Axes {
+    /// `X` points east, `Y` points north and `Z` points up, along the surface normal.
+    EastNorthUp,
+    /// `X` points north, `Y` points east and `Z` points down, against the surface normal.
+    /// This is the usual frame of aircraft and vehicles.
+    NorthEastDown,
+}
+
+/// A cartesian frame tangent to the surface of the ellipsoid, whose origin is
+/// anchored to a geographic coordinate. Local coordinates are expressed in meters.
+/// The `Z` axis of the spatial reference is expected to be the polar axis, as in `ECEF`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LocalFrame {
+    origin: Geographic,
+    center: DVec3,
+    east: DVec3,
+    north: DVec3,
+    up: DVec3,
+    axes: Axes,
+}
+
+impl LocalFrame {
+    /// Creates a local frame anchored to the origin, with the specified axes.
+    pub fn new<S: SpatialReference + ?Sized>(srs: &S, origin: Geographic, axes: Axes) -> Self {
+        let up = srs.normal_f64(origin);
+
+        // The east vector is computed from the longitude rather than from the
+        // normal, so that it remains defined at the poles.
+        let (sin_lon, cos_lon) = origin.lon_rad().sin_cos();
+        let east = DVec3::new(-sin_lon, cos_lon, 0.0);
+        let north = up.cross(&east);
+
+        LocalFrame {
+            origin,
+            center: srs.convert_f64(origin),
+            east,
+            north,
+            up,
+            axes,
+        }
+    }
+
+    /// Creates an East-North-Up frame anchored to the origin.
+    pub fn enu<S: SpatialReference + ?Sized>(srs: &S, origin: Geographic) -> Self {
+        Self::new(srs, origin, Axes::EastNorthUp)
+    }
+
+    /// Creates a North-East-Down frame anchored to the origin.
+    pub fn ned<S: SpatialReference + ?Sized>(srs: &S, origin: Geographic) -> Self {
+        Self::new(srs, origin, Axes::NorthEastDown)
+    }
+
+    /// Returns the geographic coordinate of the origin of this frame.
+    pub fn origin(&self) -> Geographic {
+        self.origin
+    }
+
+    /// Returns the position of the origin of this frame, in the spatial reference.
+    pub fn center(&self) -> DVec3 {
+        self.center
+    }
+
+    /// Returns the axes of this frame.
+    pub fn axes(&self) -> Axes {
+        self.axes
+    }
+
+    /// Returns the rotation from local axes to the axes of the spatial reference.
+    /// The columns of the matrix are the local axes.
+    pub fn rotation(&self) -> DMat3 {
+        match self.axes {
+            Axes::EastNorthUp => DMat3::from_columns(&[self.east, self.north, self.up]),
+            Axes::NorthEastDown => DMat3::from_columns(&[self.north, self.east, -self.up]),
+        }
+    }
+
+    /// Returns the matrix that transforms local coordinates into the spatial reference.
+    pub fn to_ecef_matrix(&self) -> DMat4 {
+        glm::translation(&self.center) * glm::mat3_to_mat4(&self.rotation())
+    }
+
+    /// Returns the matrix that transforms coordinates of the spatial reference into local coordinates.
+    pub fn from_ecef_matrix(&self) -> DMat4 {
+        glm::mat3_to_mat4(&self.rotation().transpose()) * glm::translation(&-self.center)
+    }
+
+    /// Transforms local coordinates into the spatial reference.
+    pub fn to_ecef(&self, local: DVec3) -> DVec3 {
+        self.rotation() * local + self.center
+    }
+
+    /// Transforms coordinates of the spatial reference into local coordinates.
+    pub fn to_local(&self, p: DVec3) -> DVec3 {
+        self.rotation().transpose() * (p - self.center)
+    }
+
+    /// Returns the orientation, in the spatial reference, of a body whose attitude
+    /// relative to this frame is described by the heading, pitch and roll.
+    /// The body axes are `X` forward, `Y` right and `Z` down.
+    pub fn orientation(&self, hpr: &HeadingPitchRoll) -> DQuat {
+        let ned = DMat3::from_columns(&[self.north, self.east, -self.up]);
+
+        glm::mat3_to_quat(&(ned * glm::quat_to_mat3(&hpr.to_quaternion())))
+    }
+
+    /// Returns the heading, pitch and roll relative to this frame of a body whose
+    /// orientation in the spatial reference is given. This is the inverse of `orientation`.
+    pub fn heading_pitch_roll(&self, orientation: &DQuat) -> HeadingPitchRoll {
+        let ned = DMat3::from_columns(&[self.north, self.east, -self.up]);
+        let local = ned.transpose() * glm::quat_to_mat3(orientation);
+
+        HeadingPitchRoll::from_quaternion(&glm::mat3_to_quat(&local))
+    }
+}
+
+/// The serialized form of `LocalFrame`, whose vectors are `[x, y, z]` arrays.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "LocalFrame", deny_unknown_fields)]
+struct LocalFrameFields {
+    origin: Geographic,
+    center: [f64; 3],
+    east: [f64; 3],
+    north: [f64; 3],
+    up: [f64; 3],
+    axes: Axes,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for LocalFrame {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let array = |v: DVec3| [v.x, v.y, v.z];
+
+        LocalFrameFields {
+            origin: self.origin,
+            center: array(self.center),
+            east: array(self.east),
+            north: array(self.north),
+            up: array(self.up),
+            axes: self.axes,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes a frame, whose center must be finite and whose east, north and up
+/// vectors must form a right-handed orthonormal basis.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for LocalFrame {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = LocalFrameFields::deserialize(deserializer)?;
+        let vector = |v: [f64; 3]| DVec3::new(v[0], v[1], v[2]);
+        let (center, east, north, up) = (
+            vector(f.center),
+            vector(f.east),
+            vector(f.north),
+            vector(f.up),
+        );
+
+        if !center.iter().all(|v| v.is_finite()) {
+            return Err(de::Error::custom("the center must be finite"));
+        }
+
+        let basis = DMat3::from_columns(&[east, north, up]);
+        let error = (basis.transpose() * basis - DMat3::identity()).norm();
+        if !(error < 1e-9 && basis.determinant() > 0.0) {
+            return Err(de::Error::custom(
+                "the axes must form a right-handed orthonormal basis",
+            ));
+        }
+
+        Ok(LocalFrame {
+            origin: f.origin,
+            center,
+            east,
+            north,
+            up,
+            axes: f.axes,
+        })
+    }
+}
+
+/// The attitude of a body relative to a North-East-Down frame, in degrees.
+/// Rotations are applied in order: heading around the down axis, then pitch around
+/// the right axis, then roll around the forward axis.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HeadingPitchRoll {
+    /// The angle from the north, positive towards the east.
+    pub heading: f64,
+    /// The angle above the horizontal plane, positive when the nose points up.
+    pub pitch: f64,
+    /// The angle around the forward axis, positive when the right side goes down.
+    pub roll: f64,
+}
+
+impl HeadingPitchRoll {
+    /// Creates a heading, pitch and roll, in degrees.
+    pub fn new(heading: f64, pitch: f64, roll: f64) -> Self {
+        HeadingPitchRoll {
+            heading,
+            pitch,
+            roll,
+        }
+    }
+
+    /// Returns the quaternion that rotates body axes into the North-East-Down frame.
+    pub fn to_quaternion(&self) -> DQuat {
+        let half = |angle: f64| Radians::from(Degrees(angle / 2.0)).sin_cos();
+        let (sy, cy) = half(self.heading);
+        let (sp, cp) = half(self.pitch);
+        let (sr, cr) = half(self.roll);
+
+        glm::quat(
+            sr * cp * cy - cr * sp * sy,
+            cr * sp * cy + sr * cp * sy,
+            cr * cp * sy - sr * sp * cy,
+            cr * cp * cy + sr * sp * sy,
+        )
+    }
+
+    /// Returns the heading, pitch and roll of the quaternion that rotates
+    /// body axes into the North-East-Down frame.
+    /// At a pitch of ±90°, heading and roll are not distinct, and roll is set at zero.
+    pub fn from_quaternion(q: &DQuat) -> Self {
+        let q = glm::quat_normalize(q).coords;
+        let (x, y, z, w) = (q.x, q.y, q.z, q.w);
+
+        let sin_pitch = 2.0 * (w * y - z * x);
+
+        if sin_pitch.abs() >= 1.0 - 1e-12 {
+            // Gimbal lock: only the difference (or sum) of heading and roll is defined.
+            let heading = Radians::atan2(-2.0 * (x * y - w * z), 1.0 - 2.0 * (x * x + z * z));
+            let pitch = Degrees(90.0f64.copysign(sin_pitch));
+
+            return HeadingPitchRoll::new(heading.to_degrees().value(), pitch.value(), 0.0);
+        }
+
+        let heading = Radians::atan2(2.0 * (w * z + x * y), 1.0 - 2.0 * (y * y + z * z));
+        let pitch = Radians::asin(sin_pitch);
+        let roll = Radians::atan2(2.0 * (w * x + y * z), 1.0 - 2.0 * (x * x + y * y));
+
+        HeadingPitchRoll::new(
+            heading.to_degrees().value(),
+            pitch.to_degrees().value(),
+            roll.to_degrees().value(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::transformations::ecef::ECEF;
+    use crate::transformations::local::*;
+    use rand::Rng;
+
+    fn random_hpr() -> HeadingPitchRoll {
+        let mut rng = rand::thread_rng();
+
+        HeadingPitchRoll::new(
+            rng.gen_range(-179.0, 179.0),
+            rng.gen_range(-89.0, 89.0),
+            rng.gen_range(-179.0, 179.0),
+        )
+    }
+
+    fn assert_vec_eq(a: DVec3, b: DVec3) {
+        assert_abs_diff_eq!(a.x, b.x, epsilon = 1e-9);
+        assert_abs_diff_eq!(a.y, b.y, epsilon = 1e-9);
+        assert_abs_diff_eq!(a.z, b.z, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn enu_axes_at_the_origin() {
+        let frame = LocalFrame::enu(&ECEF::default(), Geographic::new(0.0, 0.0, 0.0));
+        let r = frame.rotation();
+
+        assert_vec_eq(r.column(0).into(), DVec3::new(0.0, 1.0, 0.0));
+        assert_vec_eq(r.column(1).into(), DVec3::new(0.0, 0.0, 1.0));
+        assert_vec_eq(r.column(2).into(), DVec3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn ned_axes_at_the_north_pole() {
+        let frame = LocalFrame::ned(&ECEF::default(), Geographic::new(90.0, 0.0, 0.0));
+        let r = frame.rotation();
+
+        assert_vec_eq(r.column(0).into(), DVec3::new(-1.0, 0.0, 0.0));
+        assert_vec_eq(r.column(1).into(), DVec3::new(0.0, 1.0, 0.0));
+        assert_vec_eq(r.column(2).into(), DVec3::new(0.0, 0.0, -1.0));
+    }
+
+    #[quickcheck]
+    fn raised_origin_lies_on_the_vertical_axis(geo: Geographic) -> bool {
+        let ecef = ECEF::default();
+        let origin = geo.flatten();
+        let height = geo.elevation();
+        let p = ecef.convert_f64(geo);
+
+        let enu = LocalFrame::enu(&ecef, origin).to_local(p);
+        let ned = LocalFrame::ned(&ecef, origin).to_local(p);
+
+        let epsilon = 1e-6 * height.abs().max(1.0);
+
+        abs_diff_eq!(enu, DVec3::new(0.0, 0.0, height), epsilon = epsilon)
+            && abs_diff_eq!(ned, DVec3::new(0.0, 0.0, -height), epsilon = epsilon)
+    }
+
+    #[quickcheck]
+    fn to_ecef_is_the_inverse_of_to_local(origin: Geographic, other: Geographic) -> bool {
+        let ecef = ECEF::default();
+        let frame = LocalFrame::enu(&ecef, origin);
+        let p = ecef.convert_f64(other);
+
+        abs_diff_eq!(frame.to_ecef(frame.to_local(p)), p, epsilon = 1e-3)
+    }
+
+    #[quickcheck]
+    fn matrices_are_consistent_with_point_transforms(
+        origin: Geographic,
+        other: Geographic,
+    ) -> bool {
+        let ecef = ECEF::default();
+        let frame = LocalFrame::ned(&ecef, origin);
+        let p = ecef.convert_f64(other);
+
+        let local = frame.from_ecef_matrix() * glm::vec4(p.x, p.y, p.z, 1.0);
+        let back = frame.to_ecef_matrix() * local;
+
+        abs_diff_eq!(local.xyz(), frame.to_local(p), epsilon = 1e-3)
+            && abs_diff_eq!(back.xyz(), p, epsilon = 1e-3)
+    }
+
+    #[cfg(feature = "serde")]
+    #[quickcheck]
+    fn deserialize_is_the_inverse_of_serialize(origin: Geographic) -> bool {
+        let frame = LocalFrame::ned(&ECEF::default(), origin);
+        let json = serde_json::to_string(&frame).unwrap();
+
+        serde_json::from_str::<LocalFrame>(&json).unwrap() == frame
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_validates_the_axes() {
+        let parse = |east: &str, north: &str, up: &str| {
+            serde_json::from_str::<LocalFrame>(&format!(
+                r#"{{"origin": {{"latitude": 0.0, "longitude": 0.0}},
+                    "center": [6378137.0, 0.0, 0.0],
+                    "east": {}, "north": {}, "up": {}, "axes": "EastNorthUp"}}"#,
+                east, north, up
+            ))
+        };
+
+        assert_eq!(
+            parse("[0, 1, 0]", "[0, 0, 1]", "[1, 0, 0]").unwrap(),
+            LocalFrame::enu(&ECEF::default(), Geographic::new(0.0, 0.0, 0.0))
+        );
+        assert!(parse("[0, 2, 0]", "[0, 0, 1]", "[1, 0, 0]").is_err());
+        assert!(parse("[0, 1, 0]", "[0, 1, 0]", "[1, 0, 0]").is_err());
+        assert!(parse("[0, 0, 1]", "[0, 1, 0]", "[1, 0, 0]").is_err());
+    }
+
+    #[test]
+    fn heading_rotates_the_forward_axis_towards_the_east() {
+        let q = HeadingPitchRoll::new(90.0, 0.0, 0.0).to_quaternion();
+
+        assert_vec_eq(
+            glm::quat_rotate_vec3(&q, &DVec3::new(1.0, 0.0, 0.0)),
+            DVec3::new(0.0, 1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn positive_pitch_raises_the_nose() {
+        let q = HeadingPitchRoll::new(0.0, 30.0, 0.0).to_quaternion();
+        let forward = glm::quat_rotate_vec3(&q, &DVec3::new(1.0, 0.0, 0.0));
+
+        // Up is negative Z in a North-East-Down frame.
+        assert_abs_diff_eq!(forward.z, -0.5, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn orientation_with_zero_heading_points_north() {
+        let frame = LocalFrame::enu(&ECEF::default(), Geographic::new(0.0, 0.0, 0.0));
+        let q = frame.orientation(&HeadingPitchRoll::default());
+
+        assert_vec_eq(
+            glm::quat_rotate_vec3(&q, &DVec3::new(1.0, 0.0, 0.0)),
+            DVec3::new(0.0, 0.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn from_quaternion_at_vertical_pitch_keeps_the_heading() {
+        for pitch in [-90.0, 90.0].iter() {
+            let q = HeadingPitchRoll::new(30.0, *pitch, 0.0).to_quaternion();
+            let result = HeadingPitchRoll::from_quaternion(&q);
+
+            assert_abs_diff_eq!(result.heading, 30.0, epsilon = 1e-6);
+            assert_abs_diff_eq!(result.pitch, *pitch, epsilon = 1e-6);
+            assert_abs_diff_eq!(result.roll, 0.0, epsilon = 1e-6);
+        }
+    }
+
+    #[quickcheck]
+    fn from_quaternion_is_the_inverse_of_to_quaternion() -> bool {
+        let hpr = random_hpr();
+        let result = HeadingPitchRoll::from_quaternion(&hpr.to_quaternion());
+
+        abs_diff_eq!(result.heading, hpr.heading, epsilon = 1e-6)
+            && abs_diff_eq!(result.pitch, hpr.pitch, epsilon = 1e-6)
+            && abs_diff_eq!(result.roll, hpr.roll, epsilon = 1e-6)
+    }
+
+    #[quickcheck]
+    fn heading_pitch_roll_is_the_inverse_of_orientation(origin: Geographic) -> bool {
+        let frame = LocalFrame::enu(&ECEF::default(), origin);
+        let hpr = random_hpr();
+        let result = frame.heading_pitch_roll(&frame.orientation(&hpr));
+
+        abs_diff_eq!(result.heading, hpr.heading, epsilon = 1e-6)
+            && abs_diff_eq!(result.pitch, hpr.pitch, epsilon = 1e-6)
+            && abs_diff_eq!(result.roll, hpr.roll, epsilon = 1e-6)
+    }
+}
@@ -1,17 +1,211 @@
-//! Coordinate transformations from `Geographic` to a cartesian frame.
+//! Coordinate transformations from `Geographic` to a cartesian frame,
+//! and map projections from `Geographic` to a projected plane.
 
-use crate::geographic::Geographic;
+use crate::geobounds::GeoBounds;
+use crate::geographic::*;
+use glm::{DVec2, DVec3, Vec3};
 use std::fmt;
-use glm::Vec3;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 pub mod ecef;
+pub mod eci;
+pub mod ephemeris;
+pub mod local;
+pub mod polarstereographic;
+pub mod rtc;
+pub mod transversemercator;
+pub mod utm;
+pub mod webmercator;
 
 /// Provides coordinate transformation between geographic coordinates
 /// and cartesian coordinates.
+/// Single precision positions are only accurate to about half a meter at
+/// planetary scale. Use the `_f64` variants, or the `rtc` module, when
+/// more precision is required.
 pub trait SpatialReference: fmt::Debug {
     /// Converts geographic coordinates into cartesian coordinates.
-    fn convert(self: &Self, geo: Geographic) -> Vec3;
+    fn convert(&self, geo: Geographic) -> Vec3 {
+        rtc::to_f32(self.convert_f64(geo))
+    }
+
+    /// Converts geographic coordinates into double precision cartesian coordinates.
+    fn convert_f64(&self, geo: Geographic) -> DVec3;
+
+    /// Converts geographic coordinates into cartesian coordinates relative
+    /// to the provided origin. The offset is computed in double precision
+    /// before being truncated, which keeps it accurate near the origin.
+    fn convert_relative(&self, origin: DVec3, geo: Geographic) -> Vec3 {
+        rtc::to_f32(self.convert_f64(geo) - origin)
+    }
 
     /// Returns the normal vector of the given coordinate.
-    fn normal(self: &Self, geo: Geographic) -> Vec3;
+    fn normal(&self, geo: Geographic) -> Vec3;
+
+    /// Returns the double precision normal vector of the given coordinate.
+    fn normal_f64(&self, geo: Geographic) -> DVec3 {
+        rtc::to_f64(self.normal(geo))
+    }
+
+    /// Converts cartesian coordinates back into geographic coordinates.
+    /// Elevations are clamped in the domain specified by `MIN_ALT` and `MAX_ALT`.
+    fn inverse(&self, v: Vec3) -> Geographic {
+        self.inverse_f64(rtc::to_f64(v))
+    }
+
+    /// Converts double precision cartesian coordinates back into geographic coordinates.
+    /// Elevations are clamped in the domain specified by `MIN_ALT` and `MAX_ALT`.
+    fn inverse_f64(&self, v: DVec3) -> Geographic;
+}
+
+/// An axis-aligned rectangle in projected coordinates, in meters.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ProjectedBounds {
+    min: DVec2,
+    max: DVec2,
+}
+
+impl ProjectedBounds {
+    /// Creates bounds with the specified min and max corners.
+    pub fn new(min: DVec2, max: DVec2) -> Self {
+        debug_assert!(min.x <= max.x);
+        debug_assert!(min.y <= max.y);
+
+        ProjectedBounds { min, max }
+    }
+
+    /// Returns the minimal corner (the south-western corner for most projections).
+    pub fn min(&self) -> DVec2 {
+        self.min
+    }
+
+    /// Returns the maximal corner (the north-eastern corner for most projections).
+    pub fn max(&self) -> DVec2 {
+        self.max
+    }
+
+    /// Returns the width of the bounds, in meters.
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    /// Returns the height of the bounds, in meters.
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    /// Returns true if the point is contained in the bounds, including its borders.
+    pub fn contains(&self, p: DVec2) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+}
+
+/// The serialized form of `ProjectedBounds`, whose corners are `[x, y]` arrays.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ProjectedBounds", deny_unknown_fields)]
+struct ProjectedBoundsFields {
+    min: [f64; 2],
+    max: [f64; 2],
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ProjectedBounds {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ProjectedBoundsFields {
+            min: [self.min.x, self.min.y],
+            max: [self.max.x, self.max.y],
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes bounds, whose minimal corner must not be greater than their maximal corner.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ProjectedBounds {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = ProjectedBoundsFields::deserialize(deserializer)?;
+        let finite = f.min.iter().chain(f.max.iter()).all(|v| v.is_finite());
+        if !finite || f.min[0] > f.max[0] || f.min[1] > f.max[1] {
+            return Err(de::Error::custom("the minimal corner is greater than the maximal corner"));
+        }
+
+        Ok(ProjectedBounds::new(
+            DVec2::new(f.min[0], f.min[1]),
+            DVec2::new(f.max[0], f.max[1]),
+        ))
+    }
+}
+
+/// Provides conversion between geographic coordinates and the planar
+/// coordinates of a projected coordinate reference system.
+/// Elevations are not part of the projection: they are ignored when projecting,
+/// and set at zero when unprojecting.
+pub trait Projection: fmt::Debug {
+    /// Projects geographic coordinates on the plane.
+    fn project(&self, geo: Geographic) -> DVec2;
+
+    /// Returns the geographic coordinates of a point of the plane.
+    fn unproject(&self, p: DVec2) -> Geographic;
+
+    /// Returns the domain of validity of the projection, in projected coordinates.
+    fn extent(&self) -> ProjectedBounds;
+
+    /// Returns the projected extent of the geographic bounds. The default
+    /// implementation projects the four corners, which is only correct for
+    /// projections that preserve meridians and parallels as straight lines.
+    /// Bounds that cross the antimeridian are split, and the extents of both
+    /// parts are merged.
+    fn project_bounds(&self, bounds: &GeoBounds) -> ProjectedBounds {
+        let (western, eastern) = bounds.split_at_antimeridian();
+        let corners: Vec<DVec2> = std::iter::once(western)
+            .chain(eastern)
+            .flat_map(|b| {
+                vec![
+                    self.project(Geographic::new(b.south(), b.west(), 0.0)),
+                    self.project(Geographic::new(b.south(), b.east(), 0.0)),
+                    self.project(Geographic::new(b.north(), b.west(), 0.0)),
+                    self.project(Geographic::new(b.north(), b.east(), 0.0)),
+                ]
+            })
+            .collect();
+
+        let min = corners
+            .iter()
+            .fold(corners[0], |a, c| DVec2::new(a.x.min(c.x), a.y.min(c.y)));
+        let max = corners
+            .iter()
+            .fold(corners[0], |a, c| DVec2::new(a.x.max(c.x), a.y.max(c.y)));
+
+        ProjectedBounds::new(min, max)
+    }
+
+    /// Returns the geographic bounds of the projected extent, at zero elevation.
+    /// The default implementation has the same limitations as `project_bounds`.
+    /// The western edge is read on the left side of the extent and the eastern edge
+    /// on its right side, so that extents whose longitudes wrap around give bounds
+    /// that cross the antimeridian.
+    fn unproject_bounds(&self, bounds: &ProjectedBounds) -> GeoBounds {
+        let (min, max) = (bounds.min(), bounds.max());
+        let left = [
+            self.unproject(min),
+            self.unproject(DVec2::new(min.x, max.y)),
+        ];
+        let right = [
+            self.unproject(max),
+            self.unproject(DVec2::new(max.x, min.y)),
+        ];
+        let corners = left.iter().chain(right.iter());
+
+        let south = corners.clone().map(|c| c.lat()).fold(MAX_LAT, f64::min);
+        let north = corners.map(|c| c.lat()).fold(MIN_LAT, f64::max);
+        let west = left.iter().map(|c| c.lon()).fold(MAX_LON, f64::min);
+        let east = right.iter().map(|c| c.lon()).fold(MIN_LON, f64::max);
+
+        GeoBounds::new(
+            Geographic::new(south, west, 0.0),
+            Geographic::new(north, east, 0.0),
+        )
+    }
 }
@@ -0,0 +1,235 @@
+use crate::angle::Radians;
+use crate::ellipsoid::Ellipsoid;
+use crate::geographic::*;
+use crate::transformations::{ProjectedBounds, Projection};
+use glm::DVec2;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The ellipsoidal stereographic projection centered on a pole. This is the projection
+/// used by the UPS system (see the `utm` module). `X` is the easting and `Y` is the
+/// northing, both in meters. Seen from above the pole, the meridian 0° points towards
+/// the equator along `-Y` in the northern hemisphere, and along `+Y` in the southern one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolarStereographic {
+    ellipsoid: Ellipsoid,
+    hemisphere: Hemisphere,
+    scale_factor: f64,
+    false_easting: f64,
+    false_northing: f64,
+    // The distance from the pole of the unit conformal colatitude.
+    radius: f64,
+}
+
+impl PolarStereographic {
+    /// Creates a projection on the ellipsoid, centered on the pole of the hemisphere,
+    /// with the specified scale factor at the pole and offsets of the pole.
+    pub fn new(
+        ellipsoid: Ellipsoid,
+        hemisphere: Hemisphere,
+        scale_factor: f64,
+        false_easting: f64,
+        false_northing: f64,
+    ) -> Self {
+        debug_assert!(scale_factor > 0.0);
+
+        // See "Map Projections - A Working Manual", by J. P. Snyder, equation 21-33.
+        let e = ellipsoid.eccentricity();
+        let c = ((1.0 + e).powf(1.0 + e) * (1.0 - e).powf(1.0 - e)).sqrt();
+        let radius = 2.0 * ellipsoid.semi_major_axis() * scale_factor / c;
+
+        PolarStereographic {
+            ellipsoid,
+            hemisphere,
+            scale_factor,
+            false_easting,
+            false_northing,
+            radius,
+        }
+    }
+
+    /// Returns the ellipsoid of the projection.
+    pub fn ellipsoid(&self) -> &Ellipsoid {
+        &self.ellipsoid
+    }
+
+    /// Returns the hemisphere whose pole is the center of the projection.
+    pub fn hemisphere(&self) -> Hemisphere {
+        self.hemisphere
+    }
+
+    /// Returns the scale factor at the pole.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Returns the easting of the pole, in meters.
+    pub fn false_easting(&self) -> f64 {
+        self.false_easting
+    }
+
+    /// Returns the northing of the pole, in meters.
+    pub fn false_northing(&self) -> f64 {
+        self.false_northing
+    }
+
+    // Returns 1 for the northern hemisphere, and -1 for the southern one.
+    fn sign(&self) -> f64 {
+        match self.hemisphere {
+            Hemisphere::North => 1.0,
+            Hemisphere::South => -1.0,
+        }
+    }
+}
+
+/// The serialized form of `PolarStereographic`, without the radius of the projection,
+/// which is computed again when deserializing.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "PolarStereographic", deny_unknown_fields)]
+struct Parameters {
+    ellipsoid: Ellipsoid,
+    hemisphere: Hemisphere,
+    scale_factor: f64,
+    false_easting: f64,
+    false_northing: f64,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PolarStereographic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Parameters {
+            ellipsoid: self.ellipsoid,
+            hemisphere: self.hemisphere,
+            scale_factor: self.scale_factor,
+            false_easting: self.false_easting,
+            false_northing: self.false_northing,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes a projection, whose scale factor must be positive.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PolarStereographic {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let p = Parameters::deserialize(deserializer)?;
+        if !(p.scale_factor > 0.0 && p.scale_factor.is_finite()) {
+            return Err(de::Error::custom("the scale factor must be positive"));
+        }
+        if !(p.false_easting.is_finite() && p.false_northing.is_finite()) {
+            return Err(de::Error::custom("the false origin must be finite"));
+        }
+
+        Ok(PolarStereographic::new(
+            p.ellipsoid,
+            p.hemisphere,
+            p.scale_factor,
+            p.false_easting,
+            p.false_northing,
+        ))
+    }
+}
+
+impl Projection for PolarStereographic {
+    fn project(&self, geo: Geographic) -> DVec2 {
+        let sign = self.sign();
+        let chi = self
+            .ellipsoid
+            .conformal_latitude(Radians(sign * geo.lat_rad().value()));
+
+        let rho = self.radius * (FRAC_PI_4 - chi.value() / 2.0).tan();
+        let (sin_lon, cos_lon) = geo.lon_rad().sin_cos();
+
+        DVec2::new(
+            self.false_easting + rho * sin_lon,
+            self.false_northing - sign * rho * cos_lon,
+        )
+    }
+
+    fn unproject(&self, p: DVec2) -> Geographic {
+        let sign = self.sign();
+        let x = p.x - self.false_easting;
+        let y = p.y - self.false_northing;
+
+        let rho = x.hypot(y);
+        let chi = Radians(FRAC_PI_2 - 2.0 * (rho / self.radius).atan());
+        let lat = self.ellipsoid.geodetic_latitude(chi);
+        let lon = Radians::atan2(x, -sign * y);
+
+        Geographic::from_radians(Radians(sign * lat.value()), lon, 0.0)
+    }
+
+    /// Returns the projection of the hemisphere, down to the equator.
+    fn extent(&self) -> ProjectedBounds {
+        let half_extent = self.radius;
+
+        ProjectedBounds::new(
+            DVec2::new(
+                self.false_easting - half_extent,
+                self.false_northing - half_extent,
+            ),
+            DVec2::new(
+                self.false_easting + half_extent,
+                self.false_northing + half_extent,
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ellipsoid::Ellipsoid;
+    use crate::geographic::*;
+    use crate::transformations::polarstereographic::*;
+
+    fn ups(hemisphere: Hemisphere) -> PolarStereographic {
+        PolarStereographic::new(
+            Ellipsoid::wgs84(),
+            hemisphere,
+            0.994,
+            2_000_000.0,
+            2_000_000.0,
+        )
+    }
+
+    #[test]
+    fn project_returns_correct_values_for_reference_points() {
+        // Reference values computed with PROJ.
+        let north = ups(Hemisphere::North).project(Geographic::new(85.0, 45.0, 0.0));
+        assert_abs_diff_eq!(north.x, 2_392_767.688, epsilon = 0.001);
+        assert_abs_diff_eq!(north.y, 1_607_232.312, epsilon = 0.001);
+
+        let south = ups(Hemisphere::South).project(Geographic::new(-85.0, 45.0, 0.0));
+        assert_abs_diff_eq!(south.x, 2_392_767.688, epsilon = 0.001);
+        assert_abs_diff_eq!(south.y, 2_392_767.688, epsilon = 0.001);
+
+        let south = ups(Hemisphere::South).project(Geographic::new(-87.0, -120.0, 0.0));
+        assert_abs_diff_eq!(south.x, 1_711_488.413, epsilon = 0.001);
+        assert_abs_diff_eq!(south.y, 1_833_427.757, epsilon = 0.001);
+    }
+
+    #[test]
+    fn poles_project_on_the_origin() {
+        let north = ups(Hemisphere::North).project(Geographic::new(MAX_LAT, 12.0, 0.0));
+        let south = ups(Hemisphere::South).project(Geographic::new(MIN_LAT, 12.0, 0.0));
+
+        assert_abs_diff_eq!(north.x, 2_000_000.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(north.y, 2_000_000.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(south.x, 2_000_000.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(south.y, 2_000_000.0, epsilon = 1e-6);
+    }
+
+    #[quickcheck]
+    fn unproject_is_the_inverse_of_project(geo: Geographic) -> bool {
+        let ps = ups(geo.hemisphere());
+        let geo = geo.flatten();
+
+        let result = ps.unproject(ps.project(geo));
+
+        abs_diff_eq!(result.lat(), geo.lat(), epsilon = 1e-9)
+            && (abs_diff_eq!(result.lon(), geo.lon(), epsilon = 1e-9) || geo.lat().abs() > 89.999)
+    }
+}
@@ -0,0 +1,216 @@
+//! Relative-to-center (RTC) positions.
+//! GPUs work in single precision, which cannot represent positions at planetary
+//! scale with better than about half a meter of accuracy, producing visible
+//! vertex jitter when the camera is close to the surface. Expressing positions
+//! as small single precision offsets to a double precision center, or splitting
+//! them into a high and a low part, preserves the precision where it matters.
+
+use crate::geobounds::GeoBounds;
+use crate::geographic::Geographic;
+use crate::transformations::SpatialReference;
+use glm::{DVec3, Vec3};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Positions expressed as single precision offsets to a double precision center.
+#[derive(Debug, Clone)]
+pub struct RelativeToCenter {
+    center: DVec3,
+    offsets: Vec<Vec3>,
+}
+
+impl RelativeToCenter {
+    /// Converts the geographic coordinates into offsets to the given center.
+    pub fn new<S: SpatialReference + ?Sized>(
+        srs: &S,
+        center: DVec3,
+        points: &[Geographic],
+    ) -> Self {
+        let offsets = points
+            .iter()
+            .map(|p| srs.convert_relative(center, *p))
+            .collect();
+
+        RelativeToCenter { center, offsets }
+    }
+
+    /// Samples the bounds with a grid of `x_count` by `y_count` points, as in
+    /// `GeoBounds::grid`, using the center of the bounds surface as the origin.
+    pub fn from_grid<S: SpatialReference + ?Sized>(
+        srs: &S,
+        bounds: &GeoBounds,
+        x_count: usize,
+        y_count: usize,
+    ) -> Self {
+        let mut grid = vec![Geographic::default(); x_count * y_count];
+        bounds.grid(&mut grid, x_count, y_count);
+
+        let center = srs.convert_f64(bounds.center().flatten());
+
+        Self::new(srs, center, &grid)
+    }
+
+    /// Returns the double precision center of the positions.
+    pub fn center(&self) -> DVec3 {
+        self.center
+    }
+
+    /// Returns the single precision offsets to the center.
+    pub fn offsets(&self) -> &[Vec3] {
+        &self.offsets
+    }
+
+    /// Returns the absolute, double precision position at the given index.
+    pub fn position(&self, index: usize) -> DVec3 {
+        self.center + to_f64(self.offsets[index])
+    }
+}
+
+/// The serialized form of `RelativeToCenter`, whose vectors are `[x, y, z]` arrays.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "RelativeToCenter", deny_unknown_fields)]
+struct RelativeToCenterFields {
+    center: [f64; 3],
+    offsets: Vec<[f32; 3]>,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for RelativeToCenter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RelativeToCenterFields {
+            center: [self.center.x, self.center.y, self.center.z],
+            offsets: self.offsets.iter().map(|v| [v.x, v.y, v.z]).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes positions, whose center and offsets must be finite.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for RelativeToCenter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = RelativeToCenterFields::deserialize(deserializer)?;
+        let finite = f.center.iter().all(|v| v.is_finite())
+            && f.offsets.iter().flatten().all(|v| v.is_finite());
+        if !finite {
+            return Err(de::Error::custom("the center and offsets must be finite"));
+        }
+
+        Ok(RelativeToCenter {
+            center: DVec3::new(f.center[0], f.center[1], f.center[2]),
+            offsets: f
+                .offsets
+                .iter()
+                .map(|v| Vec3::new(v[0], v[1], v[2]))
+                .collect(),
+        })
+    }
+}
+
+/// A double precision position split into two single precision parts,
+/// such that `high + low` approximates the original position to a fraction
+/// of a millimeter. Shaders subtract the similarly encoded camera position
+/// from both parts separately before adding them.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EncodedPosition {
+    pub high: Vec3,
+    pub low: Vec3,
+}
+
+impl EncodedPosition {
+    /// Splits the double precision position into a high and a low part.
+    pub fn encode(v: DVec3) -> Self {
+        let high = to_f32(v);
+        let low = to_f32(v - to_f64(high));
+
+        EncodedPosition { high, low }
+    }
+
+    /// Returns the double precision position.
+    pub fn decode(&self) -> DVec3 {
+        to_f64(self.high) + to_f64(self.low)
+    }
+}
+
+/// Truncates a double precision vector into a single precision vector.
+pub fn to_f32(v: DVec3) -> Vec3 {
+    Vec3::new(v.x as f32, v.y as f32, v.z as f32)
+}
+
+/// Widens a single precision vector into a double precision vector.
+pub fn to_f64(v: Vec3) -> DVec3 {
+    DVec3::new(f64::from(v.x), f64::from(v.y), f64::from(v.z))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geobounds::GeoBounds;
+    use crate::geographic::*;
+    use crate::transformations::ecef::ECEF;
+    use crate::transformations::rtc::*;
+
+    /// The expected accuracy of the RTC positions, in meters.
+    const EPSILON: f64 = 0.01;
+
+    #[quickcheck]
+    fn encode_then_decode_returns_the_same_position(geo: Geographic) -> bool {
+        let v = ECEF::default().convert_f64(geo);
+
+        (EncodedPosition::encode(v).decode() - v).norm() < EPSILON
+    }
+
+    #[quickcheck]
+    fn relative_positions_are_accurate_near_the_center(geo: Geographic) -> bool {
+        let ecef = ECEF::default();
+        let bounds = GeoBounds::new(geo.flatten(), geo.flatten()).grow(0.01, 0.01);
+
+        let rtc = RelativeToCenter::from_grid(&ecef, &bounds, 4, 4);
+
+        let mut grid = vec![Geographic::default(); 16];
+        bounds.grid(&mut grid, 4, 4);
+
+        grid.iter()
+            .enumerate()
+            .all(|(i, p)| (rtc.position(i) - ecef.convert_f64(*p)).norm() < EPSILON)
+    }
+
+    #[test]
+    fn relative_positions_are_more_accurate_than_absolute_positions() {
+        let ecef = ECEF::default();
+        let geo = Geographic::new(48.858_370_1, 2.294_481_3, 0.0);
+        let exact = ecef.convert_f64(geo);
+
+        let absolute = to_f64(ecef.convert(geo));
+        let relative = RelativeToCenter::new(&ecef, exact + DVec3::new(10.0, 10.0, 10.0), &[geo]);
+
+        assert!((absolute - exact).norm() > 0.05);
+        assert!((relative.position(0) - exact).norm() < 0.001);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_is_the_inverse_of_serialize() {
+        let ecef = ECEF::default();
+        let bounds = GeoBounds::new(
+            Geographic::new(48.0, 2.0, 0.0),
+            Geographic::new(49.0, 3.0, 0.0),
+        );
+        let rtc = RelativeToCenter::from_grid(&ecef, &bounds, 3, 3);
+
+        let json = serde_json::to_string(&rtc).unwrap();
+        let result = serde_json::from_str::<RelativeToCenter>(&json).unwrap();
+
+        assert_eq!(result.center(), rtc.center());
+        assert_eq!(result.offsets(), rtc.offsets());
+        assert!(
+            serde_json::from_str::<RelativeToCenter>(r#"{"center": [0, 0], "offsets": []}"#)
+                .is_err()
+        );
+        assert!(serde_json::from_str::<RelativeToCenter>(
+            r#"{"center": [0, 0, 0], "offsets": [[1e39, 0, 0]]}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,326 @@
+use crate::angle::{Degrees, Radians};
+use crate::ellipsoid::Ellipsoid;
+use crate::geographic::*;
+use crate::transformations::{ProjectedBounds, Projection};
+use glm::DVec2;
+use std::f64::consts::FRAC_PI_2;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The order of the Krüger series.
+const ORDER: usize = 6;
+
+/// The largest distance from the central meridian, expressed as the `η` coordinate
+/// of the Gauss-Schreiber projection (about 3800 km on the Earth). Within this
+/// distance, the Krüger series are accurate to better than a millimeter.
+const MAX_ETA: f64 = 0.6;
+
+/// The ellipsoidal Transverse Mercator projection, computed with the 6th order
+/// Krüger series. This is the projection used by the UTM system (see the `utm` module).
+/// `X` is the easting and `Y` is the northing, both in meters.
+/// Accuracy is better than a millimeter within the extent of the projection, which
+/// lies about 3800 km on each side of the central meridian (see `MAX_ETA`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransverseMercator {
+    ellipsoid: Ellipsoid,
+    central_meridian: f64,
+    scale_factor: f64,
+    false_easting: f64,
+    false_northing: f64,
+    // The radius of the rectifying sphere, multiplied by the scale factor.
+    radius: f64,
+    alpha: [f64; ORDER],
+    beta: [f64; ORDER],
+}
+
+impl TransverseMercator {
+    /// Creates a projection on the ellipsoid, centered on the meridian (in degrees),
+    /// with the specified scale factor along this meridian and offsets of the origin.
+    pub fn new(
+        ellipsoid: Ellipsoid,
+        central_meridian: f64,
+        scale_factor: f64,
+        false_easting: f64,
+        false_northing: f64,
+    ) -> Self {
+        debug_assert!((MIN_LON..=MAX_LON).contains(&central_meridian));
+        debug_assert!(scale_factor > 0.0);
+
+        // Coefficients taken from the paper "Transverse Mercator with an
+        // accuracy of a few nanometers", by C. F. F. Karney (2011).
+        let n = ellipsoid.flattening() / (2.0 - ellipsoid.flattening());
+        let n2 = n * n;
+        let n3 = n2 * n;
+        let n4 = n3 * n;
+        let n5 = n4 * n;
+        let n6 = n5 * n;
+
+        let radius = scale_factor * ellipsoid.semi_major_axis() / (1.0 + n)
+            * (1.0 + n2 / 4.0 + n4 / 64.0 + n6 / 256.0);
+
+        let alpha = [
+            n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0 + 41.0 * n4 / 180.0 - 127.0 * n5 / 288.0
+                + 7891.0 * n6 / 37800.0,
+            13.0 * n2 / 48.0 - 3.0 * n3 / 5.0 + 557.0 * n4 / 1440.0 + 281.0 * n5 / 630.0
+                - 1_983_433.0 * n6 / 1_935_360.0,
+            61.0 * n3 / 240.0 - 103.0 * n4 / 140.0
+                + 15061.0 * n5 / 26880.0
+                + 167_603.0 * n6 / 181_440.0,
+            49561.0 * n4 / 161_280.0 - 179.0 * n5 / 168.0 + 6_601_661.0 * n6 / 7_257_600.0,
+            34729.0 * n5 / 80640.0 - 3_418_889.0 * n6 / 1_995_840.0,
+            212_378_941.0 * n6 / 319_334_400.0,
+        ];
+
+        let beta = [
+            n / 2.0 - 2.0 * n2 / 3.0 + 37.0 * n3 / 96.0 - n4 / 360.0 - 81.0 * n5 / 512.0
+                + 96199.0 * n6 / 604_800.0,
+            n2 / 48.0 + n3 / 15.0 - 437.0 * n4 / 1440.0 + 46.0 * n5 / 105.0
+                - 1_118_711.0 * n6 / 3_870_720.0,
+            17.0 * n3 / 480.0 - 37.0 * n4 / 840.0 - 209.0 * n5 / 4480.0 + 5569.0 * n6 / 90720.0,
+            4397.0 * n4 / 161_280.0 - 11.0 * n5 / 504.0 - 830_251.0 * n6 / 7_257_600.0,
+            4583.0 * n5 / 161_280.0 - 108_847.0 * n6 / 3_991_680.0,
+            20_648_693.0 * n6 / 638_668_800.0,
+        ];
+
+        TransverseMercator {
+            ellipsoid,
+            central_meridian,
+            scale_factor,
+            false_easting,
+            false_northing,
+            radius,
+            alpha,
+            beta,
+        }
+    }
+
+    /// Returns the ellipsoid of the projection.
+    pub fn ellipsoid(&self) -> &Ellipsoid {
+        &self.ellipsoid
+    }
+
+    /// Returns the longitude of the central meridian, in degrees.
+    pub fn central_meridian(&self) -> f64 {
+        self.central_meridian
+    }
+
+    /// Returns the scale factor along the central meridian.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Returns the easting of the central meridian, in meters.
+    pub fn false_easting(&self) -> f64 {
+        self.false_easting
+    }
+
+    /// Returns the northing of the equator, in meters.
+    pub fn false_northing(&self) -> f64 {
+        self.false_northing
+    }
+}
+
+/// The serialized form of `TransverseMercator`, without the coefficients of the series,
+/// which are computed again when deserializing.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "TransverseMercator", deny_unknown_fields)]
+struct Parameters {
+    ellipsoid: Ellipsoid,
+    central_meridian: f64,
+    scale_factor: f64,
+    false_easting: f64,
+    false_northing: f64,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for TransverseMercator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Parameters {
+            ellipsoid: self.ellipsoid,
+            central_meridian: self.central_meridian,
+            scale_factor: self.scale_factor,
+            false_easting: self.false_easting,
+            false_northing: self.false_northing,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes a projection, whose central meridian must be a valid longitude,
+/// and whose scale factor must be positive.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for TransverseMercator {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let p = Parameters::deserialize(deserializer)?;
+        let central_meridian = check_range("central meridian", p.central_meridian, MIN_LON, MAX_LON)?;
+        if !(p.scale_factor > 0.0 && p.scale_factor.is_finite()) {
+            return Err(de::Error::custom("the scale factor must be positive"));
+        }
+        if !(p.false_easting.is_finite() && p.false_northing.is_finite()) {
+            return Err(de::Error::custom("the false origin must be finite"));
+        }
+
+        Ok(TransverseMercator::new(
+            p.ellipsoid,
+            central_meridian,
+            p.scale_factor,
+            p.false_easting,
+            p.false_northing,
+        ))
+    }
+}
+
+impl Projection for TransverseMercator {
+    fn project(&self, geo: Geographic) -> DVec2 {
+        let lon = Degrees(geo.lon() - self.central_meridian)
+            .to_radians()
+            .normalize();
+        let chi = self.ellipsoid.conformal_latitude(geo.lat_rad());
+
+        // The Gauss-Schreiber projection of the conformal sphere.
+        let (sin_chi, cos_chi) = chi.sin_cos();
+        let (sin_lon, cos_lon) = lon.sin_cos();
+        let xi_prime = sin_chi.atan2(cos_chi * cos_lon);
+        let eta_prime = (cos_chi * sin_lon).atanh();
+
+        let mut xi = xi_prime;
+        let mut eta = eta_prime;
+        for (j, a) in self.alpha.iter().enumerate() {
+            let k = 2.0 * (j + 1) as f64;
+            xi += a * (k * xi_prime).sin() * (k * eta_prime).cosh();
+            eta += a * (k * xi_prime).cos() * (k * eta_prime).sinh();
+        }
+
+        DVec2::new(
+            self.false_easting + self.radius * eta,
+            self.false_northing + self.radius * xi,
+        )
+    }
+
+    fn unproject(&self, p: DVec2) -> Geographic {
+        let xi = (p.y - self.false_northing) / self.radius;
+        let eta = (p.x - self.false_easting) / self.radius;
+
+        let mut xi_prime = xi;
+        let mut eta_prime = eta;
+        for (j, b) in self.beta.iter().enumerate() {
+            let k = 2.0 * (j + 1) as f64;
+            xi_prime -= b * (k * xi).sin() * (k * eta).cosh();
+            eta_prime -= b * (k * xi).cos() * (k * eta).sinh();
+        }
+
+        let xi_prime = num::clamp(xi_prime, -FRAC_PI_2, FRAC_PI_2);
+        let sinh_eta = eta_prime.sinh();
+        let (sin_xi, cos_xi) = xi_prime.sin_cos();
+
+        let chi = Radians::atan2(sin_xi, sinh_eta.hypot(cos_xi));
+        let lon = Radians::atan2(sinh_eta, cos_xi).to_degrees().value() + self.central_meridian;
+        let lat = self.ellipsoid.geodetic_latitude(chi);
+
+        Geographic::from_radians(lat, Degrees(lon).to_radians().normalize(), 0.0)
+    }
+
+    fn extent(&self) -> ProjectedBounds {
+        let half_width = self.radius * MAX_ETA;
+        let half_height = self.radius * FRAC_PI_2;
+
+        ProjectedBounds::new(
+            DVec2::new(
+                self.false_easting - half_width,
+                self.false_northing - half_height,
+            ),
+            DVec2::new(
+                self.false_easting + half_width,
+                self.false_northing + half_height,
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ellipsoid::Ellipsoid;
+    use crate::geographic::*;
+    use crate::transformations::transversemercator::*;
+
+    fn utm_north(zone: f64) -> TransverseMercator {
+        TransverseMercator::new(
+            Ellipsoid::wgs84(),
+            zone * 6.0 - 183.0,
+            0.9996,
+            500_000.0,
+            0.0,
+        )
+    }
+
+    #[test]
+    fn project_returns_correct_values_for_reference_points() {
+        // Reference values computed with GeographicLib.
+        let baghdad = utm_north(38.0).project(Geographic::new(33.3, 44.4, 0.0));
+        assert_abs_diff_eq!(baghdad.x, 444_140.545, epsilon = 0.001);
+        assert_abs_diff_eq!(baghdad.y, 3_684_706.356, epsilon = 0.001);
+
+        let new_york = utm_north(18.0).project(Geographic::new(40.7128, -74.006, 0.0));
+        assert_abs_diff_eq!(new_york.x, 583_959.372, epsilon = 0.001);
+        assert_abs_diff_eq!(new_york.y, 4_507_350.998, epsilon = 0.001);
+
+        let north = utm_north(31.0).project(Geographic::new(83.9, 3.0, 0.0));
+        assert_abs_diff_eq!(north.x, 500_000.0, epsilon = 0.001);
+        assert_abs_diff_eq!(north.y, 9_316_930.154, epsilon = 0.001);
+    }
+
+    #[test]
+    fn poles_project_on_the_central_meridian() {
+        let tm = utm_north(31.0);
+
+        let north = tm.project(Geographic::new(MAX_LAT, 50.0, 0.0));
+        let south = tm.project(Geographic::new(MIN_LAT, 50.0, 0.0));
+
+        assert_abs_diff_eq!(north.x, 500_000.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(north.y, -south.y, epsilon = 1e-6);
+        assert_abs_diff_eq!(tm.unproject(north).lat(), MAX_LAT, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn unproject_bounds_across_the_antimeridian_returns_crossing_bounds() {
+        let tm = TransverseMercator::new(Ellipsoid::wgs84(), 180.0, 1.0, 0.0, 0.0);
+        let extent =
+            ProjectedBounds::new(DVec2::new(-50_000.0, 0.0), DVec2::new(50_000.0, 100_000.0));
+
+        let b = tm.unproject_bounds(&extent);
+
+        assert!(b.crosses_antimeridian());
+        assert!(b.west() > 179.0 && b.east() < -179.0);
+        assert_abs_diff_eq!(b.span_lon(), 0.9, epsilon = 0.01);
+    }
+
+    #[quickcheck]
+    fn unproject_is_the_inverse_of_project(geo: Geographic, offset: i8) -> bool {
+        // Stay within 20° of the central meridian.
+        let lon = geo.lon() + f64::from(offset) / 128.0 * 20.0;
+        let tm = TransverseMercator::new(Ellipsoid::wgs84(), geo.lon(), 0.9996, 500_000.0, 0.0);
+        let geo = Geographic::new(geo.lat(), num::clamp(lon, MIN_LON, MAX_LON), 0.0);
+
+        let result = tm.unproject(tm.project(geo));
+
+        // The antimeridian may be returned as either -180° or 180°.
+        let lon_error = (result.lon() - geo.lon()).abs() % 360.0;
+
+        abs_diff_eq!(result.lat(), geo.lat(), epsilon = 1e-9)
+            && (lon_error.min(360.0 - lon_error) < 1e-9 || geo.lat().abs() > 89.999)
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_computes_the_series_again() {
+        let tm = utm_north(31.0);
+        let json = serde_json::to_string(&tm).unwrap();
+        let invalid = json.replace("0.9996", "-1.0");
+
+        assert!(!json.contains("alpha"));
+        assert_eq!(serde_json::from_str::<TransverseMercator>(&json).unwrap(), tm);
+        assert!(serde_json::from_str::<TransverseMercator>(&invalid).is_err());
+    }
+}
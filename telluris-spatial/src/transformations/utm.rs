@@ -0,0 +1,398 @@
+//! The Universal Transverse Mercator (UTM) and Universal Polar Stereographic (UPS)
+//! coordinate systems, on the WGS 84 ellipsoid. Together, they cover the whole globe:
+//! UTM is used between 80°S and 84°N, and UPS is used around the poles.
+
+use crate::ellipsoid::Ellipsoid;
+use crate::geographic::*;
+use crate::transformations::polarstereographic::PolarStereographic;
+use crate::transformations::transversemercator::TransverseMercator;
+use crate::transformations::{ProjectedBounds, Projection};
+use glm::DVec2;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// The southernmost latitude of the UTM system. Latitudes below are covered by UPS.
+pub const UTM_MIN_LAT: f64 = -80.0;
+
+/// The northernmost latitude (excluded) of the UTM system. Latitudes above are covered by UPS.
+pub const UTM_MAX_LAT: f64 = 84.0;
+
+/// The number of UTM zones, each spanning 6° of longitude.
+pub const UTM_ZONE_COUNT: u8 = 60;
+
+/// The scale factor of UTM zones along their central meridian.
+pub const UTM_SCALE_FACTOR: f64 = 0.9996;
+
+/// The easting of the central meridian of UTM zones, in meters.
+pub const UTM_FALSE_EASTING: f64 = 500_000.0;
+
+/// The northing of the equator in the southern hemisphere, in meters.
+pub const UTM_FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+/// The scale factor of UPS zones at the pole.
+pub const UPS_SCALE_FACTOR: f64 = 0.994;
+
+/// The easting and northing of the pole in UPS zones, in meters.
+pub const UPS_FALSE_ORIGIN: f64 = 2_000_000.0;
+
+/// A zone of the UTM/UPS system.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Zone {
+    /// A UTM zone, numbered from 1 to 60 eastward from the antimeridian,
+    /// in the northern or southern hemisphere.
+    Utm(u8, Hemisphere),
+    /// The UPS zone of the northern or southern pole.
+    Ups(Hemisphere),
+}
+
+impl Zone {
+    /// Creates the UTM zone with the specified number, from 1 to 60.
+    pub fn utm(number: u8, hemisphere: Hemisphere) -> Self {
+        assert!(
+            (1..=UTM_ZONE_COUNT).contains(&number),
+            "invalid UTM zone: {}",
+            number
+        );
+
+        Zone::Utm(number, hemisphere)
+    }
+
+    /// Returns the standard zone of the coordinate, including the exceptions of
+    /// southwestern Norway (zone 32V is widened to the west) and of Svalbard (zones
+    /// 32X, 34X and 36X do not exist, and the neighbouring zones are widened).
+    pub fn from_geographic(geo: Geographic) -> Self {
+        let lat = geo.lat();
+        let hemisphere = geo.hemisphere();
+
+        if !(UTM_MIN_LAT..UTM_MAX_LAT).contains(&lat) {
+            return Zone::Ups(hemisphere);
+        }
+
+        // The antimeridian belongs to zone 1.
+        let lon = if geo.lon() >= MAX_LON {
+            geo.lon() - 360.0
+        } else {
+            geo.lon()
+        };
+
+        let mut number = ((lon - MIN_LON) / 6.0).floor() as u8 + 1;
+
+        if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+            number = 32;
+        } else if lat >= 72.0 && (0.0..42.0).contains(&lon) {
+            number = if lon < 9.0 {
+                31
+            } else if lon < 21.0 {
+                33
+            } else if lon < 33.0 {
+                35
+            } else {
+                37
+            };
+        }
+
+        Zone::Utm(number, hemisphere)
+    }
+
+    /// Returns the hemisphere of the zone.
+    pub fn hemisphere(&self) -> Hemisphere {
+        match self {
+            Zone::Utm(_, h) | Zone::Ups(h) => *h,
+        }
+    }
+
+    /// Returns the number of the zone, or `None` for UPS zones.
+    pub fn number(&self) -> Option<u8> {
+        match self {
+            Zone::Utm(n, _) => Some(*n),
+            Zone::Ups(_) => None,
+        }
+    }
+
+    /// Returns the longitude of the central meridian of the zone, in degrees,
+    /// or `None` for UPS zones.
+    pub fn central_meridian(&self) -> Option<f64> {
+        self.number().map(|n| f64::from(n) * 6.0 - 183.0)
+    }
+
+    fn transverse_mercator(number: u8, hemisphere: Hemisphere) -> TransverseMercator {
+        let false_northing = match hemisphere {
+            Hemisphere::North => 0.0,
+            Hemisphere::South => UTM_FALSE_NORTHING_SOUTH,
+        };
+
+        TransverseMercator::new(
+            Ellipsoid::wgs84(),
+            f64::from(number) * 6.0 - 183.0,
+            UTM_SCALE_FACTOR,
+            UTM_FALSE_EASTING,
+            false_northing,
+        )
+    }
+
+    fn polar_stereographic(hemisphere: Hemisphere) -> PolarStereographic {
+        PolarStereographic::new(
+            Ellipsoid::wgs84(),
+            hemisphere,
+            UPS_SCALE_FACTOR,
+            UPS_FALSE_ORIGIN,
+            UPS_FALSE_ORIGIN,
+        )
+    }
+}
+
+impl Projection for Zone {
+    fn project(&self, geo: Geographic) -> DVec2 {
+        match *self {
+            Zone::Utm(n, h) => Self::transverse_mercator(n, h).project(geo),
+            Zone::Ups(h) => Self::polar_stereographic(h).project(geo),
+        }
+    }
+
+    fn unproject(&self, p: DVec2) -> Geographic {
+        match *self {
+            Zone::Utm(n, h) => Self::transverse_mercator(n, h).unproject(p),
+            Zone::Ups(h) => Self::polar_stereographic(h).unproject(p),
+        }
+    }
+
+    /// Returns the domain of coordinates that are accepted in the zone,
+    /// including the overlap with the neighbouring zones.
+    fn extent(&self) -> ProjectedBounds {
+        match self {
+            Zone::Utm(_, Hemisphere::North) => {
+                ProjectedBounds::new(DVec2::new(0.0, 0.0), DVec2::new(1_000_000.0, 9_600_000.0))
+            }
+            Zone::Utm(_, Hemisphere::South) => ProjectedBounds::new(
+                DVec2::new(0.0, 900_000.0),
+                DVec2::new(1_000_000.0, 10_000_000.0),
+            ),
+            Zone::Ups(_) => {
+                ProjectedBounds::new(DVec2::new(0.0, 0.0), DVec2::new(4_000_000.0, 4_000_000.0))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Zone {
+    /// Formats the zone as its number followed by the hemisphere, such as `31N`,
+    /// or as `UPS` followed by the hemisphere.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let h = match self.hemisphere() {
+            Hemisphere::North => 'N',
+            Hemisphere::South => 'S',
+        };
+
+        match self {
+            Zone::Utm(n, _) => write!(f, "{}{}", n, h),
+            Zone::Ups(_) => write!(f, "UPS{}", h),
+        }
+    }
+}
+
+/// Deserializes a zone, whose UTM number must be between 1 and 60.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Zone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Zone")]
+        enum Variants {
+            Utm(u8, Hemisphere),
+            Ups(Hemisphere),
+        }
+
+        match Variants::deserialize(deserializer)? {
+            Variants::Utm(n, _) if !(1..=UTM_ZONE_COUNT).contains(&n) => {
+                Err(de::Error::custom(format_args!("invalid UTM zone: {}", n)))
+            }
+            Variants::Utm(n, h) => Ok(Zone::Utm(n, h)),
+            Variants::Ups(h) => Ok(Zone::Ups(h)),
+        }
+    }
+}
+
+/// A position expressed in the UTM/UPS system, as an easting and northing
+/// in meters, in a zone.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UtmCoordinate {
+    zone: Zone,
+    easting: f64,
+    northing: f64,
+}
+
+impl UtmCoordinate {
+    /// Creates a coordinate with the specified easting and northing, in meters.
+    pub fn new(zone: Zone, easting: f64, northing: f64) -> Self {
+        UtmCoordinate {
+            zone,
+            easting,
+            northing,
+        }
+    }
+
+    /// Converts geographic coordinates into the standard zone of the coordinate.
+    /// Elevations are ignored.
+    pub fn from_geographic(geo: Geographic) -> Self {
+        Self::from_geographic_in_zone(geo, Zone::from_geographic(geo))
+    }
+
+    /// Converts geographic coordinates into the specified zone, which may not be
+    /// the standard zone of the coordinate, for example to express positions
+    /// that straddle a zone boundary in a single zone. Elevations are ignored.
+    pub fn from_geographic_in_zone(geo: Geographic, zone: Zone) -> Self {
+        let p = zone.project(geo);
+
+        Self::new(zone, p.x, p.y)
+    }
+
+    /// Returns the zone of the coordinate.
+    pub fn zone(&self) -> Zone {
+        self.zone
+    }
+
+    /// Returns the easting, in meters.
+    pub fn easting(&self) -> f64 {
+        self.easting
+    }
+
+    /// Returns the northing, in meters.
+    pub fn northing(&self) -> f64 {
+        self.northing
+    }
+
+    /// Converts the coordinate back into geographic coordinates, at zero elevation.
+    pub fn to_geographic(&self) -> Geographic {
+        self.zone.unproject(DVec2::new(self.easting, self.northing))
+    }
+}
+
+impl fmt::Display for UtmCoordinate {
+    /// Formats the coordinate as its zone, easting and northing, such as
+    /// `38N 444140.5 3684706.4`. The precision of the formatter applies to the meters.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(
+                f,
+                "{} {:.*} {:.*}",
+                self.zone, p, self.easting, p, self.northing
+            ),
+            None => write!(f, "{} {} {}", self.zone, self.easting, self.northing),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geographic::*;
+    use crate::transformations::utm::*;
+
+    fn zone_of(lat: f64, lon: f64) -> Zone {
+        Zone::from_geographic(Geographic::new(lat, lon, 0.0))
+    }
+
+    #[test]
+    fn from_geographic_returns_standard_zones() {
+        assert_eq!(zone_of(0.0, 0.0), Zone::Utm(31, Hemisphere::North));
+        assert_eq!(zone_of(-0.1, 0.0), Zone::Utm(31, Hemisphere::South));
+        assert_eq!(zone_of(45.0, -179.9), Zone::Utm(1, Hemisphere::North));
+        assert_eq!(zone_of(45.0, 179.9), Zone::Utm(60, Hemisphere::North));
+        assert_eq!(zone_of(45.0, MAX_LON), Zone::Utm(1, Hemisphere::North));
+        assert_eq!(zone_of(33.3, 44.4), Zone::Utm(38, Hemisphere::North));
+        assert_eq!(
+            zone_of(-33.8568, 151.2153),
+            Zone::Utm(56, Hemisphere::South)
+        );
+    }
+
+    #[test]
+    fn from_geographic_returns_ups_zones_around_the_poles() {
+        assert_eq!(zone_of(UTM_MAX_LAT, -10.0), Zone::Ups(Hemisphere::North));
+        assert_eq!(zone_of(83.99, -10.0), Zone::Utm(29, Hemisphere::North));
+        assert_eq!(zone_of(UTM_MIN_LAT, 10.0), Zone::Utm(32, Hemisphere::South));
+        assert_eq!(zone_of(-80.01, 10.0), Zone::Ups(Hemisphere::South));
+    }
+
+    #[test]
+    fn from_geographic_handles_the_norway_exception() {
+        assert_eq!(zone_of(60.0, 2.9), Zone::Utm(31, Hemisphere::North));
+        assert_eq!(zone_of(60.0, 3.0), Zone::Utm(32, Hemisphere::North));
+        assert_eq!(zone_of(60.0, 11.9), Zone::Utm(32, Hemisphere::North));
+        assert_eq!(zone_of(60.0, 12.0), Zone::Utm(33, Hemisphere::North));
+        assert_eq!(zone_of(55.9, 5.0), Zone::Utm(31, Hemisphere::North));
+        assert_eq!(zone_of(64.0, 5.0), Zone::Utm(31, Hemisphere::North));
+    }
+
+    #[test]
+    fn from_geographic_handles_the_svalbard_exception() {
+        assert_eq!(zone_of(78.0, 8.9), Zone::Utm(31, Hemisphere::North));
+        assert_eq!(zone_of(78.0, 9.0), Zone::Utm(33, Hemisphere::North));
+        assert_eq!(zone_of(78.0, 20.9), Zone::Utm(33, Hemisphere::North));
+        assert_eq!(zone_of(78.0, 21.0), Zone::Utm(35, Hemisphere::North));
+        assert_eq!(zone_of(78.0, 33.0), Zone::Utm(37, Hemisphere::North));
+        assert_eq!(zone_of(78.0, 41.9), Zone::Utm(37, Hemisphere::North));
+        assert_eq!(zone_of(78.0, 42.0), Zone::Utm(38, Hemisphere::North));
+        assert_eq!(zone_of(71.9, 20.0), Zone::Utm(34, Hemisphere::North));
+    }
+
+    #[test]
+    fn from_geographic_returns_correct_values_for_reference_points() {
+        // Reference values computed with GeographicLib and PROJ.
+        let baghdad = UtmCoordinate::from_geographic(Geographic::new(33.3, 44.4, 0.0));
+        assert_eq!(format!("{:.1}", baghdad), "38N 444140.5 3684706.4");
+
+        let sydney = UtmCoordinate::from_geographic(Geographic::new(-33.8568, 151.2153, 0.0));
+        assert_eq!(sydney.zone(), Zone::Utm(56, Hemisphere::South));
+        assert_abs_diff_eq!(sydney.easting(), 334_900.570, epsilon = 0.001);
+        assert_abs_diff_eq!(sydney.northing(), 6_252_288.753, epsilon = 0.001);
+
+        let bergen = UtmCoordinate::from_geographic(Geographic::new(60.0, 5.0, 0.0));
+        assert_eq!(bergen.zone(), Zone::Utm(32, Hemisphere::North));
+        assert_abs_diff_eq!(bergen.easting(), 276_979.926, epsilon = 0.001);
+        assert_abs_diff_eq!(bergen.northing(), 6_658_157.202, epsilon = 0.001);
+
+        let longyearbyen = UtmCoordinate::from_geographic(Geographic::new(78.0, 16.0, 0.0));
+        assert_eq!(longyearbyen.zone(), Zone::Utm(33, Hemisphere::North));
+        assert_abs_diff_eq!(longyearbyen.easting(), 523_208.738, epsilon = 0.001);
+        assert_abs_diff_eq!(longyearbyen.northing(), 8_658_567.700, epsilon = 0.001);
+
+        let south_pole = UtmCoordinate::from_geographic(Geographic::new(-85.0, 45.0, 0.0));
+        assert_eq!(format!("{:.3}", south_pole), "UPSS 2392767.688 2392767.688");
+    }
+
+    #[quickcheck]
+    fn to_geographic_is_the_inverse_of_from_geographic(geo: Geographic) -> bool {
+        let geo = geo.flatten();
+        let utm = UtmCoordinate::from_geographic(geo);
+        let result = utm.to_geographic();
+
+        abs_diff_eq!(result.lat(), geo.lat(), epsilon = 1e-9)
+            && (abs_diff_eq!(result.lon(), geo.lon(), epsilon = 1e-9) || geo.lat().abs() > 89.999)
+    }
+
+    #[quickcheck]
+    fn standard_zones_contain_their_coordinates(geo: Geographic) -> bool {
+        let utm = UtmCoordinate::from_geographic(geo);
+
+        utm.zone()
+            .extent()
+            .contains(DVec2::new(utm.easting(), utm.northing()))
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_validates_zones() {
+        let zone = |s| serde_json::from_str::<Zone>(s);
+        let utm = UtmCoordinate::from_geographic(Geographic::new(48.8583, 2.2945, 0.0));
+        let json = serde_json::to_string(&utm).unwrap();
+
+        assert_eq!(zone(r#"{"Utm": [31, "North"]}"#).unwrap(), Zone::utm(31, Hemisphere::North));
+        assert_eq!(zone(r#"{"Ups": "South"}"#).unwrap(), Zone::Ups(Hemisphere::South));
+        assert!(zone(r#"{"Utm": [61, "North"]}"#).is_err());
+        assert!(zone(r#"{"Utm": [0, "South"]}"#).is_err());
+        assert_eq!(serde_json::from_str::<UtmCoordinate>(&json).unwrap(), utm);
+    }
+}
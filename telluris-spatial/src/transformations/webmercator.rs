@@ -0,0 +1,178 @@
+use crate::angle::{Degrees, Radians};
+use crate::ellipsoid::WGS84_SEMI_MAJOR_AXIS;
+use crate::geobounds::GeoBounds;
+use crate::geographic::*;
+use crate::transformations::{ProjectedBounds, Projection};
+use glm::DVec2;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The northernmost latitude of the Web Mercator projection, such that the
+/// projected world is a square. Latitudes beyond this value are clamped.
+pub const WEB_MERCATOR_MAX_LAT: f64 = 85.051_128_779_806_59;
+
+/// The southernmost latitude of the Web Mercator projection.
+pub const WEB_MERCATOR_MIN_LAT: f64 = -WEB_MERCATOR_MAX_LAT;
+
+/// Half the width (and height) of the projected world, in meters.
+pub const WEB_MERCATOR_HALF_EXTENT: f64 = PI * WGS84_SEMI_MAJOR_AXIS;
+
+/// The spherical Mercator projection used by most web map tile services
+/// (EPSG:3857). Geographic coordinates are interpreted as WGS 84 coordinates
+/// projected on a sphere whose radius is the WGS 84 semi major axis.
+/// `X` points east and `Y` points north, with the origin at (0°N, 0°E).
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WebMercator {}
+
+impl WebMercator {
+    /// Returns the bounds of the world that can be projected.
+    pub fn geo_bounds() -> GeoBounds {
+        GeoBounds::new(
+            Geographic::new(WEB_MERCATOR_MIN_LAT, MIN_LON, 0.0),
+            Geographic::new(WEB_MERCATOR_MAX_LAT, MAX_LON, 0.0),
+        )
+    }
+}
+
+impl Projection for WebMercator {
+    fn project(&self, geo: Geographic) -> DVec2 {
+        let lat = Degrees(num::clamp(
+            geo.lat(),
+            WEB_MERCATOR_MIN_LAT,
+            WEB_MERCATOR_MAX_LAT,
+        ));
+        let lat = lat.to_radians().value();
+
+        let x = WGS84_SEMI_MAJOR_AXIS * geo.lon_rad().value();
+        let y = WGS84_SEMI_MAJOR_AXIS * (FRAC_PI_4 + lat / 2.0).tan().ln();
+
+        DVec2::new(x, y)
+    }
+
+    fn unproject(&self, p: DVec2) -> Geographic {
+        let x = num::clamp(p.x, -WEB_MERCATOR_HALF_EXTENT, WEB_MERCATOR_HALF_EXTENT);
+        let y = num::clamp(p.y, -WEB_MERCATOR_HALF_EXTENT, WEB_MERCATOR_HALF_EXTENT);
+
+        let lon = Radians(x / WGS84_SEMI_MAJOR_AXIS);
+        let lat = Radians(2.0 * (y / WGS84_SEMI_MAJOR_AXIS).exp().atan() - FRAC_PI_2);
+
+        Geographic::from_radians(lat, lon, 0.0)
+    }
+
+    fn extent(&self) -> ProjectedBounds {
+        ProjectedBounds::new(
+            DVec2::new(-WEB_MERCATOR_HALF_EXTENT, -WEB_MERCATOR_HALF_EXTENT),
+            DVec2::new(WEB_MERCATOR_HALF_EXTENT, WEB_MERCATOR_HALF_EXTENT),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geobounds::GeoBounds;
+    use crate::geographic::*;
+    use crate::transformations::webmercator::*;
+
+    #[test]
+    fn project_returns_correct_values_for_reference_points() {
+        let m = WebMercator {};
+
+        let origin = m.project(Geographic::new(0.0, 0.0, 0.0));
+        assert_abs_diff_eq!(origin.x, 0.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(origin.y, 0.0, epsilon = 1e-6);
+
+        let london = m.project(Geographic::new(51.5074, -0.1278, 0.0));
+        assert_abs_diff_eq!(london.x, -14_226.631, epsilon = 0.001);
+        assert_abs_diff_eq!(london.y, 6_711_542.476, epsilon = 0.001);
+
+        let sydney = m.project(Geographic::new(-33.8568, 151.2153, 0.0));
+        assert_abs_diff_eq!(sydney.x, 16_833_210.196, epsilon = 0.001);
+        assert_abs_diff_eq!(sydney.y, -4_009_589.934, epsilon = 0.001);
+    }
+
+    #[test]
+    fn corners_of_the_world_project_to_the_extent() {
+        let m = WebMercator {};
+
+        let ne = m.project(Geographic::new(WEB_MERCATOR_MAX_LAT, MAX_LON, 0.0));
+        assert_abs_diff_eq!(ne.x, WEB_MERCATOR_HALF_EXTENT, epsilon = 0.001);
+        assert_abs_diff_eq!(ne.y, WEB_MERCATOR_HALF_EXTENT, epsilon = 0.001);
+
+        let sw = m.project(Geographic::new(WEB_MERCATOR_MIN_LAT, MIN_LON, 0.0));
+        assert_abs_diff_eq!(sw.x, -WEB_MERCATOR_HALF_EXTENT, epsilon = 0.001);
+        assert_abs_diff_eq!(sw.y, -WEB_MERCATOR_HALF_EXTENT, epsilon = 0.001);
+    }
+
+    #[test]
+    fn latitudes_beyond_the_limit_are_clamped() {
+        let m = WebMercator {};
+
+        let north = m.project(Geographic::new(MAX_LAT, 0.0, 0.0));
+        let south = m.project(Geographic::new(MIN_LAT, 0.0, 0.0));
+
+        assert_abs_diff_eq!(north.y, WEB_MERCATOR_HALF_EXTENT, epsilon = 0.001);
+        assert_abs_diff_eq!(south.y, -WEB_MERCATOR_HALF_EXTENT, epsilon = 0.001);
+    }
+
+    #[quickcheck]
+    fn unproject_is_the_inverse_of_project(geo: Geographic) -> bool {
+        let m = WebMercator {};
+        let lat = num::clamp(geo.lat(), WEB_MERCATOR_MIN_LAT, WEB_MERCATOR_MAX_LAT);
+        let result = m.unproject(m.project(geo));
+
+        abs_diff_eq!(result, Geographic::new(lat, geo.lon(), 0.0), epsilon = 1e-9)
+    }
+
+    #[quickcheck]
+    fn projected_points_lie_in_the_extent(geo: Geographic) -> bool {
+        let m = WebMercator {};
+
+        let p = m.project(geo);
+
+        // Allow for rounding errors on the edges of the extent.
+        p.x.abs() <= WEB_MERCATOR_HALF_EXTENT + 1e-6 && p.y.abs() <= WEB_MERCATOR_HALF_EXTENT + 1e-6
+    }
+
+    #[quickcheck]
+    fn unproject_bounds_is_the_inverse_of_project_bounds(b: GeoBounds) -> bool {
+        let m = WebMercator {};
+        let clamp = |lat| num::clamp(lat, WEB_MERCATOR_MIN_LAT, WEB_MERCATOR_MAX_LAT);
+        let b = GeoBounds::new(
+            Geographic::new(clamp(b.south()), b.west(), 0.0),
+            Geographic::new(clamp(b.north()), b.east(), 0.0),
+        );
+
+        let result = m.unproject_bounds(&m.project_bounds(&b));
+
+        abs_diff_eq!(result, b, epsilon = 1e-9)
+    }
+
+    #[test]
+    fn project_bounds_across_the_antimeridian_spans_both_edges() {
+        let m = WebMercator {};
+        let b = GeoBounds::new(
+            Geographic::new(-10.0, 170.0, 0.0),
+            Geographic::new(10.0, -170.0, 0.0),
+        );
+
+        let extent = m.project_bounds(&b);
+        let y = |lat| m.project(Geographic::new(lat, 0.0, 0.0)).y;
+
+        assert_abs_diff_eq!(extent.min().x, -WEB_MERCATOR_HALF_EXTENT, epsilon = 1e-6);
+        assert_abs_diff_eq!(extent.max().x, WEB_MERCATOR_HALF_EXTENT, epsilon = 1e-6);
+        assert_abs_diff_eq!(extent.min().y, y(-10.0), epsilon = 1e-6);
+        assert_abs_diff_eq!(extent.max().y, y(10.0), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn world_projects_to_the_extent() {
+        let m = WebMercator {};
+        let extent = m.project_bounds(&WebMercator::geo_bounds());
+
+        assert_abs_diff_eq!(extent.width(), m.extent().width(), epsilon = 0.001);
+        assert_abs_diff_eq!(extent.height(), m.extent().height(), epsilon = 0.001);
+    }
+}
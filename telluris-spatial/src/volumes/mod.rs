@@ -0,0 +1,65 @@
+//! Cartesian bounding volumes, used to cull and select tiles.
+//! Volumes are expressed in double precision, in the frame of a `SpatialReference`.
+
+use crate::angle::Degrees;
+use crate::geobounds::GeoBounds;
+use crate::geographic::Geographic;
+use crate::transformations::SpatialReference;
+use glm::DVec3;
+
+pub mod obb;
+pub mod sphere;
+
+/// The maximal angle between two adjacent samples of a `GeoBounds`, in degrees.
+const MAX_SAMPLE_SPACING: f64 = 5.0;
+
+/// Samples the bounds with a grid of points at both the floor and top altitudes.
+/// Returns the converted positions, and the maximal distance between the volume
+/// covered by the bounds and the convex hull of the positions, due to the curvature
+/// of the surface between samples.
+pub(crate) fn sample_geo_bounds<S: SpatialReference + ?Sized>(
+    srs: &S,
+    bounds: &GeoBounds,
+) -> (Vec<DVec3>, f64) {
+    let count = |span: f64| (span / MAX_SAMPLE_SPACING).ceil().max(2.0) as usize + 1;
+    let x_count = count(bounds.span_lon());
+    let y_count = count(bounds.span_lat());
+
+    let mut grid = vec![Geographic::default(); x_count * y_count];
+    bounds.grid(&mut grid, x_count, y_count);
+
+    let positions: Vec<DVec3> = grid
+        .iter()
+        .flat_map(|g| {
+            let floor = Geographic::new(g.lat(), g.lon(), bounds.floor());
+            let top = Geographic::new(g.lat(), g.lon(), bounds.top());
+            vec![srs.convert_f64(floor), srs.convert_f64(top)]
+        })
+        .collect();
+
+    // The sagitta of the diagonal of a grid cell, on a sphere whose radius is the
+    // farthest sample. The longitude spacing is measured at the equator, which overestimates it.
+    let d_lon = bounds.span_lon() / (x_count - 1) as f64;
+    let d_lat = bounds.span_lat() / (y_count - 1) as f64;
+    let half_diagonal = Degrees((d_lon * d_lon + d_lat * d_lat).sqrt() / 2.0).to_radians();
+    let radius = positions.iter().map(|p| p.norm()).fold(0.0, f64::max);
+    let margin = radius * (1.0 - half_diagonal.cos());
+
+    (positions, margin)
+}
+
+/// Returns the bounds with a random altitude range.
+#[cfg(test)]
+pub(crate) fn with_altitudes(b: GeoBounds) -> GeoBounds {
+    use crate::geographic::MIN_ALT;
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    let floor = rng.gen_range(MIN_ALT, 10_000.0);
+    let top = floor + rng.gen_range(0.0, 100_000.0);
+
+    GeoBounds::new(
+        Geographic::new(b.south(), b.west(), floor),
+        Geographic::new(b.north(), b.east(), top),
+    )
+}
@@ -0,0 +1,260 @@
+use crate::geobounds::GeoBounds;
+use crate::transformations::local::LocalFrame;
+use crate::transformations::SpatialReference;
+use crate::volumes::sample_geo_bounds;
+use crate::volumes::sphere::BoundingSphere;
+use glm::{DMat3, DMat4, DVec3};
+
+/// A box enclosing a set of positions, whose axes are not necessarily aligned
+/// with the axes of the frame. Boxes are much tighter than spheres around
+/// flat volumes, such as the tiles of a planetary surface.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrientedBoundingBox {
+    center: DVec3,
+    rotation: DMat3,
+    half_extents: DVec3,
+}
+
+impl OrientedBoundingBox {
+    /// Creates a box with the specified center, half extents, and rotation.
+    /// The columns of the rotation are the unit axes of the box.
+    pub fn new(center: DVec3, rotation: DMat3, half_extents: DVec3) -> Self {
+        debug_assert!(half_extents.x >= 0.0 && half_extents.y >= 0.0 && half_extents.z >= 0.0);
+
+        OrientedBoundingBox {
+            center,
+            rotation,
+            half_extents,
+        }
+    }
+
+    /// Returns the smallest box enclosing the points, whose axes are the columns of the rotation.
+    pub fn from_points(rotation: DMat3, points: &[DVec3]) -> Self {
+        assert!(!points.is_empty(), "cannot bound an empty set of points");
+
+        let inverse = rotation.transpose();
+        let first = inverse * points[0];
+        let (min, max) = points.iter().fold((first, first), |(min, max), p| {
+            let local = inverse * p;
+            (glm::min2(&min, &local), glm::max2(&max, &local))
+        });
+
+        OrientedBoundingBox {
+            center: rotation * ((min + max) / 2.0),
+            rotation,
+            half_extents: (max - min) / 2.0,
+        }
+    }
+
+    /// Returns a box enclosing the volume covered by the bounds, from their `floor`
+    /// to their `top` altitudes, in the frame of the spatial reference. The box
+    /// is aligned with the East-North-Up frame at the center of the bounds.
+    pub fn from_geo_bounds<S: SpatialReference + ?Sized>(srs: &S, bounds: &GeoBounds) -> Self {
+        let (positions, margin) = sample_geo_bounds(srs, bounds);
+        let frame = LocalFrame::enu(srs, bounds.center().flatten());
+        let obb = Self::from_points(frame.rotation(), &positions);
+
+        OrientedBoundingBox {
+            half_extents: obb.half_extents.add_scalar(margin),
+            ..obb
+        }
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> DVec3 {
+        self.center
+    }
+
+    /// Returns the rotation of the box, whose columns are the unit axes of the box.
+    pub fn rotation(&self) -> DMat3 {
+        self.rotation
+    }
+
+    /// Returns the half lengths of the box along each of its axes.
+    pub fn half_extents(&self) -> DVec3 {
+        self.half_extents
+    }
+
+    /// Returns the half axes of the box, as the columns of the matrix.
+    pub fn half_axes(&self) -> DMat3 {
+        self.rotation * DMat3::from_diagonal(&self.half_extents)
+    }
+
+    /// Returns the eight corners of the box.
+    pub fn corners(&self) -> [DVec3; 8] {
+        let axes = self.half_axes();
+        let (x, y, z) = (axes.column(0), axes.column(1), axes.column(2));
+        let c = self.center;
+
+        [
+            c - x - y - z,
+            c + x - y - z,
+            c - x + y - z,
+            c + x + y - z,
+            c - x - y + z,
+            c + x - y + z,
+            c - x + y + z,
+            c + x + y + z,
+        ]
+    }
+
+    /// Returns true if the point is inside the box, including its faces.
+    pub fn contains(&self, p: &DVec3) -> bool {
+        let local = self.rotation.transpose() * (p - self.center);
+
+        local.x.abs() <= self.half_extents.x
+            && local.y.abs() <= self.half_extents.y
+            && local.z.abs() <= self.half_extents.z
+    }
+
+    /// Returns the sphere that encloses the box.
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::new(self.center, self.half_extents.norm())
+    }
+
+    /// Returns the smallest box that encloses both boxes, aligned with the axes of this box.
+    pub fn merge(&self, other: &OrientedBoundingBox) -> Self {
+        let mut points = self.corners().to_vec();
+        points.extend_from_slice(&other.corners());
+
+        Self::from_points(self.rotation, &points)
+    }
+
+    /// Returns the box transformed by the matrix. The matrix is expected to preserve
+    /// angles, such as a combination of rotations, translations and uniform scales.
+    pub fn transform(&self, m: &DMat4) -> Self {
+        let c = self.center;
+        let center = (m * glm::vec4(c.x, c.y, c.z, 1.0)).xyz();
+        let linear = glm::mat4_to_mat3(m);
+
+        let mut rotation = DMat3::identity();
+        let mut half_extents = DVec3::zeros();
+        for i in 0..3 {
+            let axis = linear * self.rotation.column(i);
+            let scale = axis.norm();
+            rotation.set_column(i, &(axis / scale));
+            half_extents[i] = self.half_extents[i] * scale;
+        }
+
+        OrientedBoundingBox {
+            center,
+            rotation,
+            half_extents,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geobounds::GeoBounds;
+    use crate::geographic::*;
+    use crate::transformations::ecef::ECEF;
+    use crate::volumes::obb::*;
+    use crate::volumes::with_altitudes;
+    use rand::Rng;
+
+    #[test]
+    fn from_points_returns_correct_values() {
+        let points = [DVec3::new(1.0, 2.0, 3.0), DVec3::new(3.0, -2.0, 4.0)];
+        let obb = OrientedBoundingBox::from_points(DMat3::identity(), &points);
+
+        assert_abs_diff_eq!(obb.center(), DVec3::new(2.0, 0.0, 3.5));
+        assert_abs_diff_eq!(obb.half_extents(), DVec3::new(1.0, 2.0, 0.5));
+    }
+
+    #[test]
+    fn from_geo_bounds_is_tighter_than_the_bounding_sphere() {
+        let ecef = ECEF::default();
+        let tile = GeoBounds::new(
+            Geographic::new(45.0, 5.0, 0.0),
+            Geographic::new(46.0, 6.0, 4000.0),
+        );
+
+        let obb = OrientedBoundingBox::from_geo_bounds(&ecef, &tile);
+        let sphere = BoundingSphere::from_geo_bounds(&ecef, &tile);
+
+        // The box is flat: its vertical extent is much smaller than its horizontal ones.
+        assert!(obb.half_extents().z < obb.half_extents().x / 10.0);
+
+        let [x, y, z] = [
+            obb.half_extents().x,
+            obb.half_extents().y,
+            obb.half_extents().z,
+        ];
+        assert!(8.0 * x * y * z < 4.0 / 3.0 * std::f64::consts::PI * sphere.radius().powi(3));
+    }
+
+    #[quickcheck]
+    fn from_geo_bounds_contains_the_bounds(b: GeoBounds, wrap: bool) -> bool {
+        let b = with_altitudes(if wrap {
+            GeoBounds::new(
+                Geographic::new(b.south(), b.east(), 0.0),
+                Geographic::new(b.north(), b.west(), 0.0),
+            )
+        } else {
+            b
+        });
+
+        let ecef = ECEF::default();
+        let obb = OrientedBoundingBox::from_geo_bounds(&ecef, &b);
+        let mut rng = rand::thread_rng();
+
+        (0..100).all(|_| {
+            let p = b.sample(
+                rng.gen_range(0.0, 1.0),
+                rng.gen_range(0.0, 1.0),
+                rng.gen_range(0.0, 1.0),
+            );
+            obb.contains(&ecef.convert_f64(p))
+        })
+    }
+
+    #[quickcheck]
+    fn bounding_sphere_contains_the_corners(b: GeoBounds) -> bool {
+        let obb = OrientedBoundingBox::from_geo_bounds(&ECEF::default(), &with_altitudes(b));
+        let sphere = obb.bounding_sphere();
+        let tolerance = BoundingSphere::new(sphere.center(), sphere.radius() * (1.0 + 1e-12));
+
+        obb.corners().iter().all(|c| tolerance.contains(c))
+    }
+
+    #[quickcheck]
+    fn merge_contains_both_boxes(a: GeoBounds, b: GeoBounds) -> bool {
+        let ecef = ECEF::default();
+        let a = OrientedBoundingBox::from_geo_bounds(&ecef, &with_altitudes(a));
+        let b = OrientedBoundingBox::from_geo_bounds(&ecef, &with_altitudes(b));
+
+        let merged = a.merge(&b);
+        let tolerance = OrientedBoundingBox {
+            half_extents: merged.half_extents().add_scalar(1e-6),
+            ..merged
+        };
+
+        a.corners()
+            .iter()
+            .chain(b.corners().iter())
+            .all(|c| tolerance.contains(c))
+    }
+
+    #[test]
+    fn transform_rotates_the_box() {
+        let obb = OrientedBoundingBox::new(
+            DVec3::new(1.0, 0.0, 0.0),
+            DMat3::identity(),
+            DVec3::new(1.0, 2.0, 3.0),
+        );
+        let m = glm::rotation(std::f64::consts::FRAC_PI_2, &DVec3::new(0.0, 0.0, 1.0))
+            * glm::scaling(&DVec3::new(2.0, 2.0, 2.0));
+
+        let result = obb.transform(&m);
+
+        assert_abs_diff_eq!(result.center(), DVec3::new(0.0, 2.0, 0.0), epsilon = 1e-9);
+        assert_abs_diff_eq!(
+            result.half_extents(),
+            DVec3::new(2.0, 4.0, 6.0),
+            epsilon = 1e-9
+        );
+        assert!(result.contains(&DVec3::new(-3.9, 2.0, 0.0)));
+        assert!(!result.contains(&DVec3::new(0.0, 4.5, 0.0)));
+    }
+}
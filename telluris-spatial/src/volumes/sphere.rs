@@ -0,0 +1,227 @@
+use crate::geobounds::GeoBounds;
+use crate::transformations::SpatialReference;
+use crate::volumes::sample_geo_bounds;
+use glm::{DMat4, DVec3};
+
+/// A sphere enclosing a set of positions.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingSphere {
+    center: DVec3,
+    radius: f64,
+}
+
+impl BoundingSphere {
+    /// Creates a sphere with the specified center and radius.
+    pub fn new(center: DVec3, radius: f64) -> Self {
+        debug_assert!(radius >= 0.0);
+
+        BoundingSphere { center, radius }
+    }
+
+    /// Returns the smallest of two candidate spheres enclosing the points: the sphere
+    /// centered on their axis-aligned bounding box, and the sphere computed by Ritter's
+    /// algorithm. Neither is optimal, but both are within a few percent of the optimal sphere.
+    pub fn from_points(points: &[DVec3]) -> Self {
+        assert!(!points.is_empty(), "cannot bound an empty set of points");
+
+        let min = points.iter().fold(points[0], |a, p| glm::min2(&a, p));
+        let max = points.iter().fold(points[0], |a, p| glm::max2(&a, p));
+        let center = (min + max) / 2.0;
+        let radius = points
+            .iter()
+            .map(|p| glm::distance(p, &center))
+            .fold(0.0, f64::max);
+
+        let ritter = Self::ritter(points);
+
+        if ritter.radius < radius {
+            ritter
+        } else {
+            BoundingSphere { center, radius }
+        }
+    }
+
+    fn ritter(points: &[DVec3]) -> Self {
+        let farthest_from = |origin: &DVec3| {
+            points
+                .iter()
+                .max_by(|a, b| {
+                    glm::distance2(a, origin)
+                        .partial_cmp(&glm::distance2(b, origin))
+                        .unwrap()
+                })
+                .unwrap()
+        };
+
+        let a = farthest_from(&points[0]);
+        let b = farthest_from(a);
+
+        let mut sphere = BoundingSphere {
+            center: (a + b) / 2.0,
+            radius: glm::distance(a, b) / 2.0,
+        };
+
+        for p in points {
+            sphere = sphere.expand(p);
+        }
+
+        sphere
+    }
+
+    /// Returns a sphere enclosing the volume covered by the bounds, from their `floor`
+    /// to their `top` altitudes, in the frame of the spatial reference.
+    pub fn from_geo_bounds<S: SpatialReference + ?Sized>(srs: &S, bounds: &GeoBounds) -> Self {
+        let (positions, margin) = sample_geo_bounds(srs, bounds);
+        let sphere = Self::from_points(&positions);
+
+        BoundingSphere {
+            center: sphere.center,
+            radius: sphere.radius + margin,
+        }
+    }
+
+    /// Returns the center of the sphere.
+    pub fn center(&self) -> DVec3 {
+        self.center
+    }
+
+    /// Returns the radius of the sphere.
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Returns true if the point is inside the sphere, including its surface.
+    pub fn contains(&self, p: &DVec3) -> bool {
+        glm::distance2(&self.center, p) <= self.radius * self.radius
+    }
+
+    /// Returns the smallest sphere that encloses this sphere and the point.
+    pub fn expand(&self, p: &DVec3) -> Self {
+        let distance = glm::distance(&self.center, p);
+
+        if distance <= self.radius {
+            return *self;
+        }
+
+        let radius = (self.radius + distance) / 2.0;
+        let center = self.center + (p - self.center) * ((radius - self.radius) / distance);
+
+        BoundingSphere { center, radius }
+    }
+
+    /// Returns the smallest sphere that encloses both spheres.
+    pub fn merge(&self, other: &BoundingSphere) -> Self {
+        let offset = other.center - self.center;
+        let distance = offset.norm();
+
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+
+        let radius = (self.radius + distance + other.radius) / 2.0;
+        let center = self.center + offset * ((radius - self.radius) / distance);
+
+        BoundingSphere { center, radius }
+    }
+
+    /// Returns the sphere transformed by the matrix. The radius is scaled by the
+    /// largest scale factor of the matrix, so that the result encloses the transformed sphere.
+    pub fn transform(&self, m: &DMat4) -> Self {
+        let center = (m * glm::vec4(self.center.x, self.center.y, self.center.z, 1.0)).xyz();
+        let scale = (0..3).map(|i| m.column(i).xyz().norm()).fold(0.0, f64::max);
+
+        BoundingSphere {
+            center,
+            radius: self.radius * scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ellipsoid::*;
+    use crate::geobounds::GeoBounds;
+    use crate::geographic::*;
+    use crate::transformations::ecef::ECEF;
+    use crate::volumes::sphere::*;
+    use crate::volumes::with_altitudes;
+    use rand::Rng;
+
+    #[test]
+    fn from_geo_bounds_of_the_surface_is_centered_on_the_earth() {
+        let sphere = BoundingSphere::from_geo_bounds(&ECEF::default(), &GeoBounds::surface());
+
+        assert_abs_diff_eq!(sphere.center().norm(), 0.0, epsilon = 1.0);
+        assert!(sphere.radius() >= WGS84_SEMI_MAJOR_AXIS);
+        assert!(sphere.radius() <= WGS84_SEMI_MAJOR_AXIS * 1.01);
+    }
+
+    #[quickcheck]
+    fn from_points_contains_all_points(points: Vec<Geographic>) -> bool {
+        if points.is_empty() {
+            return true;
+        }
+
+        let ecef = ECEF::default();
+        let positions: Vec<DVec3> = points.iter().map(|p| ecef.convert_f64(*p)).collect();
+        let sphere = BoundingSphere::from_points(&positions);
+        let tolerance = BoundingSphere::new(sphere.center(), sphere.radius() * (1.0 + 1e-12));
+
+        positions.iter().all(|p| tolerance.contains(p))
+    }
+
+    #[quickcheck]
+    fn from_geo_bounds_contains_the_bounds(b: GeoBounds, wrap: bool) -> bool {
+        let b = with_altitudes(if wrap {
+            GeoBounds::new(
+                Geographic::new(b.south(), b.east(), 0.0),
+                Geographic::new(b.north(), b.west(), 0.0),
+            )
+        } else {
+            b
+        });
+
+        let ecef = ECEF::default();
+        let sphere = BoundingSphere::from_geo_bounds(&ecef, &b);
+        let mut rng = rand::thread_rng();
+
+        (0..100).all(|_| {
+            let p = b.sample(
+                rng.gen_range(0.0, 1.0),
+                rng.gen_range(0.0, 1.0),
+                rng.gen_range(0.0, 1.0),
+            );
+            sphere.contains(&ecef.convert_f64(p))
+        })
+    }
+
+    #[quickcheck]
+    fn merge_contains_both_spheres(a: Geographic, b: Geographic, ra: u32, rb: u32) -> bool {
+        let ecef = ECEF::default();
+        let a = BoundingSphere::new(ecef.convert_f64(a), f64::from(ra));
+        let b = BoundingSphere::new(ecef.convert_f64(b), f64::from(rb));
+
+        let merged = a.merge(&b);
+        let tolerance = 1e-6;
+
+        glm::distance(&merged.center(), &a.center()) + a.radius() <= merged.radius() + tolerance
+            && glm::distance(&merged.center(), &b.center()) + b.radius()
+                <= merged.radius() + tolerance
+    }
+
+    #[test]
+    fn transform_translates_and_scales() {
+        let sphere = BoundingSphere::new(DVec3::new(1.0, 2.0, 3.0), 2.0);
+        let m = glm::translation(&DVec3::new(10.0, 0.0, 0.0))
+            * glm::scaling(&DVec3::new(1.0, 3.0, 2.0));
+
+        let result = sphere.transform(&m);
+
+        assert_abs_diff_eq!(result.center(), DVec3::new(11.0, 6.0, 6.0));
+        assert_abs_diff_eq!(result.radius(), 6.0);
+    }
+}
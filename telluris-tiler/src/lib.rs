@@ -1,3 +1,4 @@
+pub mod scheme;
 pub mod tile;
 pub mod tiler;
 
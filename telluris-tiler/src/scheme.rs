@@ -0,0 +1,357 @@
+//! Tiling schemes, which define how the surface of the world is divided into tiles
+//! addressed by a column `x`, a row `y` and a level `z`.
+//! At level zero, the scheme is made of a fixed number of root tiles. Each level
+//! divides every tile of the previous level into four tiles of equal angular width,
+//! and of equal height in the space of the scheme. Columns are numbered from the
+//! antimeridian eastward. Rows are numbered from the north (XYZ) or from the south (TMS),
+//! depending on the `YAxis` of the scheme. Sources that share a scheme line up.
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use telluris_spatial::angle::{Degrees, Radians};
+use telluris_spatial::geobounds::GeoBounds;
+use telluris_spatial::geographic::*;
+use telluris_spatial::transformations::webmercator::{WEB_MERCATOR_MAX_LAT, WEB_MERCATOR_MIN_LAT};
+
+/// The deepest level supported by tiling schemes.
+pub const MAX_LEVEL: u32 = 30;
+
+/// The numbering of the rows of a tiling scheme.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum YAxis {
+    /// Rows are numbered from the north, as in the XYZ convention of most web map services.
+    #[default]
+    Xyz,
+    /// Rows are numbered from the south, as in the Tile Map Service specification.
+    Tms,
+}
+
+/// Provides the mapping between tile coordinates and geographic bounds.
+/// Implementors only describe their root tiles and the vertical spacing of their rows.
+pub trait TilingScheme {
+    /// Returns the number of columns and rows of root tiles, at level zero.
+    fn root_tiles(&self) -> (u32, u32);
+
+    /// Returns the numbering of the rows.
+    fn y_axis(&self) -> YAxis;
+
+    /// Returns the position of the latitude between the northern edge (0) and
+    /// the southern edge (1) of the scheme. Latitudes beyond the edges are clamped.
+    fn row_fraction(&self, lat: f64) -> f64;
+
+    /// Returns the latitude at the position between the northern edge (0) and
+    /// the southern edge (1) of the scheme. This is the inverse of `row_fraction`.
+    fn latitude_at(&self, fraction: f64) -> f64;
+
+    /// Returns the number of columns and rows at the level.
+    fn tile_count(&self, z: u32) -> (u32, u32) {
+        assert!(z <= MAX_LEVEL, "level {} is deeper than {}", z, MAX_LEVEL);
+        let (columns, rows) = self.root_tiles();
+
+        (columns << z, rows << z)
+    }
+
+    /// Returns the row of the other numbering: a XYZ row becomes a TMS row, and vice versa.
+    fn flip_y(&self, y: u32, z: u32) -> u32 {
+        let (_, rows) = self.tile_count(z);
+        assert!(y < rows, "row {} is out of range", y);
+
+        rows - 1 - y
+    }
+
+    /// Returns the bounds covered by the scheme, at zero elevation.
+    fn bounds(&self) -> GeoBounds {
+        GeoBounds::new(
+            Geographic::new(self.latitude_at(1.0), MIN_LON, 0.0),
+            Geographic::new(self.latitude_at(0.0), MAX_LON, 0.0),
+        )
+    }
+
+    /// Returns the bounds of the tile, at zero elevation.
+    fn tile_bounds(&self, x: u32, y: u32, z: u32) -> GeoBounds {
+        let (columns, rows) = self.tile_count(z);
+        assert!(x < columns, "column {} is out of range", x);
+        assert!(y < rows, "row {} is out of range", y);
+
+        let row = from_north(self.y_axis(), y, rows);
+        let width = 360.0 / f64::from(columns);
+        let north = self.latitude_at(f64::from(row) / f64::from(rows));
+        let south = self.latitude_at(f64::from(row + 1) / f64::from(rows));
+
+        GeoBounds::new(
+            Geographic::new(south, MIN_LON + f64::from(x) * width, 0.0),
+            Geographic::new(north, MIN_LON + f64::from(x + 1) * width, 0.0),
+        )
+    }
+
+    /// Returns the column and row of the tile that contains the coordinate at the level.
+    /// Coordinates on the edge between two tiles belong to the eastern or southern tile,
+    /// except on the eastern and southern edges of the scheme.
+    fn tile_at(&self, geo: Geographic, z: u32) -> (u32, u32) {
+        let (columns, rows) = self.tile_count(z);
+        let column = first(column_fraction(geo.lon()), columns);
+        let row = first(self.row_fraction(geo.lat()), rows);
+
+        (column, from_north(self.y_axis(), row, rows))
+    }
+
+    /// Returns the column and row of the tiles that intersect the bounds at the level,
+    /// from north to south, then from west to east. Tiles that only touch the bounds
+    /// by an edge are excluded. Bounds that cross the antimeridian are supported.
+    /// The number of tiles grows fourfold at each level.
+    fn tiles_covering(&self, bounds: &GeoBounds, z: u32) -> Vec<(u32, u32)> {
+        let (columns, rows) = self.tile_count(z);
+        let (west, east) = (
+            column_fraction(bounds.west()),
+            column_fraction(bounds.east()),
+        );
+        let column_spans = if bounds.crosses_antimeridian() {
+            vec![span(west, 1.0, columns), span(0.0, east, columns)]
+        } else {
+            vec![span(west, east, columns)]
+        };
+        let (north, south) = span(
+            self.row_fraction(bounds.north()),
+            self.row_fraction(bounds.south()),
+            rows,
+        );
+
+        let mut tiles = Vec::new();
+        for row in north..=south {
+            for &(first, last) in &column_spans {
+                for column in first..=last {
+                    tiles.push((column, from_north(self.y_axis(), row, rows)));
+                }
+            }
+        }
+
+        tiles
+    }
+}
+
+/// Converts a row numbered along the axis into a row numbered from the north, and vice versa.
+fn from_north(y_axis: YAxis, row: u32, rows: u32) -> u32 {
+    match y_axis {
+        YAxis::Xyz => row,
+        YAxis::Tms => rows - 1 - row,
+    }
+}
+
+/// Returns the position of the longitude between the antimeridian (0) and itself (1).
+fn column_fraction(lon: f64) -> f64 {
+    (lon - MIN_LON) / (MAX_LON - MIN_LON)
+}
+
+/// Returns the index of the tile whose interval contains the fraction, among `count` tiles.
+fn first(fraction: f64, count: u32) -> u32 {
+    let index = (fraction * f64::from(count)).floor().max(0.0) as u32;
+
+    index.min(count - 1)
+}
+
+/// Returns the indices of the first and last tiles that intersect the interval between
+/// the fractions, among `count` tiles.
+fn span(start: f64, end: f64, count: u32) -> (u32, u32) {
+    let first = first(start, count);
+    let last = ((end * f64::from(count)).ceil().max(0.0) as u32).min(count);
+
+    (first, last.max(first + 1) - 1)
+}
+
+/// The geographic tiling scheme (EPSG:4326), where tiles have the same angular height
+/// and width. The world is covered by two root tiles: the western and eastern hemispheres.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct GeographicTilingScheme {
+    y_axis: YAxis,
+}
+
+impl GeographicTilingScheme {
+    /// Creates a geographic scheme, with the specified row numbering.
+    pub fn new(y_axis: YAxis) -> Self {
+        GeographicTilingScheme { y_axis }
+    }
+}
+
+impl TilingScheme for GeographicTilingScheme {
+    fn root_tiles(&self) -> (u32, u32) {
+        (2, 1)
+    }
+
+    fn y_axis(&self) -> YAxis {
+        self.y_axis
+    }
+
+    fn row_fraction(&self, lat: f64) -> f64 {
+        ((MAX_LAT - lat) / (MAX_LAT - MIN_LAT)).clamp(0.0, 1.0)
+    }
+
+    fn latitude_at(&self, fraction: f64) -> f64 {
+        MAX_LAT - fraction * (MAX_LAT - MIN_LAT)
+    }
+}
+
+/// The Web Mercator tiling scheme (EPSG:3857), used by most web map services, where
+/// tiles are square in the projected plane. The world is covered by a single root tile,
+/// between the latitudes `WEB_MERCATOR_MIN_LAT` and `WEB_MERCATOR_MAX_LAT`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct WebMercatorTilingScheme {
+    y_axis: YAxis,
+}
+
+impl WebMercatorTilingScheme {
+    /// Creates a Web Mercator scheme, with the specified row numbering.
+    pub fn new(y_axis: YAxis) -> Self {
+        WebMercatorTilingScheme { y_axis }
+    }
+}
+
+impl TilingScheme for WebMercatorTilingScheme {
+    fn root_tiles(&self) -> (u32, u32) {
+        (1, 1)
+    }
+
+    fn y_axis(&self) -> YAxis {
+        self.y_axis
+    }
+
+    fn row_fraction(&self, lat: f64) -> f64 {
+        let lat = Degrees(lat.clamp(WEB_MERCATOR_MIN_LAT, WEB_MERCATOR_MAX_LAT));
+        let y = (FRAC_PI_4 + lat.to_radians().value() / 2.0).tan().ln();
+
+        ((1.0 - y / PI) / 2.0).clamp(0.0, 1.0)
+    }
+
+    fn latitude_at(&self, fraction: f64) -> f64 {
+        let y = PI * (1.0 - 2.0 * fraction);
+        let lat = Radians(2.0 * y.exp().atan() - FRAC_PI_2).to_degrees();
+
+        lat.value()
+            .clamp(WEB_MERCATOR_MIN_LAT, WEB_MERCATOR_MAX_LAT)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scheme::*;
+    use quickcheck::{Arbitrary, Gen};
+    use rand::Rng;
+
+    /// The address of an existing tile, in either scheme.
+    #[derive(Debug, Clone, Copy)]
+    struct Address {
+        geographic: bool,
+        y_axis: YAxis,
+        x: u32,
+        y: u32,
+        z: u32,
+    }
+
+    impl Address {
+        fn scheme(&self) -> Box<dyn TilingScheme> {
+            if self.geographic {
+                Box::new(GeographicTilingScheme::new(self.y_axis))
+            } else {
+                Box::new(WebMercatorTilingScheme::new(self.y_axis))
+            }
+        }
+    }
+
+    impl Arbitrary for Address {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let geographic = g.gen();
+            let y_axis = if g.gen() { YAxis::Xyz } else { YAxis::Tms };
+            let z = g.gen_range(0, 24);
+            let columns = if geographic { 2 << z } else { 1 << z };
+
+            Address {
+                geographic,
+                y_axis,
+                x: g.gen_range(0, columns),
+                y: g.gen_range(0, 1 << z),
+                z,
+            }
+        }
+    }
+
+    #[test]
+    fn geographic_roots_are_the_hemispheres() {
+        let scheme = GeographicTilingScheme::default();
+        let west = scheme.tile_bounds(0, 0, 0);
+        let east = scheme.tile_bounds(1, 0, 0);
+
+        assert_eq!(scheme.tile_count(0), (2, 1));
+        assert_eq!(scheme.tile_count(3), (16, 8));
+        assert_eq!((west.west(), west.east()), (MIN_LON, 0.0));
+        assert_eq!((east.west(), east.east()), (0.0, MAX_LON));
+        assert_eq!((west.south(), west.north()), (MIN_LAT, MAX_LAT));
+        assert_eq!(scheme.bounds(), GeoBounds::surface());
+    }
+
+    #[test]
+    fn web_mercator_tiles_match_the_xyz_convention() {
+        let xyz = WebMercatorTilingScheme::new(YAxis::Xyz);
+        let tms = WebMercatorTilingScheme::new(YAxis::Tms);
+        let paris = Geographic::new(48.8566, 2.3522, 0.0);
+        let north_west = xyz.tile_bounds(0, 0, 1);
+
+        assert_eq!(xyz.tile_at(paris, 10), (518, 352));
+        assert_eq!(tms.tile_at(paris, 10), (518, 671));
+        assert_eq!(xyz.flip_y(352, 10), 671);
+        assert_eq!(xyz.tile_bounds(0, 0, 1), tms.tile_bounds(0, 1, 1));
+        assert!((north_west.north() - WEB_MERCATOR_MAX_LAT).abs() < 1e-9);
+        assert!(north_west.south().abs() < 1e-9);
+        assert_eq!((north_west.west(), north_west.east()), (MIN_LON, 0.0));
+    }
+
+    #[test]
+    fn tiles_covering_splits_bounds_on_the_antimeridian() {
+        let scheme = GeographicTilingScheme::default();
+        let bounds = GeoBounds::new(
+            Geographic::new(-10.0, 170.0, 0.0),
+            Geographic::new(10.0, -170.0, 0.0),
+        );
+
+        assert_eq!(
+            scheme.tiles_covering(&bounds, 2),
+            vec![(7, 1), (0, 1), (7, 2), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn tiles_covering_excludes_tiles_touching_an_edge() {
+        let scheme = GeographicTilingScheme::default();
+        let bounds = GeoBounds::new(
+            Geographic::new(0.0, 0.0, 0.0),
+            Geographic::new(45.0, 45.0, 0.0),
+        );
+        let point = GeoBounds::new(
+            Geographic::new(0.0, 0.0, 0.0),
+            Geographic::new(0.0, 0.0, 0.0),
+        );
+
+        assert_eq!(scheme.tiles_covering(&bounds, 2), vec![(4, 1)]);
+        assert_eq!(scheme.tiles_covering(&point, 2), vec![(4, 2)]);
+        assert_eq!(scheme.tiles_covering(&GeoBounds::world(), 1).len(), 8);
+    }
+
+    #[quickcheck]
+    fn tile_at_returns_the_tile_containing_the_coordinate(a: Address) -> bool {
+        let scheme = a.scheme();
+
+        scheme.tile_at(scheme.tile_bounds(a.x, a.y, a.z).center(), a.z) == (a.x, a.y)
+    }
+
+    #[quickcheck]
+    fn tiles_covering_a_tile_include_the_tile(a: Address) -> bool {
+        let scheme = a.scheme();
+        let bounds = scheme.tile_bounds(a.x, a.y, a.z);
+
+        scheme.tiles_covering(&bounds, a.z).contains(&(a.x, a.y))
+    }
+
+    #[quickcheck]
+    fn flip_y_is_an_involution(a: Address) -> bool {
+        let scheme = a.scheme();
+
+        scheme.flip_y(scheme.flip_y(a.y, a.z), a.z) == a.y
+    }
+}
@@ -1,12 +1,16 @@
 use specs::{Component, HashMapStorage};
 use telluris_spatial::geobounds::GeoBounds;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 /// The Tile is the fundamental spatial element, serving as the entry point
 /// of the rendering pipeline.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Tile {
     bounds: GeoBounds,
 }
@@ -43,4 +47,12 @@ mod test {
         let t = Tile::new(b);
         t.bounds() == b
     }
+
+    #[cfg(feature = "serde")]
+    #[quickcheck]
+    fn deserialize_is_the_inverse_of_serialize(t: Tile) -> bool {
+        let json = serde_json::to_string(&t).unwrap();
+
+        serde_json::from_str::<Tile>(&json).unwrap().bounds() == t.bounds()
+    }
 }